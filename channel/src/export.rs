@@ -0,0 +1,305 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::rc::Rc;
+
+use cid::Cid;
+
+use components::toast::Toaster;
+
+use gloo_console::error;
+
+#[cfg(debug_assertions)]
+use gloo_console::info;
+
+use linked_data::types::{IPLDLink, IPNSAddress};
+
+use utils::{
+    client::{ClientError, IpfsClient},
+    ipfs::IPFSContext,
+    syndication::{self, FeedInfo},
+};
+
+use ybc::{Button, Buttons};
+
+use yew::{platform::spawn_local, prelude::*};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    Atom,
+    JsonFeed,
+}
+
+impl Format {
+    fn file_name(&self) -> &'static str {
+        match self {
+            Self::Atom => "feed.atom",
+            Self::JsonFeed => "feed.json",
+        }
+    }
+
+    fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Atom => "application/atom+xml",
+            Self::JsonFeed => "application/feed+json",
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// Channel Address
+    pub addr: IPNSAddress,
+
+    /// Channel name
+    pub name: String,
+
+    /// Channel content index
+    pub index: IPLDLink,
+}
+
+/// Export channel content as Atom or JSON Feed documents.
+pub struct ExportFeed {
+    ipfs: Option<Rc<dyn IpfsClient>>,
+    read_only: bool,
+    toaster: Toaster,
+
+    loading: bool,
+
+    /// CIDs of the Atom and JSON Feed documents added to IPFS.
+    added: Option<(Cid, Cid)>,
+}
+
+pub enum Msg {
+    Download(Format),
+    Add,
+    Added((Cid, Cid)),
+    Done,
+}
+
+impl Component for ExportFeed {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        #[cfg(debug_assertions)]
+        info!("Export Feed Create");
+
+        let (ipfs, read_only) = match ctx.link().context::<IPFSContext>(Callback::noop()) {
+            Some((context, _)) => (Some(context.api.clone()), context.is_read_only()),
+            None => (None, true),
+        };
+
+        let toaster = ctx
+            .link()
+            .context::<Toaster>(Callback::noop())
+            .map(|(toaster, _)| toaster)
+            .unwrap_or_default();
+
+        Self {
+            ipfs,
+            read_only,
+            toaster,
+
+            loading: false,
+            added: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        #[cfg(debug_assertions)]
+        info!("Export Feed Update");
+
+        match msg {
+            Msg::Download(format) => {
+                let ipfs = match self.ipfs.clone() {
+                    Some(ipfs) => ipfs,
+                    None => return false,
+                };
+
+                spawn_local(download(
+                    ipfs,
+                    ctx.props().index,
+                    feed_info(ctx.props()),
+                    format,
+                    self.toaster.clone(),
+                    ctx.link().callback(|_| Msg::Done),
+                ));
+
+                self.loading = true;
+
+                true
+            }
+            Msg::Add => {
+                let ipfs = match self.ipfs.clone() {
+                    Some(ipfs) => ipfs,
+                    None => return false,
+                };
+
+                spawn_local(add_to_ipfs(
+                    ipfs,
+                    ctx.props().index,
+                    feed_info(ctx.props()),
+                    self.toaster.clone(),
+                    ctx.link().callback(Msg::Added),
+                    ctx.link().callback(|_| Msg::Done),
+                ));
+
+                self.loading = true;
+
+                true
+            }
+            Msg::Added(cids) => {
+                self.added = Some(cids);
+                self.loading = false;
+
+                true
+            }
+            Msg::Done => {
+                self.loading = false;
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        #[cfg(debug_assertions)]
+        info!("Export Feed View");
+
+        let disabled = self.ipfs.is_none() || self.loading;
+
+        html! {
+        <>
+            <Buttons classes={classes!("are-small")} >
+                <Button {disabled} onclick={ctx.link().callback(|_| Msg::Download(Format::Atom))} >
+                    <span class="icon-text">
+                        <span class="icon"><i class="fa-solid fa-rss"></i></span>
+                        <span>{"Atom"}</span>
+                    </span>
+                </Button>
+                <Button {disabled} onclick={ctx.link().callback(|_| Msg::Download(Format::JsonFeed))} >
+                    <span class="icon-text">
+                        <span class="icon"><i class="fa-solid fa-rss"></i></span>
+                        <span>{"JSON Feed"}</span>
+                    </span>
+                </Button>
+                if !self.read_only {
+                <Button classes={classes!(self.loading.then_some("is-loading"))} {disabled} onclick={ctx.link().callback(|_| Msg::Add)} >
+                    {"Add to IPFS"}
+                </Button>
+                }
+            </Buttons>
+            if let Some((atom, json)) = self.added {
+                { self.render_added(atom, json) }
+            }
+        </>
+        }
+    }
+}
+
+impl ExportFeed {
+    fn render_added(&self, atom: Cid, json: Cid) -> Html {
+        let gateway = utils::ipfs::get_gateway_addr();
+
+        html! {
+        <p>
+            <small>
+                <a href={format!("{}/ipfs/{}", gateway, atom)} target="_blank" rel="noopener noreferrer" >
+                    { format!("ipfs://{}", atom) }
+                </a>
+                <br/>
+                <a href={format!("{}/ipfs/{}", gateway, json)} target="_blank" rel="noopener noreferrer" >
+                    { format!("ipfs://{}", json) }
+                </a>
+            </small>
+        </p>
+        }
+    }
+}
+
+fn feed_info(props: &Props) -> FeedInfo {
+    let location = web_sys::window().unwrap().location();
+
+    let base_url = format!(
+        "{}{}",
+        location.origin().unwrap_or_default(),
+        location.pathname().unwrap_or_default()
+    );
+
+    FeedInfo {
+        title: props.name.clone(),
+        base_url,
+        gateway: utils::ipfs::get_gateway_addr(),
+        channel_path: format!("#/channel/{}", props.addr),
+    }
+}
+
+async fn serialize(
+    ipfs: Rc<dyn IpfsClient>,
+    index: IPLDLink,
+    info: &FeedInfo,
+) -> Result<(String, String), ClientError> {
+    let entries = syndication::collect_entries(ipfs, index).await?;
+
+    let atom = syndication::to_atom(info, &entries);
+    let json = syndication::to_json_feed(info, &entries);
+
+    Ok((atom, json))
+}
+
+async fn download(
+    ipfs: Rc<dyn IpfsClient>,
+    index: IPLDLink,
+    info: FeedInfo,
+    format: Format,
+    toaster: Toaster,
+    callback: Callback<()>,
+) {
+    match serialize(ipfs, index, &info).await {
+        Ok((atom, json)) => {
+            let document = match format {
+                Format::Atom => atom,
+                Format::JsonFeed => json,
+            };
+
+            if let Err(e) = utils::save_file(&document, format.file_name(), format.mime_type()) {
+                error!(&format!("{:#?}", e));
+            }
+        }
+        Err(e) => toaster.error_with("Feed export failed.", e.into(), None),
+    }
+
+    callback.emit(());
+}
+
+async fn add_to_ipfs(
+    ipfs: Rc<dyn IpfsClient>,
+    index: IPLDLink,
+    info: FeedInfo,
+    toaster: Toaster,
+    added_cb: Callback<(Cid, Cid)>,
+    done_cb: Callback<()>,
+) {
+    let result = async {
+        let (atom, json) = serialize(ipfs.clone(), index, &info).await?;
+
+        let atom = ipfs.add(atom.into_bytes()).await?;
+        let json = ipfs.add(json.into_bytes()).await?;
+
+        Ok::<_, ClientError>((atom, json))
+    }
+    .await;
+
+    match result {
+        Ok(cids) => {
+            toaster.success("Feeds added to IPFS.");
+
+            added_cb.emit(cids);
+        }
+        Err(e) => {
+            toaster.error_with("Adding feeds to IPFS failed.", e.into(), None);
+
+            done_cb.emit(());
+        }
+    }
+}
@@ -1,16 +1,19 @@
 #![cfg(target_arch = "wasm32")]
 
+mod export;
+mod lists;
 mod manage_content;
 
-use defluencer::Defluencer;
-
-use ipfs_api::IpfsService;
-
+use export::ExportFeed;
+use lists::SubscriptionLists;
 use manage_content::ManageContent;
 
 use linked_data::{identity::Identity, types::IPNSAddress};
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    rc::Rc,
+};
 
 use components::{
     pure::{DagExplorer, Followee, IPFSImage, NavigationBar, Searching, Thumbnail},
@@ -25,8 +28,11 @@ use gloo_console::info;
 use linked_data::{channel::ChannelMetadata, media::Media};
 
 use utils::{
+    client::IpfsClient,
     defluencer::{ChannelContext, UserContext},
+    identities::IdentityStore,
     ipfs::IPFSContext,
+    mute::MuteList,
 };
 
 use ybc::{
@@ -60,11 +66,12 @@ pub struct ChannelPage {
     metadata: Option<ChannelMetadata>,
     update_cb: Callback<(IPNSAddress, Cid, ChannelMetadata)>,
 
-    content: VecDeque<(Cid, Media)>,
-    content_cb: Callback<(Cid, Media)>,
+    /// Media and whether it is signed by its identity wallet.
+    content: VecDeque<(Cid, Media, bool)>,
+    content_cb: Callback<(Cid, Media, bool)>,
 
     identity_cb: Callback<(Cid, Identity)>,
-    identities: HashMap<Cid, Identity>,
+    identities: IdentityStore,
 
     /// Is the user this channel's owner?
     channel_owner: bool,
@@ -72,8 +79,14 @@ pub struct ChannelPage {
     subscribe_cb: Callback<MouseEvent>,
     subscription: bool,
 
+    /// Opted in new content notifications.
+    notify: bool,
+
     filter: Filter,
 
+    /// Muted content is not displayed.
+    mutes: MuteList,
+
     followees: HashMap<Cid, Identity>,
     followees_cb: Callback<HashMap<Cid, Identity>>,
 }
@@ -89,9 +102,11 @@ pub enum Filter {
 
 pub enum Msg {
     Update((IPNSAddress, Cid, ChannelMetadata)),
-    Content((Cid, Media)),
+    Content((Cid, Media, bool)),
     Identity((Cid, Identity)),
     Subscribe,
+    Lists,
+    Notify,
     Filter(Filter),
     Followees(HashMap<Cid, Identity>),
 }
@@ -107,6 +122,10 @@ impl Component for ChannelPage {
         let update_cb = ctx.link().callback(Msg::Update);
         let content_cb = ctx.link().callback(Msg::Content);
         let identity_cb = ctx.link().callback(Msg::Identity);
+        let identities = match ctx.link().context::<IdentityStore>(Callback::noop()) {
+            Some((store, _)) => store,
+            None => IdentityStore::new(),
+        };
         let subscribe_cb = ctx.link().callback(|_| Msg::Subscribe);
         let followees_cb = ctx.link().callback(Msg::Followees);
 
@@ -116,13 +135,13 @@ impl Component for ChannelPage {
 
         if let Some((context, _)) = ctx.link().context::<IPFSContext>(Callback::noop()) {
             spawn_local(utils::r#async::get_channels(
-                context.client.clone(),
+                context.api.clone(),
                 update_cb.clone(),
                 HashSet::from([addr]),
             ));
 
             spawn_local(utils::r#async::channel_subscribe(
-                context.client.clone(),
+                context.api.clone(),
                 update_cb.clone(),
                 addr,
                 regis,
@@ -161,7 +180,7 @@ impl Component for ChannelPage {
             content: Default::default(),
             content_cb,
 
-            identities: Default::default(),
+            identities,
             identity_cb,
 
             channel_owner: own_channel,
@@ -169,8 +188,14 @@ impl Component for ChannelPage {
             subscribe_cb,
             subscription: following,
 
+            notify: utils::notifications::get_settings()
+                .channels
+                .contains(&addr),
+
             filter: Filter::None,
 
+            mutes: MuteList::load(),
+
             followees: Default::default(),
             followees_cb,
         }
@@ -182,9 +207,27 @@ impl Component for ChannelPage {
 
         match msg {
             Msg::Update((_, _, metadata)) => self.on_channel_update(ctx, metadata),
-            Msg::Content((cid, media)) => self.on_content_discovered(ctx, cid, media),
-            Msg::Identity((cid, identity)) => self.identities.insert(cid, identity).is_none(),
+            Msg::Content((cid, media, verified)) => {
+                self.on_content_discovered(ctx, cid, media, verified)
+            }
+            Msg::Identity(_) => true,
             Msg::Subscribe => self.on_subscribe(ctx),
+            Msg::Notify => {
+                self.notify = utils::notifications::toggle_channel(self.addr);
+
+                if self.notify && !utils::notifications::is_granted() {
+                    spawn_local(async {
+                        utils::notifications::request_permission().await;
+                    });
+                }
+
+                true
+            }
+            Msg::Lists => {
+                self.subscription = utils::subscriptions::get_sub_list().contains(&self.addr);
+
+                true
+            }
             Msg::Filter(filter) => self.on_filtering(filter),
             Msg::Followees(followees) => self.on_followees(followees),
         }
@@ -195,7 +238,7 @@ impl Component for ChannelPage {
         info!("Channel Page Changed");
 
         let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.client,
+            Some((context, _)) => context.api,
             None => return false,
         };
 
@@ -235,6 +278,9 @@ impl Component for ChannelPage {
             }
 
             self.subscription = utils::subscriptions::get_sub_list().contains(&self.addr);
+            self.notify = utils::notifications::get_settings()
+                .channels
+                .contains(&self.addr);
 
             return true;
         }
@@ -299,6 +345,18 @@ impl ChannelPage {
                                 }
                                 </Button>
                             </LevelItem>
+                            if self.subscription {
+                            <LevelItem>
+                                <Button classes={classes!("is-small", "is-rounded")} onclick={ctx.link().callback(|_| Msg::Notify)} >
+                                    <span class="icon is-small">
+                                        <i class={ if self.notify {"fa-solid fa-bell"} else {"fa-regular fa-bell-slash"} }></i>
+                                    </span>
+                                </Button>
+                            </LevelItem>
+                            }
+                            <LevelItem>
+                                <SubscriptionLists key={ctx.props().addr.to_string()} addr={ctx.props().addr} onchange={ctx.link().callback(|_| Msg::Lists)} />
+                            </LevelItem>
                             if let Some(live) = meta.live {
                             <LevelItem>
                                 <ButtonRouter<Route> classes={classes!("is-small", "is-rounded")} route={Route::Live{ cid: live.link }} >
@@ -308,6 +366,11 @@ impl ChannelPage {
                             }
                         </LevelLeft>
                         <LevelRight>
+                        if let Some(index) = meta.content_index {
+                            <LevelItem>
+                                <ExportFeed addr={ctx.props().addr} name={identity.name.clone()} {index} />
+                            </LevelItem>
+                        }
                         if let Some(addr) = identity.ipns_addr {
                             <LevelItem>
                                 <small>{format!("ipns://{}", addr)}</small>
@@ -395,6 +458,7 @@ impl ChannelPage {
             return self
                 .followees
                 .iter()
+                .filter(|(cid, identity)| !self.mutes.mutes_identity(cid, Some(identity)))
                 .map(|(cid, identity)| {
                     let cid = *cid;
                     let identity = identity.clone();
@@ -408,13 +472,21 @@ impl ChannelPage {
                 .collect::<Html>();
         }
 
+        if self.mutes.mutes_channel(&self.addr) {
+            return html! {
+                <Block>
+                    <p class="has-text-centered">{"You muted this channel. Manage your mute list in the settings."}</p>
+                </Block>
+            };
+        }
+
         if self.content.is_empty() {
             return html! {<Searching />};
         }
 
         self.content
             .iter()
-            .filter_map(|(cid, media)| {
+            .filter_map(|(cid, media, verified)| {
                 if self.filter != Filter::None {
                     match media {
                         Media::Blog(_) => {
@@ -437,26 +509,29 @@ impl ChannelPage {
 
                 let cid = *cid;
                 let media = media.clone();
+                let verified = *verified;
 
                 let identity = match self.identities.get(&media.identity().link) {
-                    Some(id) => id.clone(),
+                    Some(id) => id,
                     None => return None,
                 };
 
-                let mut shared_by = None;
+                if self.mutes.mutes_media(&media, Some(&identity)) {
+                    return None;
+                }
+
+                let mut shared_by = Vec::new();
 
+                // Content signed by someone else was shared by this channel
                 if let Some(channel) = &self.metadata {
                     if channel.identity != media.identity() {
-                        shared_by = match self.identities.get(&channel.identity.link) {
-                            Some(id) => Some(id.clone()),
-                            None => None,
-                        };
+                        shared_by.extend(self.identities.get(&channel.identity.link));
                     }
                 }
 
                 return Some(html! {
                 <Block>
-                    <Thumbnail key={cid.to_string()} {cid} {media} {identity} {shared_by} />
+                    <Thumbnail key={cid.to_string()} {cid} {media} {identity} {shared_by} {verified} />
                 </Block>
                 });
             })
@@ -478,7 +553,7 @@ impl ChannelPage {
 
     fn on_channel_update(&mut self, ctx: &Context<Self>, metadata: ChannelMetadata) -> bool {
         let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.client,
+            Some((context, _)) => context.api,
             None => return false,
         };
 
@@ -494,12 +569,12 @@ impl ChannelPage {
             ));
         }
 
-        if !self.identities.contains_key(&metadata.identity.link) {
-            spawn_local(utils::r#async::dag_get(
+        if !self.identities.contains(&metadata.identity.link) {
+            self.identities.request(
                 ipfs.clone(),
                 metadata.identity.link,
                 self.identity_cb.clone(),
-            ));
+            );
         }
 
         if let Some(idx) = metadata.content_index {
@@ -524,21 +599,24 @@ impl ChannelPage {
         true
     }
 
-    fn on_content_discovered(&mut self, ctx: &Context<Self>, cid: Cid, media: Media) -> bool {
+    fn on_content_discovered(
+        &mut self,
+        ctx: &Context<Self>,
+        cid: Cid,
+        media: Media,
+        verified: bool,
+    ) -> bool {
         let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.client,
+            Some((context, _)) => context.api,
             None => return false,
         };
 
-        if !self.identities.contains_key(&media.identity().link) {
-            spawn_local(utils::r#async::dag_get(
-                ipfs,
-                media.identity().link,
-                self.identity_cb.clone(),
-            ));
+        if !self.identities.contains(&media.identity().link) {
+            self.identities
+                .request(ipfs, media.identity().link, self.identity_cb.clone());
         }
 
-        self.content.push_back((cid, media));
+        self.content.push_back((cid, media, verified));
 
         true
     }
@@ -579,15 +657,11 @@ impl ChannelPage {
 }
 
 async fn get_followees(
-    ipfs: IpfsService,
+    ipfs: Rc<dyn IpfsClient>,
     metadata: ChannelMetadata,
     callback: Callback<HashMap<Cid, Identity>>,
 ) {
-    let defluencer = Defluencer::from(ipfs);
-
-    let hash_map = defluencer
-        .followees_identity(std::iter::once(&metadata))
-        .await;
+    let hash_map = utils::r#async::followees_identity(&*ipfs, &metadata).await;
 
     callback.emit(hash_map);
 }
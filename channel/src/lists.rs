@@ -0,0 +1,171 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::collections::{BTreeMap, HashSet};
+
+#[cfg(debug_assertions)]
+use gloo_console::info;
+
+use linked_data::types::IPNSAddress;
+
+use utils::subscriptions;
+
+use ybc::{Button, Control, Field, Input};
+
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// Channel Address
+    pub addr: IPNSAddress,
+
+    /// Emitted when lists are modified.
+    pub onchange: Callback<()>,
+}
+
+/// Dropdown to add a channel to named subscription lists.
+pub struct SubscriptionLists {
+    open: bool,
+
+    lists: BTreeMap<String, HashSet<IPNSAddress>>,
+
+    name: String,
+    name_cb: Callback<String>,
+}
+
+pub enum Msg {
+    Open,
+    Toggle(String),
+    Name(String),
+    Create,
+}
+
+impl Component for SubscriptionLists {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        #[cfg(debug_assertions)]
+        info!("Subscription Lists Create");
+
+        Self {
+            open: false,
+            lists: BTreeMap::default(),
+            name: String::new(),
+            name_cb: ctx.link().callback(Msg::Name),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        #[cfg(debug_assertions)]
+        info!("Subscription Lists Update");
+
+        match msg {
+            Msg::Open => {
+                self.open = !self.open;
+
+                // Subscribing also changes the lists
+                if self.open {
+                    self.lists = subscriptions::get_lists();
+                }
+
+                true
+            }
+            Msg::Toggle(name) => {
+                subscriptions::toggle_in_list(&name, ctx.props().addr);
+
+                self.lists = subscriptions::get_lists();
+
+                ctx.props().onchange.emit(());
+
+                true
+            }
+            Msg::Name(name) => {
+                self.name = name;
+
+                true
+            }
+            Msg::Create => {
+                let name = self.name.trim().to_owned();
+
+                if name.is_empty() {
+                    return false;
+                }
+
+                if subscriptions::create_list(&name) {
+                    subscriptions::toggle_in_list(&name, ctx.props().addr);
+
+                    ctx.props().onchange.emit(());
+                }
+
+                self.lists = subscriptions::get_lists();
+                self.name.clear();
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        #[cfg(debug_assertions)]
+        info!("Subscription Lists View");
+
+        let addr = ctx.props().addr;
+
+        let items = self
+            .lists
+            .iter()
+            .map(|(name, list)| {
+                let icon = if list.contains(&addr) {
+                    "fa-solid fa-square-check"
+                } else {
+                    "fa-regular fa-square"
+                };
+
+                let onclick = {
+                    let name = name.clone();
+                    ctx.link().callback(move |_| Msg::Toggle(name.clone()))
+                };
+
+                html! {
+                <a key={name.clone()} class="dropdown-item" {onclick} >
+                    <span class="icon-text">
+                        <span class="icon"><i class={icon}></i></span>
+                        <span>{ name }</span>
+                    </span>
+                </a>
+                }
+            })
+            .collect::<Html>();
+
+        html! {
+        <div class={classes!("dropdown", self.open.then_some("is-active"))} >
+            <div class="dropdown-trigger">
+                <Button classes={classes!("is-small", "is-rounded")} onclick={ctx.link().callback(|_| Msg::Open)} >
+                    <span class="icon-text">
+                        <span>{"Lists"}</span>
+                        <span class="icon"><i class="fas fa-angle-down"></i></span>
+                    </span>
+                </Button>
+            </div>
+            <div class="dropdown-menu">
+                <div class="dropdown-content">
+                    { items }
+                    <hr class="dropdown-divider" />
+                    <div class="dropdown-item">
+                        <Field addons=true >
+                            <Control expanded=true >
+                                <Input classes={classes!("is-small")} name="list_name" value={self.name.clone()} update={self.name_cb.clone()} placeholder="New list" />
+                            </Control>
+                            <Control>
+                                <Button classes={classes!("is-small")} disabled={self.name.trim().is_empty()} onclick={ctx.link().callback(|_| Msg::Create)} >
+                                    {"Add"}
+                                </Button>
+                            </Control>
+                        </Field>
+                    </div>
+                </div>
+            </div>
+        </div>
+        }
+    }
+}
@@ -1,26 +1,25 @@
 #![cfg(target_arch = "wasm32")]
 
-use std::collections::HashSet;
+use std::{collections::HashSet, rc::Rc};
 
 use cid::Cid;
 
-use defluencer::{
-    channel::{local::LocalUpdater, Channel},
-    crypto::signers::MetamaskSigner,
-    user::User,
-};
+use defluencer::{crypto::signers::MetamaskSigner, user::User};
+
+use components::toast::Toaster;
 
 use futures_util::{stream::FuturesUnordered, StreamExt};
 
 use gloo_console::error;
 
-use ipfs_api::IpfsService;
-
 use linked_data::types::IPNSAddress;
 
 use utils::{
+    client::IpfsClient,
     defluencer::{ChannelContext, UserContext},
+    errors::AppError,
     ipfs::IPFSContext,
+    outbox::{self, Operation},
 };
 
 use ybc::{Button, Buttons, Control, Field, File, Input, Tag, Tags, TextArea};
@@ -36,6 +35,8 @@ pub struct Props {
 }
 
 pub struct ManageContent {
+    toaster: Toaster,
+
     video_modal_cb: Callback<MouseEvent>,
     post_modal_cb: Callback<MouseEvent>,
     article_modal_cb: Callback<MouseEvent>,
@@ -96,6 +97,7 @@ pub enum Msg {
     Tag(String),
     RemoveTag(String),
     Result(Cid),
+    Error(AppError),
 }
 
 impl Component for ManageContent {
@@ -119,7 +121,15 @@ impl Component for ManageContent {
         let word_cb = ctx.link().callback(Msg::WordCount);
         let tag_cb = ctx.link().callback(Msg::Tag);
 
+        let toaster = ctx
+            .link()
+            .context::<Toaster>(Callback::noop())
+            .map(|(toaster, _)| toaster)
+            .unwrap_or_default();
+
         Self {
+            toaster,
+
             video_modal_cb,
             post_modal_cb,
             article_modal_cb,
@@ -171,6 +181,7 @@ impl Component for ManageContent {
             Msg::Tag(tag) => self.on_tag(tag),
             Msg::RemoveTag(tag) => self.tags.remove(&tag),
             Msg::Result(_) => self.on_result(),
+            Msg::Error(e) => self.on_error(ctx, e),
         }
     }
 
@@ -361,7 +372,7 @@ impl ManageContent {
 
     fn on_manage(&mut self, ctx: &Context<Self>) -> bool {
         let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.client,
+            Some((context, _)) => context.api,
             None => return false,
         };
 
@@ -371,31 +382,38 @@ impl ManageContent {
         };
 
         let channel = match ctx.link().context::<ChannelContext>(Callback::noop()) {
-            Some((context, _)) => context.channel,
+            Some((context, _)) => context,
             None => return false,
         };
 
+        let toaster = self.toaster.clone();
+        let callback = ctx.link().callback(Msg::Result);
+        let error_cb = ctx.link().callback(Msg::Error);
+
+        // Form data is kept until success so that failed posts can be retried
         match self.modal {
-            Modals::MicroPost => {
-                spawn_local(create_micro_post(
-                    ipfs,
-                    user,
-                    channel,
-                    self.title.clone(),
-                    self.tags.clone(),
-                    ctx.link().callback(Msg::Result),
-                ));
-            }
+            Modals::MicroPost => spawn_local(create_micro_post(
+                ipfs,
+                user,
+                channel,
+                self.title.clone(),
+                self.tags.clone(),
+                toaster,
+                callback,
+                error_cb,
+            )),
             Modals::Article => spawn_local(create_article(
                 ipfs,
                 user,
                 channel,
                 self.title.clone(),
-                self.images.pop(),
-                self.markdowns.pop(),
+                self.images.last().cloned(),
+                self.markdowns.last().cloned(),
                 self.word_count,
                 self.tags.clone(),
-                ctx.link().callback(Msg::Result),
+                toaster,
+                callback,
+                error_cb,
             )),
             Modals::Video => spawn_local(create_video_post(
                 ipfs,
@@ -403,26 +421,27 @@ impl ManageContent {
                 channel,
                 self.title.clone(),
                 self.form_cid,
-                self.images.pop(),
+                self.images.last().cloned(),
                 self.tags.clone(),
-                ctx.link().callback(Msg::Result),
+                toaster,
+                callback,
+                error_cb,
             )),
             Modals::Remove => match IPNSAddress::try_from(self.form_cid) {
-                Ok(addr) => spawn_local(remove_follow(
-                    channel,
-                    addr,
-                    ctx.link().callback(Msg::Result),
-                )),
+                Ok(addr) => spawn_local(remove_follow(channel, addr, toaster, callback)),
                 Err(_) => spawn_local(remove_content(
                     channel,
                     self.form_cid,
-                    ctx.link().callback(Msg::Result),
+                    toaster,
+                    callback,
+                    error_cb,
                 )),
             },
             Modals::Follow => spawn_local(add_follow(
                 channel,
                 self.form_ipns.clone(),
-                ctx.link().callback(Msg::Result),
+                toaster,
+                callback,
             )),
             Modals::None => return false,
         }
@@ -525,6 +544,17 @@ impl ManageContent {
         true
     }
 
+    /// The modal stays open with it's data, ready to retry.
+    fn on_error(&mut self, ctx: &Context<Self>, e: AppError) -> bool {
+        self.loading = false;
+
+        let retry = ctx.link().callback(|_| Msg::Create);
+
+        self.toaster.error(e, Some(retry));
+
+        true
+    }
+
     fn on_word_count(&mut self, count: String) -> bool {
         if count.is_empty() {
             self.disabled = true;
@@ -569,65 +599,59 @@ impl ManageContent {
 }
 
 async fn create_micro_post(
-    ipfs: IpfsService,
+    ipfs: Rc<dyn IpfsClient>,
     user: User<MetamaskSigner>,
-    channel: Channel<LocalUpdater>,
+    channel: ChannelContext,
     text: String,
     tags: HashSet<String>,
+    toaster: Toaster,
     callback: Callback<Cid>,
+    error_cb: Callback<AppError>,
 ) {
     let cid = match user.create_micro_blog_post(text, None, false).await {
         Ok((cid, _)) => cid,
-        Err(e) => {
-            error!(&format!("{:#?}", e));
-            return;
-        }
+        Err(e) => return error_cb.emit(AppError::signing(e)),
     };
 
-    publish_tags(ipfs, cid, tags).await;
+    publish_tags(ipfs, cid, tags, toaster.clone()).await;
 
-    match channel.add_content(cid).await {
-        Ok(cid) => callback.emit(cid),
-        Err(e) => error!(&format!("{:#?}", e)),
-    }
+    add_content(channel, cid, toaster, callback);
 }
 
 async fn create_video_post(
-    ipfs: IpfsService,
+    ipfs: Rc<dyn IpfsClient>,
     user: User<MetamaskSigner>,
-    channel: Channel<LocalUpdater>,
+    channel: ChannelContext,
     title: String,
     cid: Cid,
     image: Option<SysFile>,
     tags: HashSet<String>,
+    toaster: Toaster,
     callback: Callback<Cid>,
+    error_cb: Callback<AppError>,
 ) {
     let cid = match user.create_video_post(title, cid, image, false).await {
         Ok((cid, _)) => cid,
-        Err(e) => {
-            error!(&format!("{:#?}", e));
-            return;
-        }
+        Err(e) => return error_cb.emit(AppError::signing(e)),
     };
 
-    publish_tags(ipfs, cid, tags).await;
+    publish_tags(ipfs, cid, tags, toaster.clone()).await;
 
-    match channel.add_content(cid).await {
-        Ok(cid) => callback.emit(cid),
-        Err(e) => error!(&format!("{:#?}", e)),
-    }
+    add_content(channel, cid, toaster, callback);
 }
 
 async fn create_article(
-    ipfs: IpfsService,
+    ipfs: Rc<dyn IpfsClient>,
     user: User<MetamaskSigner>,
-    channel: Channel<LocalUpdater>,
+    channel: ChannelContext,
     title: String,
     image: Option<SysFile>,
     markdown: Option<SysFile>,
     count: u64,
     tags: HashSet<String>,
+    toaster: Toaster,
     callback: Callback<Cid>,
+    error_cb: Callback<AppError>,
 ) {
     let markdown = match markdown {
         Some(md) => md,
@@ -641,21 +665,39 @@ async fn create_article(
         .await
     {
         Ok((cid, _)) => cid,
-        Err(e) => {
-            error!(&format!("{:#?}", e));
-            return;
-        }
+        Err(e) => return error_cb.emit(AppError::signing(e)),
     };
 
-    publish_tags(ipfs, cid, tags).await;
+    publish_tags(ipfs, cid, tags, toaster.clone()).await;
 
-    match channel.add_content(cid).await {
-        Ok(cid) => callback.emit(cid),
-        Err(e) => error!(&format!("{:#?}", e)),
-    }
+    add_content(channel, cid, toaster, callback);
+}
+
+/// Add signed content to the channel.
+///
+/// On failure, the update is queued in the outbox, the content is not signed again.
+fn add_content(channel: ChannelContext, cid: Cid, toaster: Toaster, callback: Callback<Cid>) {
+    spawn_local(async move {
+        match outbox::submit(&channel, Operation::AddContent(cid.into())).await {
+            Ok(root) => {
+                toaster.success("Published to your channel.");
+                callback.emit(root.unwrap_or(cid));
+            }
+            Err(e) => {
+                toaster.queued(
+                    format!("Content {} was signed but not added to your channel.", cid),
+                    e,
+                    channel,
+                );
+
+                // The content is safe, the form can be closed
+                callback.emit(cid);
+            }
+        }
+    });
 }
 
-async fn publish_tags(ipfs: IpfsService, cid: Cid, tags: HashSet<String>) {
+async fn publish_tags(ipfs: Rc<dyn IpfsClient>, cid: Cid, tags: HashSet<String>, toaster: Toaster) {
     let mut pub_pool: FuturesUnordered<_> = tags
         .into_iter()
         .map(|topic| ipfs.pubsub_pub(topic, cid.to_bytes()))
@@ -663,31 +705,56 @@ async fn publish_tags(ipfs: IpfsService, cid: Cid, tags: HashSet<String>) {
 
     while let Some(result) = pub_pool.next().await {
         if let Err(e) = result {
-            error!(&format!("{:#?}", e));
+            toaster.error_with("Tag publishing failed.", e.into(), None);
         }
     }
 }
 
-async fn remove_content(channel: Channel<LocalUpdater>, cid: Cid, callback: Callback<Cid>) {
-    match channel.remove_content(cid).await {
+async fn remove_content(
+    channel: ChannelContext,
+    cid: Cid,
+    toaster: Toaster,
+    callback: Callback<Cid>,
+    error_cb: Callback<AppError>,
+) {
+    match outbox::submit(&channel, Operation::RemoveContent(cid.into())).await {
         Ok(option) => match option {
             Some(cid) => callback.emit(cid),
-            None => error!("Cannot find content to remove!"),
+            None => error_cb.emit(AppError::NotFound(cid.to_string())),
         },
-        Err(e) => error!(&format!("{:#?}", e)),
+        Err(e) => {
+            toaster.queued(format!("Content {} was not removed.", cid), e, channel);
+            callback.emit(cid);
+        }
     }
 }
 
-async fn add_follow(channel: Channel<LocalUpdater>, addr: IPNSAddress, callback: Callback<Cid>) {
-    match channel.follow(addr).await {
-        Ok(cid) => callback.emit(cid),
-        Err(e) => error!(&format!("{:#?}", e)),
+async fn add_follow(
+    channel: ChannelContext,
+    addr: IPNSAddress,
+    toaster: Toaster,
+    callback: Callback<Cid>,
+) {
+    match outbox::submit(&channel, Operation::Follow(addr)).await {
+        Ok(root) => callback.emit(root.unwrap_or_default()),
+        Err(e) => {
+            toaster.queued(format!("Channel {} was not followed.", addr), e, channel);
+            callback.emit(Cid::default());
+        }
     }
 }
 
-async fn remove_follow(channel: Channel<LocalUpdater>, addr: IPNSAddress, callback: Callback<Cid>) {
-    match channel.unfollow(addr).await {
-        Ok(cid) => callback.emit(cid),
-        Err(e) => error!(&format!("{:#?}", e)),
+async fn remove_follow(
+    channel: ChannelContext,
+    addr: IPNSAddress,
+    toaster: Toaster,
+    callback: Callback<Cid>,
+) {
+    match outbox::submit(&channel, Operation::Unfollow(addr)).await {
+        Ok(root) => callback.emit(root.unwrap_or_default()),
+        Err(e) => {
+            toaster.queued(format!("Channel {} was not unfollowed.", addr), e, channel);
+            callback.emit(Cid::default());
+        }
     }
 }
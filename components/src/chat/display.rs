@@ -1,6 +1,9 @@
 #![cfg(target_arch = "wasm32")]
 
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    rc::Rc,
+};
 
 use cid::Cid;
 
@@ -13,14 +16,18 @@ use futures_util::{
 
 use gloo_console::error;
 
-use ipfs_api::IpfsService;
-
 use linked_data::{
     media::chat::{ChatInfo, ChatMessage, MessageType},
     types::PeerId,
 };
 
-use utils::ipfs::IPFSContext;
+use utils::{
+    client::{ClientError, IpfsClient},
+    ipfs::IPFSContext,
+    mute::MuteList,
+};
+
+use crate::toast::Toaster;
 
 use yew::{platform::spawn_local, prelude::*};
 
@@ -33,14 +40,19 @@ pub struct ChatDisplay {
 
     verified_sigs: HashMap<Cid, ChatInfo>,
 
+    /// Signatures of muted users.
+    muted_sigs: HashSet<Cid>,
+
     pending_verifs: HashMap<Cid, String>,
 
+    mutes: MuteList,
+
     messages: VecDeque<Html>,
 }
 
 pub enum Msg {
     PubSub((PeerId, Vec<u8>)),
-    Verification((Cid, ChatInfo)),
+    Verification((Cid, ChatInfo, String)),
 }
 
 impl Component for ChatDisplay {
@@ -51,7 +63,7 @@ impl Component for ChatDisplay {
         let (handle, regis) = AbortHandle::new_pair();
 
         if let Some((context, _)) = ctx.link().context::<IPFSContext>(Callback::noop()) {
-            let ipfs = context.client;
+            let ipfs = context.api;
 
             if let Some((context, _)) = ctx.link().context::<LiveContext>(Callback::noop()) {
                 let live = context.settings;
@@ -61,7 +73,7 @@ impl Component for ChatDisplay {
                         let cb = ctx.link().callback(Msg::PubSub).clone();
 
                         async move {
-                            let stream = ipfs.pubsub_sub(topic.into_bytes());
+                            let stream = ipfs.pubsub_sub(topic);
 
                             let mut stream = Abortable::new(stream, regis).boxed_local();
 
@@ -80,7 +92,9 @@ impl Component for ChatDisplay {
         Self {
             handle,
             verified_sigs: Default::default(),
+            muted_sigs: Default::default(),
             pending_verifs: Default::default(),
+            mutes: MuteList::load(),
             messages: VecDeque::with_capacity(MAX_MSG_NUM),
         }
     }
@@ -88,7 +102,7 @@ impl Component for ChatDisplay {
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Msg::PubSub((from, data)) => self.on_message(ctx, from, data),
-            Msg::Verification((cid, info)) => self.on_verif(cid, info),
+            Msg::Verification((cid, info, addr)) => self.on_verif(cid, info, addr),
         }
     }
 
@@ -115,7 +129,7 @@ impl ChatDisplay {
     /// Callback when a message is received
     fn on_message(&mut self, ctx: &Context<Self>, peer_id: PeerId, data: Vec<u8>) -> bool {
         let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.client,
+            Some((context, _)) => context.api,
             None => return false,
         };
 
@@ -134,6 +148,10 @@ impl ChatDisplay {
             _ => return false, //TODO process moderation messages
         };
 
+        if self.muted_sigs.contains(&signature.link) || self.mutes.mutes_text(&text) {
+            return false;
+        }
+
         if let Some(info) = self.verified_sigs.get(&signature.link) {
             if info.node == peer_id {
                 let msg = message_html(&info.name, &text);
@@ -150,10 +168,17 @@ impl ChatDisplay {
 
         self.pending_verifs.insert(signature.link, text);
 
+        let toaster = ctx
+            .link()
+            .context::<Toaster>(Callback::noop())
+            .map(|(toaster, _)| toaster)
+            .unwrap_or_default();
+
         spawn_local(user_verification(
             ipfs,
             peer_id,
             signature.link,
+            toaster,
             ctx.link().callback(Msg::Verification),
         ));
 
@@ -161,7 +186,14 @@ impl ChatDisplay {
     }
 
     /// Callback when user verification is complete
-    fn on_verif(&mut self, sig: Cid, info: ChatInfo) -> bool {
+    fn on_verif(&mut self, sig: Cid, info: ChatInfo, addr: String) -> bool {
+        if self.mutes.mutes_eth_addr(&addr) {
+            self.pending_verifs.remove(&sig);
+            self.muted_sigs.insert(sig);
+
+            return false;
+        }
+
         if let Some(text) = self.pending_verifs.remove(&sig) {
             let msg = message_html(&info.name, &text);
 
@@ -195,11 +227,40 @@ fn message_html(name: &str, text: &str) -> Html {
 }
 
 async fn user_verification(
-    ipfs: IpfsService,
+    ipfs: Rc<dyn IpfsClient>,
     peer: PeerId,
     sig: Cid,
-    callback: Callback<(Cid, ChatInfo)>,
+    toaster: Toaster,
+    callback: Callback<(Cid, ChatInfo, String)>,
 ) {
+    match verify_sender(&*ipfs, &peer, sig).await {
+        Ok((chat_info, addr)) => callback.emit((sig, chat_info, addr)),
+        Err(Rejection::Fetch(e)) => toaster.error_with("Chat message dropped.", e.into(), None),
+        Err(Rejection::Signature) => {
+            error!("Cannot Verify Signature");
+            toaster.warning("Chat message dropped, invalid signature.");
+        }
+        Err(Rejection::Sender) => {
+            error!("Cannot Verify Chat Sender");
+            toaster.warning("Chat message dropped, unverified sender.");
+        }
+    }
+}
+
+/// Why a chat message was dropped.
+#[derive(Debug)]
+enum Rejection {
+    Fetch(ClientError),
+    Signature,
+    Sender,
+}
+
+/// Chat info and wallet address of the signature, if the peer is the node it names.
+async fn verify_sender(
+    ipfs: &dyn IpfsClient,
+    peer: &PeerId,
+    sig: Cid,
+) -> Result<(ChatInfo, String), Rejection> {
     //TODO once Ledger app is built switch to DAG-JOSE
     /* let jws: JsonWebSignature = match ipfs.dag_get::<&str, RawJWS>(msg.signature.link, None).await {
         Ok(dag) => match dag.try_into() {
@@ -220,34 +281,64 @@ async fn user_verification(
         return;
     } */
 
-    let signed_link = match ipfs.dag_get::<&str, SignedLink>(sig, None).await {
-        Ok(dag) => dag,
-        Err(e) => {
-            error!(&format!("{:#?}", e));
-            return;
-        }
-    };
+    let signed_link = ipfs
+        .dag_get::<SignedLink>(sig, None)
+        .await
+        .map_err(Rejection::Fetch)?;
 
     if !signed_link.verify() {
-        error!("Cannot Verify Signature");
-        return;
+        return Err(Rejection::Signature);
     }
 
-    let chat_info = match ipfs
-        .dag_get::<&str, ChatInfo>(signed_link.link.link, None)
+    let chat_info = ipfs
+        .dag_get::<ChatInfo>(signed_link.link.link, None)
         .await
-    {
-        Ok(dag) => dag,
-        Err(e) => {
-            error!(&format!("{:#?}", e));
-            return;
-        }
-    };
+        .map_err(Rejection::Fetch)?;
 
-    if chat_info.node != peer {
-        error!("Cannot Verify Chat Sender");
-        return;
+    if chat_info.node != *peer {
+        return Err(Rejection::Sender);
     }
 
-    callback.emit((sig, chat_info));
+    Ok((chat_info, signed_link.get_address()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use utils::client::{mock_peer_id, MockIpfs};
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    async fn unknown_signature() {
+        let peer = mock_peer_id(1);
+        let ipfs = MockIpfs::new(peer.clone());
+
+        let sig = ipfs.seed_file(b"not a node".to_vec());
+
+        let result = verify_sender(&ipfs, &peer, sig).await;
+
+        assert!(matches!(
+            result,
+            Err(Rejection::Fetch(ClientError::NotFound(_)))
+        ));
+    }
+
+    #[wasm_bindgen_test]
+    async fn malformed_signature() {
+        let peer = mock_peer_id(1);
+        let ipfs = MockIpfs::new(peer.clone());
+
+        let sig = ipfs.seed_dag(&json!({ "link": "not a signed link" }));
+
+        let result = verify_sender(&ipfs, &peer, sig).await;
+
+        assert!(matches!(
+            result,
+            Err(Rejection::Fetch(ClientError::Serde(_)))
+        ));
+    }
 }
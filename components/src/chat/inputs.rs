@@ -57,7 +57,7 @@ impl Component for ChatInputs {
             if let Some(ipld) = utils::identity::get_current_identity() {
                 if let Some((context, _)) = ctx.link().context::<IPFSContext>(Callback::noop()) {
                     spawn_local(utils::r#async::dag_get(
-                        context.client,
+                        context.api,
                         ipld.link,
                         ctx.link().callback(Msg::Identity),
                     ))
@@ -85,6 +85,12 @@ impl Component for ChatInputs {
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        if let Some((context, _)) = ctx.link().context::<IPFSContext>(Callback::noop()) {
+            if context.is_read_only() {
+                return self.read_only_dialog();
+            }
+        }
+
         if ctx
             .link()
             .context::<UserContext>(Callback::noop())
@@ -115,6 +121,19 @@ impl ChatInputs {
         }
     }
 
+    fn read_only_dialog(&self) -> Html {
+        html! {
+            <Field label={"Chat requires an IPFS node".to_owned()} >
+                <ButtonRouter<Route> route={Route::Settings}>
+                    <span class="icon-text">
+                        <span class="icon"><i class="fas fa-cog"></i></span>
+                        <span> {"Go To Settings"} </span>
+                    </span>
+                </ButtonRouter<Route>>
+            </Field>
+        }
+    }
+
     fn name_dialog(&self, ctx: &Context<Self>) -> Html {
         html! {
         <>
@@ -156,7 +175,7 @@ impl ChatInputs {
     /// Send chat message via gossipsub.
     fn send_message(&mut self, ctx: &Context<Self>) -> bool {
         let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.client,
+            Some((context, _)) => context.api,
             None => return false,
         };
 
@@ -227,8 +246,12 @@ impl ChatInputs {
         #[cfg(debug_assertions)]
         info!("Name Submitted");
 
-        let node = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.peer_id,
+        let node = match ctx
+            .link()
+            .context::<IPFSContext>(Callback::noop())
+            .and_then(|(context, _)| context.peer_id)
+        {
+            Some(peer_id) => peer_id,
             None => return false,
         };
 
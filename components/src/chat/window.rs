@@ -33,14 +33,14 @@ impl Component for ChatWindow {
 
     fn create(ctx: &Context<Self>) -> Self {
         if let Some((context, _)) = ctx.link().context::<IPFSContext>(Callback::noop()) {
-            let ipfs = context.client;
+            let ipfs = context.api;
 
             spawn_local({
                 let cb = ctx.link().callback(Msg::Settings);
                 let cid = ctx.props().cid;
 
                 async move {
-                    match ipfs.dag_get::<&str, LiveSettings>(cid, None).await {
+                    match ipfs.dag_get::<LiveSettings>(cid, None).await {
                         Ok(id) => cb.emit(id),
                         Err(e) => error!(&format!("{:#?}", e)),
                     }
@@ -2,29 +2,29 @@
 
 use cid::Cid;
 
-use defluencer::{
-    channel::{local::LocalUpdater, Channel},
-    crypto::signers::MetamaskSigner,
-    user::User,
-};
-
-use gloo_console::error;
+use defluencer::{crypto::signers::MetamaskSigner, user::User};
 
-use ipfs_api::IpfsService;
 use linked_data::{
     channel::ChannelMetadata, identity::Identity, media::comments::Comment, types::IPNSAddress,
 };
 
+use std::rc::Rc;
+
 use utils::{
+    client::IpfsClient,
     commentary::CommentaryContext,
     defluencer::{ChannelContext, UserContext},
+    errors::AppError,
     ipfs::IPFSContext,
+    outbox::{self, Operation},
 };
 
 use ybc::{Box, Button, Control, Field, TextArea};
 
 use yew::{platform::spawn_local, prelude::*};
 
+use crate::toast::Toaster;
+
 #[derive(Properties, PartialEq)]
 pub struct Props {
     /// Signed link to media Cid
@@ -38,6 +38,7 @@ pub struct Props {
 
 pub struct CommentButton {
     user_context: Option<UserContext>,
+    toaster: Toaster,
 
     text: String,
     text_cb: Callback<String>,
@@ -54,6 +55,7 @@ pub enum Msg {
     Text(String),
     Create,
     Done(Cid),
+    Error(AppError),
 }
 
 impl Component for CommentButton {
@@ -66,12 +68,19 @@ impl Component for CommentButton {
             None => None,
         };
 
+        let toaster = ctx
+            .link()
+            .context::<Toaster>(Callback::noop())
+            .map(|(toaster, _)| toaster)
+            .unwrap_or_default();
+
         let modal_cb = ctx.link().callback(|_| Msg::Modal);
         let text_cb = ctx.link().callback(Msg::Text);
         let create_cb = ctx.link().callback(|_| Msg::Create);
 
         Self {
             user_context,
+            toaster,
 
             text: String::default(),
             text_cb,
@@ -90,6 +99,7 @@ impl Component for CommentButton {
             Msg::Text(text) => self.on_text(text),
             Msg::Create => self.on_create(ctx),
             Msg::Done(cid) => self.on_done(cid, ctx),
+            Msg::Error(e) => self.on_error(ctx, e),
         }
     }
 
@@ -161,8 +171,11 @@ impl CommentButton {
             .map(|(context, _)| context.callback);
 
         let done_cb = ctx.link().callback(Msg::Done);
+        let error_cb = ctx.link().callback(Msg::Error);
 
-        spawn_local(publish_comment(user, origin, text, parent_cb, done_cb));
+        spawn_local(publish_comment(
+            user, origin, text, parent_cb, done_cb, error_cb,
+        ));
 
         self.loading = true;
 
@@ -174,17 +187,28 @@ impl CommentButton {
         self.modal = false;
 
         if let Some((context, _)) = ctx.link().context::<ChannelContext>(Callback::noop()) {
-            spawn_local(add_to_channel(context.channel, cid));
+            add_to_channel(context, cid, self.toaster.clone());
         }
 
         if let Some((context, _)) = ctx.link().context::<IPFSContext>(Callback::noop()) {
             if let Some(addr) = ctx.props().identity.ipns_addr {
-                spawn_local(send_comment(context.client, addr, cid));
+                spawn_local(send_comment(context.api, addr, cid, self.toaster.clone()));
             }
         }
 
         true
     }
+
+    /// The comment text is kept, ready to retry.
+    fn on_error(&mut self, ctx: &Context<Self>, e: AppError) -> bool {
+        self.loading = false;
+
+        let retry = ctx.link().callback(|_| Msg::Create);
+
+        self.toaster.error(e, Some(retry));
+
+        true
+    }
 }
 
 async fn publish_comment(
@@ -193,6 +217,7 @@ async fn publish_comment(
     text: String,
     parent_cb: Option<Callback<(Cid, Comment)>>,
     done_cb: Callback<Cid>,
+    error_cb: Callback<AppError>,
 ) {
     match user.create_comment(origin, text, false).await {
         Ok((cid, comment)) => {
@@ -202,36 +227,45 @@ async fn publish_comment(
 
             done_cb.emit(cid);
         }
-        Err(e) => error!(&format!("{:#?}", e)),
+        Err(e) => error_cb.emit(AppError::signing(e)),
     }
 }
 
-async fn add_to_channel(channel: Channel<LocalUpdater>, cid: Cid) {
-    if let Err(e) = channel.add_comment(cid).await {
-        error!(&format!("{:#?}", e))
-    }
+/// On failure, retrying only updates the channel, the comment is not signed again.
+fn add_to_channel(channel: ChannelContext, cid: Cid, toaster: Toaster) {
+    spawn_local(async move {
+        if let Err(e) = outbox::submit(&channel, Operation::AddComment(cid.into())).await {
+            toaster.queued(
+                format!("Comment {} was signed but not added to your channel.", cid),
+                e,
+                channel,
+            );
+        }
+    });
 }
 
-async fn send_comment(ipfs: IpfsService, addr: IPNSAddress, cid: Cid) {
+async fn send_comment(ipfs: Rc<dyn IpfsClient>, addr: IPNSAddress, cid: Cid, toaster: Toaster) {
     let root = match ipfs.name_resolve(addr.into()).await {
         Ok(cid) => cid,
         Err(e) => {
-            error!(&format!("{:#?}", e));
-            return;
+            return toaster.error_with(
+                "Comment not sent to the channel owner.",
+                AppError::ipns(e),
+                None,
+            )
         }
     };
 
-    let meta = match ipfs.dag_get::<&str, ChannelMetadata>(root, None).await {
+    let meta = match ipfs.dag_get::<ChannelMetadata>(root, None).await {
         Ok(meta) => meta,
         Err(e) => {
-            error!(&format!("{:#?}", e));
-            return;
+            return toaster.error_with("Comment not sent to the channel owner.", e.into(), None)
         }
     };
 
     if let Some(topic) = meta.agregation_channel {
         if let Err(e) = ipfs.pubsub_pub(topic, cid.to_bytes()).await {
-            error!(&format!("{:#?}", e));
+            toaster.error_with("Comment not sent to the channel owner.", e.into(), None);
         }
     }
 }
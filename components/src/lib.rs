@@ -14,6 +14,7 @@ pub mod markdown;
 pub mod pure;
 pub mod search_bar;
 pub mod share_button;
+pub mod toast;
 pub mod video_player;
 
 #[derive(Routable, Clone, PartialEq)]
@@ -27,6 +28,12 @@ pub enum Route {
     #[at("/feed")]
     Feed, // social.defluencer.eth/#/feed/
 
+    #[at("/feed/:list")]
+    FeedList { list: String }, // social.defluencer.eth/#/feed/<LIST_NAME_HERE>
+
+    #[at("/discover")]
+    Discover, // social.defluencer.eth/#/discover/
+
     #[at("/live/:cid")]
     Live { cid: Cid }, // social.defluencer.eth/#/live/<CID_HERE>
 
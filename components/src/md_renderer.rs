@@ -1,12 +1,12 @@
 #![cfg(target_arch = "wasm32")]
 
+use std::rc::Rc;
+
 use cid::Cid;
 
 use gloo_console::error;
 
-use ipfs_api::IpfsService;
-
-use utils::ipfs::IPFSContext;
+use utils::{client::IpfsClient, ipfs::IPFSContext};
 
 use yew::{platform::spawn_local, prelude::*};
 
@@ -32,7 +32,7 @@ impl Component for Markdown {
     fn create(ctx: &Context<Self>) -> Self {
         if let Some((context, _)) = ctx.link().context::<IPFSContext>(Callback::noop()) {
             spawn_local(get_markdown_file(
-                context.client,
+                context.api,
                 ctx.link().callback(Msg::Text),
                 ctx.props().cid,
             ));
@@ -56,7 +56,7 @@ impl Component for Markdown {
     }
 }
 
-async fn get_markdown_file(ipfs: IpfsService, callback: Callback<Html>, cid: Cid) {
+async fn get_markdown_file(ipfs: Rc<dyn IpfsClient>, callback: Callback<Html>, cid: Cid) {
     let data = match ipfs.cat(cid, Option::<&str>::None).await {
         Ok(data) => data,
         Err(e) => {
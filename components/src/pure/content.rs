@@ -18,7 +18,7 @@ use yew_router::prelude::Link;
 use crate::{
     comment_button::CommentButton,
     md_renderer::Markdown,
-    pure::{DagExplorer, IPFSImage, Thumbnail},
+    pure::{DagExplorer, IPFSImage, Thumbnail, VerifiedBadge},
     share_button::ShareButton,
     video_player::VideoPlayer,
     Route,
@@ -118,18 +118,6 @@ pub fn pure_content(props: &ContentProps) -> Html {
 
     let dt = timestamp_to_datetime(media.user_timestamp());
 
-    let mut check = html!();
-
-    if verified.is_some() && verified.unwrap() {
-        check = html! {
-        <LevelItem>
-            <span class="icon">
-                <i class="fa-solid fa-check"></i>
-            </span>
-        </LevelItem>
-        };
-    }
-
     html! {
     <ybc::Media>
         <MediaLeft>
@@ -144,7 +132,9 @@ pub fn pure_content(props: &ContentProps) -> Html {
                         <LevelItem>
                             { name }
                         </LevelItem>
-                        {check}
+                        if verified.unwrap_or_default() {
+                            <VerifiedBadge />
+                        }
                     </LevelLeft>
                     <LevelRight>
                         <LevelItem>
@@ -164,13 +154,13 @@ pub fn pure_content(props: &ContentProps) -> Html {
                     <LevelLeft>
                         <LevelItem>
                             <CommentButton {cid} identity={identity.clone()} >
-                                <Thumbnail key={cid.to_string()} {cid} media={media.clone()} identity={identity.clone()} />
+                                <Thumbnail key={cid.to_string()} {cid} media={media.clone()} identity={identity.clone()} verified={verified.unwrap_or_default()} />
                             </CommentButton>
                         </LevelItem>
                         if !is_author {
                         <LevelItem>
                             <ShareButton {cid} >
-                                <Thumbnail key={cid.to_string()} {cid} media={media.clone()} identity={identity.clone()} />
+                                <Thumbnail key={cid.to_string()} {cid} media={media.clone()} identity={identity.clone()} verified={verified.unwrap_or_default()} />
                             </ShareButton>
                         </LevelItem>
                         }
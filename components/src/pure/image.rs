@@ -6,6 +6,8 @@ use yew::prelude::*;
 
 use ybc::{Image, ImageSize};
 
+use utils::ipfs::{get_gateway_addr, IPFSContext};
+
 #[derive(Properties, PartialEq)]
 pub struct IPFSImageProps {
     pub cid: Cid,
@@ -21,9 +23,17 @@ pub fn pure_image(props: &IPFSImageProps) -> Html {
     let size = props.size.clone();
     let rounded = props.rounded;
 
+    let read_only = use_context::<IPFSContext>().map_or(false, |context| context.is_read_only());
+
+    let src = if read_only {
+        format!("{}/ipfs/{}", get_gateway_addr().trim_end_matches('/'), cid)
+    } else {
+        format!("http://{}.ipfs.localhost:8080", cid)
+    };
+
     html! {
     <Image {size} >
-        <img class={ if rounded { "is-rounded"} else {""} } src={ format!("http://{}.ipfs.localhost:8080", cid) } />
+        <img class={ if rounded { "is-rounded"} else {""} } {src} />
     </Image>
     }
 }
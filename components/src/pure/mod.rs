@@ -7,6 +7,7 @@ mod image;
 mod navbar;
 mod searching;
 mod thumbnail;
+mod verified;
 
 pub use content::Content;
 pub use dag_explorer::DagExplorer;
@@ -15,3 +16,4 @@ pub use image::IPFSImage;
 pub use navbar::NavigationBar;
 pub use searching::Searching;
 pub use thumbnail::Thumbnail;
+pub use verified::VerifiedBadge;
@@ -1,8 +1,15 @@
 #![cfg(target_arch = "wasm32")]
 
-use utils::defluencer::ChannelContext;
+use utils::{
+    defluencer::ChannelContext,
+    ipfs::IPFSContext,
+    subscriptions::{get_last_seen, get_unread_counts, UnreadCounts, MAX_UNREAD},
+};
 
-use yew::{classes, function_component, html, use_context, Html};
+use yew::{
+    classes, function_component, html, platform::spawn_local, use_context, use_effect_with_deps,
+    use_state, Callback, Html,
+};
 
 use yew_router::prelude::*;
 
@@ -13,9 +20,38 @@ use crate::{search_bar::SearchBar, Route};
 #[function_component(NavigationBar)]
 pub fn navbar() -> Html {
     let channel_context = use_context::<ChannelContext>();
+    let read_only = use_context::<IPFSContext>().map_or(false, |context| context.is_read_only());
 
     let subscriptions = utils::subscriptions::get_sub_list();
 
+    let unread = use_state(get_unread_counts);
+
+    {
+        let unread = unread.clone();
+        let ipfs = use_context::<IPFSContext>().map(|context| context.api);
+        let subscriptions = subscriptions.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                if let Some(ipfs) = ipfs {
+                    if unread.is_stale() && !subscriptions.is_empty() {
+                        let cb = Callback::from(move |counts| unread.set(counts));
+
+                        spawn_local(utils::r#async::count_unread(
+                            ipfs,
+                            subscriptions,
+                            get_last_seen(),
+                            cb,
+                        ));
+                    }
+                }
+
+                || ()
+            },
+            (),
+        );
+    }
+
     let navbrand = html! {
         <Link<Route> classes="navbar-item" to={Route::Home}>
             <Level>
@@ -36,12 +72,15 @@ pub fn navbar() -> Html {
     let navstart = html! {
         <>
             if !subscriptions.is_empty(){
-            <Link<Route> classes="navbar-item" to={Route::Feed}>
-                <span class="icon-text">
-                    <span class="icon"><i class="fas fa-broadcast-tower"></i></span>
-                    <span><strong>{"Content Feed"}</strong></span>
-                </span>
-            </Link<Route>>
+                { render_feed_link(&unread) }
+                if !read_only {
+                <Link<Route> classes="navbar-item" to={Route::Discover}>
+                    <span class="icon-text">
+                        <span class="icon"><i class="fa-solid fa-compass"></i></span>
+                        <span><strong>{"Discover"}</strong></span>
+                    </span>
+                </Link<Route>>
+                }
             }
             if let Some(context) = channel_context {
             <Link<Route> classes="navbar-item" to={Route::Channel { addr: context.channel.get_address() }}>
@@ -56,15 +95,65 @@ pub fn navbar() -> Html {
     };
 
     let navend = html! {
+        <>
+        if read_only {
+        <Link<Route> classes="navbar-item" to={Route::Settings}>
+            <span class="tag is-warning is-medium" title="Read-only, publishing is disabled">{"Node offline"}</span>
+        </Link<Route>>
+        }
         <Link<Route> classes="navbar-item" to={Route::Settings}>
             <span class="icon-text" >
                 <span class="icon"><i class="fas fa-cog"></i></span>
                 <span><strong>{"Settings"}</strong></span>
             </span>
         </Link<Route>>
+        </>
     };
 
     html! {
         <Navbar classes={classes!("is-spaced")} transparent=false spaced=true padded=false {navbrand} {navstart} {navend} navburger=true />
     }
 }
+
+fn display_count(count: usize) -> String {
+    if count >= MAX_UNREAD {
+        format!("{}+", MAX_UNREAD)
+    } else {
+        count.to_string()
+    }
+}
+
+/// Content feed link with unread counts per followee.
+fn render_feed_link(unread: &UnreadCounts) -> Html {
+    let link = html! {
+        <Link<Route> classes="navbar-item" to={Route::Feed}>
+            <span class="icon-text">
+                <span class="icon"><i class="fas fa-broadcast-tower"></i></span>
+                <span><strong>{"Content Feed"}</strong></span>
+                if !unread.channels.is_empty() {
+                <span class="tag is-danger is-rounded ml-2">{ display_count(unread.total()) }</span>
+                }
+            </span>
+        </Link<Route>>
+    };
+
+    if unread.channels.is_empty() {
+        return link;
+    }
+
+    html! {
+        <div class="navbar-item has-dropdown is-hoverable">
+            {link}
+            <div class="navbar-dropdown">
+            {
+                unread.channels.iter().map(|channel| html! {
+                    <Link<Route> classes="navbar-item" to={Route::Channel { addr: channel.addr }}>
+                        <span>{ &channel.name }</span>
+                        <span class="tag is-rounded ml-2">{ display_count(channel.count) }</span>
+                    </Link<Route>>
+                }).collect::<Html>()
+            }
+            </div>
+        </div>
+    }
+}
@@ -2,7 +2,9 @@
 
 use linked_data::{identity::Identity, media::Media};
 
-use utils::timestamp_to_datetime;
+use std::collections::BTreeMap;
+
+use utils::{reactions::ReactionContext, timestamp_to_datetime};
 
 use cid::Cid;
 
@@ -16,7 +18,7 @@ use ybc::{
 use yew_router::prelude::Link;
 
 use crate::{
-    pure::{DagExplorer, IPFSImage},
+    pure::{DagExplorer, IPFSImage, VerifiedBadge},
     Route,
 };
 
@@ -28,7 +30,13 @@ pub struct ThumbnailProps {
 
     pub identity: Identity,
 
-    pub shared_by: Option<Identity>,
+    /// Channels that shared this content, if any.
+    #[prop_or_default]
+    pub shared_by: Vec<Identity>,
+
+    /// Signed by the identity wallet.
+    #[prop_or_default]
+    pub verified: bool,
 }
 
 #[function_component(Thumbnail)]
@@ -38,31 +46,36 @@ pub fn pure_thumbnail(props: &ThumbnailProps) -> Html {
         media,
         identity,
         shared_by,
+        verified,
     } = props;
     let cid = *cid;
 
     let dt = timestamp_to_datetime(media.user_timestamp());
 
+    let sharers = render_sharers(shared_by);
+
+    let counts = use_context::<ReactionContext>()
+        .map(|context| context.counts(&cid))
+        .unwrap_or_default();
+
+    let reactions = render_reactions(&counts);
+
     let content = match media {
         Media::Blog(article) => {
             html! {
             <>
             <Level>
                 <LevelLeft>
-                    if let Some(sharer) = shared_by {
-                    <LevelItem>
-                        <span class="icon-text">
-                            <span><strong>{ &sharer.name }</strong></span>
-                            <span class="icon"><i class="fa-solid fa-retweet"></i></span>
-                        </span>
-                    </LevelItem>
-                    }
+                    { sharers.clone() }
                     <LevelItem>
                         <span class="icon-text">
                             <span class="icon"><i class="fas fa-user"></i></span>
                             <span><strong>{ &identity.name }</strong></span>
                         </span>
                     </LevelItem>
+                    if *verified {
+                        <VerifiedBadge />
+                    }
                     if let Some(count) = article.word_count {
                         <LevelItem>
                             <span class="icon-text">
@@ -73,6 +86,7 @@ pub fn pure_thumbnail(props: &ThumbnailProps) -> Html {
                     }
                 </LevelLeft>
                 <LevelRight>
+                    { reactions.clone() }
                     <LevelItem>
                         <span class="icon-text">
                             <span class="icon"><i class="fas fa-clock"></i></span>
@@ -97,20 +111,16 @@ pub fn pure_thumbnail(props: &ThumbnailProps) -> Html {
             <>
             <Level>
                 <LevelLeft>
-                    if let Some(sharer) = shared_by {
-                    <LevelItem>
-                        <span class="icon-text">
-                            <span><strong>{ &sharer.name }</strong></span>
-                            <span class="icon"><i class="fa-solid fa-retweet"></i></span>
-                        </span>
-                    </LevelItem>
-                    }
+                    { sharers.clone() }
                     <LevelItem>
                         <span class="icon-text">
                             <span class="icon"><i class="fas fa-user"></i></span>
                             <span><strong>{ &identity.name }</strong></span>
                         </span>
                     </LevelItem>
+                    if *verified {
+                        <VerifiedBadge />
+                    }
                     {
                         if let Some(duration) = video.duration {
                             let (hour, minute, second) = utils::seconds_to_timecode(duration);
@@ -129,6 +139,7 @@ pub fn pure_thumbnail(props: &ThumbnailProps) -> Html {
                     }
                 </LevelLeft>
                 <LevelRight>
+                    { reactions.clone() }
                     <LevelItem>
                         <span class="icon-text">
                             <span class="icon"><i class="fas fa-clock"></i></span>
@@ -155,20 +166,16 @@ pub fn pure_thumbnail(props: &ThumbnailProps) -> Html {
             <>
             <Level>
                 <LevelLeft>
-                    if let Some(sharer) = shared_by {
-                    <LevelItem>
-                        <span class="icon-text">
-                            <span><strong>{ &sharer.name }</strong></span>
-                            <span class="icon"><i class="fa-solid fa-retweet"></i></span>
-                        </span>
-                    </LevelItem>
-                    }
+                    { sharers.clone() }
                     <LevelItem>
                         <span class="icon-text">
                             <span class="icon"><i class="fas fa-user"></i></span>
                             <span><strong>{ &identity.name }</strong></span>
                         </span>
                     </LevelItem>
+                    if *verified {
+                        <VerifiedBadge />
+                    }
                     <LevelItem>
                         <span class="icon-text">
                             <span class="icon"><i class="fa-solid fa-comment"></i></span>
@@ -177,6 +184,7 @@ pub fn pure_thumbnail(props: &ThumbnailProps) -> Html {
                     </LevelItem>
                 </LevelLeft>
                 <LevelRight>
+                    { reactions.clone() }
                     <LevelItem>
                         <span class="icon-text">
                             <span class="icon"><i class="fas fa-clock"></i></span>
@@ -209,3 +217,43 @@ pub fn pure_thumbnail(props: &ThumbnailProps) -> Html {
     </ybc::Media>
     }
 }
+
+fn render_sharers(shared_by: &[Identity]) -> Html {
+    if shared_by.is_empty() {
+        return html! {};
+    }
+
+    let names = shared_by
+        .iter()
+        .map(|identity| identity.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    html! {
+    <LevelItem>
+        <span class="icon-text" title="Shared by">
+            <span><strong>{ names }</strong></span>
+            <span class="icon"><i class="fa-solid fa-retweet"></i></span>
+        </span>
+    </LevelItem>
+    }
+}
+
+/// Reaction counts of followed channels.
+fn render_reactions(counts: &BTreeMap<String, usize>) -> Html {
+    if counts.is_empty() {
+        return html! {};
+    }
+
+    html! {
+    <LevelItem>
+        <span class="tags" title="Reactions from followed channels">
+        {
+            counts.iter().map(|(emoji, count)| html! {
+                <span class="tag is-light">{ format!("{} {}", emoji, count) }</span>
+            }).collect::<Html>()
+        }
+        </span>
+    </LevelItem>
+    }
+}
@@ -0,0 +1,17 @@
+#![cfg(target_arch = "wasm32")]
+
+use ybc::LevelItem;
+
+use yew::prelude::*;
+
+/// Shown when media is signed by the wallet of its identity.
+#[function_component(VerifiedBadge)]
+pub fn verified_badge() -> Html {
+    html! {
+    <LevelItem>
+        <span class="icon" title="Signed by the identity wallet">
+            <i class="fa-solid fa-check"></i>
+        </span>
+    </LevelItem>
+    }
+}
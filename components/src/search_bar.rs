@@ -4,7 +4,7 @@ use cid::Cid;
 
 use linked_data::types::IPNSAddress;
 
-use utils::web3::Web3Context;
+use utils::{errors::AppError, web3::Web3Context};
 
 use yew::{
     function_component, html, platform::spawn_local, use_context, use_state, Callback, Html,
@@ -14,9 +14,7 @@ use yew_router::prelude::*;
 
 use ybc::{Button, Input};
 
-use crate::Route;
-
-use gloo_console::error;
+use crate::{toast::Toaster, Route};
 
 /* #[cfg(debug_assertions)]
 use gloo_console::info; */
@@ -33,6 +31,8 @@ pub fn search_bar() -> Html {
         None => return html! {},
     };
 
+    let toaster = use_context::<Toaster>().unwrap_or_default();
+
     let text_state = use_state(|| String::new());
     let loading_state = use_state(|| false);
 
@@ -66,6 +66,7 @@ pub fn search_bar() -> Html {
             spawn_local(get_channel(
                 context.clone(),
                 (*text).clone(),
+                toaster.clone(),
                 ondone.clone(),
             ));
         })
@@ -79,7 +80,12 @@ pub fn search_bar() -> Html {
     }
 }
 
-async fn get_channel(context: Web3Context, text: String, callback: Callback<Option<IPNSAddress>>) {
+async fn get_channel(
+    context: Web3Context,
+    text: String,
+    toaster: Toaster,
+    callback: Callback<Option<IPNSAddress>>,
+) {
     /* if !text.starts_with("defluencer.") {
         text.insert_str(0, "defluencer.");
     }
@@ -96,12 +102,20 @@ async fn get_channel(context: Web3Context, text: String, callback: Callback<Opti
                         callback.emit(Some(addr));
                         return;
                     }
-                    Err(e) => error!(&format!("{:#?}", e)),
+                    Err(e) => toaster.error_with(
+                        format!("{} is not a channel.", text),
+                        AppError::decode(e),
+                        None,
+                    ),
                 },
-                Err(e) => error!(&format!("{:#?}", e)),
+                Err(e) => toaster.error_with(
+                    format!("{} is not a channel.", text),
+                    AppError::decode(e),
+                    None,
+                ),
             }
         }
-        Err(e) => error!(&format!("{:#?}", e)),
+        Err(e) => toaster.error_with(format!("Cannot find {}.", text), AppError::ipns(e), None),
     }
 
     callback.emit(None);
@@ -2,16 +2,17 @@
 
 use cid::Cid;
 
-use defluencer::channel::{local::LocalUpdater, Channel};
-
-use gloo_console::error;
-
-use utils::defluencer::ChannelContext;
+use utils::{
+    defluencer::ChannelContext,
+    outbox::{self, Operation},
+};
 
 use ybc::{Box, Button};
 
 use yew::{platform::spawn_local, prelude::*};
 
+use crate::toast::Toaster;
+
 #[derive(Properties, PartialEq)]
 pub struct Props {
     /// Signed link to media Cid
@@ -22,7 +23,8 @@ pub struct Props {
 }
 
 pub struct ShareButton {
-    channel: Option<Channel<LocalUpdater>>,
+    channel: Option<ChannelContext>,
+    toaster: Toaster,
 
     share_cb: Callback<MouseEvent>,
 
@@ -43,13 +45,20 @@ impl Component for ShareButton {
         let channel = ctx
             .link()
             .context::<ChannelContext>(Callback::noop())
-            .map(|(context, _)| context.channel);
+            .map(|(context, _)| context);
+
+        let toaster = ctx
+            .link()
+            .context::<Toaster>(Callback::noop())
+            .map(|(toaster, _)| toaster)
+            .unwrap_or_default();
 
         let share_cb = ctx.link().callback(|_| Msg::Share);
         let modal_cb = ctx.link().callback(|_| Msg::Modal);
 
         Self {
             channel,
+            toaster,
 
             share_cb,
 
@@ -110,7 +119,7 @@ impl ShareButton {
             None => return false,
         };
 
-        spawn_local(share_content(channel, ctx.props().cid));
+        share_content(channel, ctx.props().cid, self.toaster.clone());
 
         self.modal = false;
 
@@ -118,8 +127,11 @@ impl ShareButton {
     }
 }
 
-async fn share_content(channel: Channel<LocalUpdater>, cid: Cid) {
-    if let Err(e) = channel.add_content(cid).await {
-        error!(&format!("{:#?}", e))
-    }
+fn share_content(channel: ChannelContext, cid: Cid, toaster: Toaster) {
+    spawn_local(async move {
+        match outbox::submit(&channel, Operation::AddContent(cid.into())).await {
+            Ok(_) => toaster.success("Shared to your channel."),
+            Err(e) => toaster.queued("Sharing failed.", e, channel),
+        }
+    });
 }
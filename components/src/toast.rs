@@ -0,0 +1,298 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::collections::HashMap;
+
+use gloo_console::error;
+
+use gloo_timers::callback::Timeout;
+
+use utils::{defluencer::ChannelContext, errors::AppError};
+
+use ybc::Button;
+
+use yew::{platform::spawn_local, prelude::*};
+
+/// Milliseconds before non-error toasts are dismissed.
+const TOAST_DURATION: u32 = 5_000;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ToastKind {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Toast {
+    pub kind: ToastKind,
+
+    pub message: String,
+
+    pub details: Option<String>,
+
+    /// Called when the user click retry.
+    pub retry: Option<Callback<()>>,
+}
+
+/// Display notifications from anywhere in the app.
+///
+/// Without a provider, toasts are dropped but errors are still logged.
+#[derive(Clone, Default, PartialEq)]
+pub struct Toaster {
+    callback: Callback<Toast>,
+}
+
+impl Toaster {
+    pub fn show(&self, toast: Toast) {
+        self.callback.emit(toast);
+    }
+
+    pub fn info(&self, message: impl Into<String>) {
+        self.show(Toast {
+            kind: ToastKind::Info,
+            message: message.into(),
+            details: None,
+            retry: None,
+        });
+    }
+
+    pub fn success(&self, message: impl Into<String>) {
+        self.show(Toast {
+            kind: ToastKind::Success,
+            message: message.into(),
+            details: None,
+            retry: None,
+        });
+    }
+
+    pub fn warning(&self, message: impl Into<String>) {
+        self.show(Toast {
+            kind: ToastKind::Warning,
+            message: message.into(),
+            details: None,
+            retry: None,
+        });
+    }
+
+    /// Display the error with a retry button if it makes sense.
+    pub fn error(&self, e: AppError, retry: Option<Callback<()>>) {
+        error!(&format!("{:#?}", e));
+
+        let retry = if e.is_retryable() { retry } else { None };
+
+        self.show(Toast {
+            kind: ToastKind::Error,
+            message: e.message().to_owned(),
+            details: Some(e.details().to_owned()),
+            retry,
+        });
+    }
+
+    /// Same as error but with a more specific message.
+    pub fn error_with(&self, message: impl Into<String>, e: AppError, retry: Option<Callback<()>>) {
+        error!(&format!("{:#?}", e));
+
+        let retry = if e.is_retryable() { retry } else { None };
+
+        self.show(Toast {
+            kind: ToastKind::Error,
+            message: format!("{} {}", message.into(), e.message()),
+            details: Some(e.details().to_owned()),
+            retry,
+        });
+    }
+
+    /// Display the error of an operation kept in the outbox.
+    ///
+    /// Retrying replays the outbox instead of queuing the operation again.
+    pub fn queued(&self, message: impl Into<String>, e: AppError, channel: ChannelContext) {
+        let retry = Callback::from({
+            let toaster = self.clone();
+
+            move |_| {
+                let channel = channel.clone();
+                let toaster = toaster.clone();
+
+                spawn_local(async move {
+                    match utils::outbox::replay(&channel, true).await {
+                        Some(0) => toaster.success("Queued operations published."),
+                        Some(count) => {
+                            toaster.warning(format!("{} operations still queued.", count))
+                        }
+                        None => {}
+                    }
+                })
+            }
+        });
+
+        self.error_with(
+            format!("{} It was queued and will be retried.", message.into()),
+            e,
+            Some(retry),
+        );
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub children: Children,
+}
+
+struct Entry {
+    toast: Toast,
+
+    /// Identical toasts are grouped.
+    count: usize,
+
+    _timeout: Option<Timeout>,
+}
+
+/// Provide the toaster context and display the toasts.
+pub struct ToastProvider {
+    toaster: Toaster,
+
+    toasts: HashMap<usize, Entry>,
+    order: Vec<usize>,
+    next_id: usize,
+}
+
+pub enum Msg {
+    Show(Toast),
+    Dismiss(usize),
+    Retry(usize),
+}
+
+impl Component for ToastProvider {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let toaster = Toaster {
+            callback: ctx.link().callback(Msg::Show),
+        };
+
+        Self {
+            toaster,
+
+            toasts: HashMap::default(),
+            order: Vec::default(),
+            next_id: 0,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Show(toast) => self.on_show(ctx, toast),
+            Msg::Dismiss(id) => self.on_dismiss(id),
+            Msg::Retry(id) => {
+                if let Some(entry) = self.toasts.get(&id) {
+                    if let Some(retry) = entry.toast.retry.as_ref() {
+                        retry.emit(());
+                    }
+                }
+
+                self.on_dismiss(id)
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+        <ContextProvider<Toaster> context={self.toaster.clone()} >
+            { ctx.props().children.clone() }
+            <div style="position: fixed; bottom: 1rem; right: 1rem; z-index: 50; max-width: 24rem;" >
+            {
+                self.order.iter().filter_map(|id| {
+                    let entry = self.toasts.get(id)?;
+
+                    Some(self.render_toast(ctx, *id, entry))
+                }).collect::<Html>()
+            }
+            </div>
+        </ContextProvider<Toaster>>
+        }
+    }
+}
+
+impl ToastProvider {
+    fn render_toast(&self, ctx: &Context<Self>, id: usize, entry: &Entry) -> Html {
+        let class = match entry.toast.kind {
+            ToastKind::Info => "is-info",
+            ToastKind::Success => "is-success",
+            ToastKind::Warning => "is-warning",
+            ToastKind::Error => "is-danger",
+        };
+
+        html! {
+        <div key={id} class={classes!("notification", class)} >
+            <button class="delete" onclick={ctx.link().callback(move |_| Msg::Dismiss(id))} />
+            <p>
+                { &entry.toast.message }
+                if entry.count > 1 {
+                    { format!(" (x{})", entry.count) }
+                }
+            </p>
+            if let Some(details) = entry.toast.details.as_ref() {
+            <details>
+                <summary>{ "Details" }</summary>
+                <pre style="max-height: 10rem; overflow: auto;" >{ details }</pre>
+            </details>
+            }
+            if entry.toast.retry.is_some() {
+            <Button classes={classes!("is-small", "mt-2")} onclick={ctx.link().callback(move |_| Msg::Retry(id))} >
+                { "Retry" }
+            </Button>
+            }
+        </div>
+        }
+    }
+
+    fn on_show(&mut self, ctx: &Context<Self>, toast: Toast) -> bool {
+        let duplicate = self.toasts.values_mut().find(|entry| {
+            entry.toast.kind == toast.kind
+                && entry.toast.message == toast.message
+                && entry.toast.retry.is_none()
+                && toast.retry.is_none()
+        });
+
+        if let Some(entry) = duplicate {
+            entry.count += 1;
+
+            return true;
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        // Errors stay until dismissed
+        let _timeout = if toast.kind != ToastKind::Error {
+            let cb = ctx.link().callback(move |_| Msg::Dismiss(id));
+
+            Some(Timeout::new(TOAST_DURATION, move || cb.emit(())))
+        } else {
+            None
+        };
+
+        let entry = Entry {
+            toast,
+            count: 1,
+            _timeout,
+        };
+
+        self.toasts.insert(id, entry);
+        self.order.push(id);
+
+        true
+    }
+
+    fn on_dismiss(&mut self, id: usize) -> bool {
+        if self.toasts.remove(&id).is_none() {
+            return false;
+        }
+
+        self.order.retain(|item| *item != id);
+
+        true
+    }
+}
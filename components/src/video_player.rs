@@ -2,6 +2,7 @@
 
 use std::{
     collections::VecDeque,
+    rc::Rc,
     str::{self, FromStr},
 };
 
@@ -14,8 +15,6 @@ use gloo_console::{error, warn};
 #[cfg(debug_assertions)]
 use gloo_console::info;
 
-use ipfs_api::IpfsService;
-
 use linked_data::{
     channel::live::LiveSettings,
     media::video::{Setup, Track, Video},
@@ -24,7 +23,7 @@ use linked_data::{
 
 use serde::{Deserialize, Serialize};
 
-use utils::{ipfs::IPFSContext, seconds_to_timecode};
+use utils::{client::IpfsClient, ipfs::IPFSContext, seconds_to_timecode};
 
 use wasm_bindgen::{closure::Closure, JsCast, UnwrapThrowExt};
 
@@ -32,6 +31,7 @@ use web_sys::{HtmlMediaElement, MediaSource, MediaSourceReadyState, SourceBuffer
 
 use yew::{
     classes,
+    context::ContextHandle,
     platform::spawn_local,
     prelude::{html, Component, Html, Properties},
     Callback, Context,
@@ -88,7 +88,8 @@ pub struct Props {
 
 /// Video player for live streams and on demand.
 pub struct VideoPlayer {
-    ipfs: IpfsService,
+    ipfs: Rc<dyn IpfsClient>,
+    _context_handle: ContextHandle<IPFSContext>,
 
     player_type: Option<Either<LiveStream, Video>>,
 
@@ -114,6 +115,7 @@ pub struct VideoPlayer {
 }
 
 pub enum Msg {
+    Context(IPFSContext),
     Settings(Either<LiveSettings, Video>),
     SourceOpen,
     Seeking,
@@ -130,12 +132,12 @@ impl Component for VideoPlayer {
     type Properties = Props;
 
     fn create(ctx: &Context<Self>) -> Self {
-        let (context, _handle) = ctx
+        let (context, _context_handle) = ctx
             .link()
-            .context::<IPFSContext>(Callback::noop())
+            .context::<IPFSContext>(ctx.link().callback(Msg::Context))
             .expect_throw("IPFS Context");
 
-        let ipfs = context.client;
+        let ipfs = context.api;
 
         let ema = ExponentialMovingAverage::new();
 
@@ -151,6 +153,7 @@ impl Component for VideoPlayer {
 
         Self {
             ipfs,
+            _context_handle,
 
             player_type: None,
 
@@ -177,6 +180,7 @@ impl Component for VideoPlayer {
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
+            Msg::Context(context) => self.on_context(ctx, context),
             Msg::Settings(set) => return self.on_settigns(ctx, set),
             Msg::SourceOpen => self.on_source_open(ctx),
             Msg::Seeking => self.on_seeking(),
@@ -240,28 +244,7 @@ impl VideoPlayer {
 
         let this = match either {
             Either::Left(settings) => {
-                let (handle, regis) = AbortHandle::new_pair();
-
-                let pubsub_cb = ctx.link().callback(Msg::PubSub);
-
-                spawn_local({
-                    let ipfs = self.ipfs.clone();
-                    let cb = pubsub_cb.clone();
-                    let topic = settings.video_topic.clone();
-
-                    async move {
-                        let stream = ipfs.pubsub_sub(topic.into_bytes());
-
-                        let mut stream = Abortable::new(stream, regis).boxed_local();
-
-                        while let Some(result) = stream.next().await {
-                            match result {
-                                Ok(msg) => cb.emit((msg.from, msg.data)),
-                                Err(e) => error!(&format!("{:#?}", e)),
-                            }
-                        }
-                    }
-                });
+                let handle = self.subscribe(ctx, settings.video_topic.clone());
 
                 let live = LiveStream {
                     settings,
@@ -281,7 +264,7 @@ impl VideoPlayer {
                     let cid = metadata.video.link;
 
                     async move {
-                        match ipfs.dag_get::<&str, Setup>(cid, Some(SETUP_PATH)).await {
+                        match ipfs.dag_get::<Setup>(cid, Some(SETUP_PATH)).await {
                             Ok(setup) => cb.emit(setup),
                             Err(e) => error!(&format!("{:#?}", e)),
                         }
@@ -297,6 +280,58 @@ impl VideoPlayer {
         false
     }
 
+    /// Subscribe to the live stream video topic.
+    fn subscribe(&self, ctx: &Context<Self>, topic: String) -> AbortHandle {
+        let (handle, regis) = AbortHandle::new_pair();
+
+        spawn_local({
+            let ipfs = self.ipfs.clone();
+            let cb = ctx.link().callback(Msg::PubSub);
+
+            async move {
+                let stream = ipfs.pubsub_sub(topic);
+
+                let mut stream = Abortable::new(stream, regis).boxed_local();
+
+                while let Some(result) = stream.next().await {
+                    match result {
+                        Ok(msg) => cb.emit((msg.from, msg.data)),
+                        Err(e) => error!(&format!("{:#?}", e)),
+                    }
+                }
+            }
+        });
+
+        handle
+    }
+
+    /// Callback when the node reconnects, live streams resume their subscription.
+    fn on_context(&mut self, ctx: &Context<Self>, context: IPFSContext) {
+        #[cfg(debug_assertions)]
+        info!("On Context");
+
+        self.ipfs = context.api;
+
+        if context.is_read_only() {
+            return;
+        }
+
+        let topic = match &self.player_type {
+            Some(Either::Left(live)) => {
+                live.handle.abort();
+
+                live.settings.video_topic.clone()
+            }
+            _ => return,
+        };
+
+        let handle = self.subscribe(ctx, topic);
+
+        if let Some(Either::Left(live)) = &mut self.player_type {
+            live.handle = handle;
+        }
+    }
+
     /// Callback when MediaSource is linked to video element.
     fn on_source_open(&mut self, ctx: &Context<Self>) {
         #[cfg(debug_assertions)]
@@ -311,7 +346,7 @@ impl VideoPlayer {
             let cid = ctx.props().cid;
 
             async move {
-                match ipfs.dag_get::<&str, LiveOrVideo>(cid, Some("/link")).await {
+                match ipfs.dag_get::<LiveOrVideo>(cid, Some("/link")).await {
                     Ok(either) => cb.emit(either.inner),
                     Err(e) => {
                         error!(&format!("{:#?}", e));
@@ -371,7 +406,7 @@ impl VideoPlayer {
                 let cb = ctx.link().callback(Msg::SetupNode);
 
                 async move {
-                    match ipfs.dag_get::<&str, Setup>(cid, Some("/setup")).await {
+                    match ipfs.dag_get::<Setup>(cid, Some("/setup")).await {
                         Ok(setup) => cb.emit(setup),
                         Err(e) => error!(&format!("{:#?}", e)),
                     }
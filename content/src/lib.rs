@@ -1,6 +1,11 @@
 #![cfg(target_arch = "wasm32")]
 
-use std::collections::{HashMap, HashSet};
+mod thread;
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    rc::Rc,
+};
 
 use futures_util::{
     stream::{AbortHandle, AbortRegistration, Abortable},
@@ -8,11 +13,24 @@ use futures_util::{
 };
 
 use utils::{
-    commentary::CommentaryContext, defluencer::ChannelContext, ipfs::IPFSContext,
-    subscriptions::get_sub_list, timestamp_to_datetime,
+    client::{ClientError, IpfsClient},
+    commentary::{self, CommentaryContext},
+    defluencer::{ChannelContext, UserContext},
+    identities::IdentityStore,
+    ipfs::IPFSContext,
+    moderation::{self, Moderation},
+    mute::MuteList,
+    reactions::{self, ReactionContext, EMOJIS},
+    subscriptions::get_sub_list,
+    timestamp_to_datetime,
+    web3::Web3Context,
 };
 
-use yew::{platform::spawn_local, prelude::*};
+use web_sys::HtmlSelectElement;
+
+use yew::{context::ContextHandle, platform::spawn_local, prelude::*};
+
+use yew_router::{prelude::*, scope_ext::LocationHandle};
 
 use cid::Cid;
 
@@ -21,15 +39,28 @@ use gloo_console::error;
 #[cfg(debug_assertions)]
 use gloo_console::info;
 
-use components::pure::{Content, NavigationBar, Searching};
-
-use ipfs_api::IpfsService;
+use components::{
+    pure::{Content, NavigationBar, Searching},
+    toast::Toaster,
+    Route,
+};
 
 use defluencer::{crypto::signed_link::SignedLink, Defluencer};
 
-use linked_data::{identity::Identity, media::comments::Comment, media::Media, types::IPNSAddress};
+use linked_data::{
+    channel::ChannelMetadata,
+    identity::Identity,
+    media::comments::Comment,
+    media::Media,
+    types::{IPLDLink, IPNSAddress},
+};
+
+use ybc::{Button, Buttons, Container, Level, LevelItem, LevelLeft, LevelRight, Section, Size};
 
-use ybc::{Container, Section};
+use thread::{ContentQuery, Order, Thread};
+
+/// Number of replies shown at a time, per comment.
+const PAGE_SIZE: usize = 10;
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
@@ -40,6 +71,8 @@ pub struct Props {
 /// social.defluencer.eth/#/content/<CID_HERE>
 ///
 /// Page displaying specific content & comments
+///
+/// Link to a comment with #/content/<CID_HERE>?comment=<CID_HERE>
 pub struct ContentPage {
     media: Option<Media>,
 
@@ -50,11 +83,56 @@ pub struct ContentPage {
     indexes: HashSet<Cid>,
     crawl_handle: AbortHandle,
 
-    comment_cb: Callback<(Cid, Comment)>,
-    comments: HashMap<Cid, Comment>,
+    /// New comments sent to the author channel aggregation topic.
+    live_handle: Option<AbortHandle>,
+
+    signed_cb: Callback<(Cid, Comment, bool)>,
+    thread: Thread,
+
+    /// Comments signed by their identity wallet.
+    verified: HashSet<Cid>,
+
+    order: Order,
+
+    /// Replies below this depth are collapsed by default.
+    collapse_depth: usize,
+
+    /// Comments expanded or collapsed by the user.
+    toggled: HashSet<Cid>,
+
+    /// Number of replies shown per comment.
+    limits: HashMap<Cid, usize>,
+
+    _location_handle: Option<LocationHandle>,
+
+    /// Linked comment and its parents, always expanded.
+    target: Option<Cid>,
+    path: HashSet<Cid>,
+    scrolled: bool,
 
     identity_cb: Callback<(Cid, Identity)>,
-    identities: HashMap<Cid, Identity>,
+    identities: IdentityStore,
+
+    /// Muted comments and their replies are not displayed.
+    mutes: MuteList,
+
+    /// Comments hidden by the content owner, for every viewer.
+    moderation: Moderation,
+    show_hidden: bool,
+
+    toaster: Toaster,
+
+    /// Reactions of followed channels.
+    reaction_context: ReactionContext,
+    _reaction_handle: Option<ContextHandle<ReactionContext>>,
+
+    /// Signed reactions already checked, valid or not.
+    checked_reactions: HashSet<Cid>,
+
+    /// Signed reactions matching their index entry.
+    valid_reactions: HashSet<Cid>,
+
+    reacting: bool,
 
     commentary: CommentaryContext,
 }
@@ -63,7 +141,22 @@ pub enum Msg {
     Crawl(Cid),
     Media((Media, String)),
     Comment((Cid, Comment)),
+    Signed((Cid, Comment, bool)),
+    Live((Cid, Comment, bool)),
     Identity((Cid, Identity)),
+    Order(Order),
+    Depth(usize),
+    Toggle(Cid),
+    More(Cid),
+    Location(Location),
+    Moderation(Moderation),
+    ShowHidden,
+    Hide(Cid),
+    Block(IPLDLink),
+    Reactions(ReactionContext),
+    Reaction((Cid, Cid)),
+    React(String),
+    Reacted,
 }
 
 impl Component for ContentPage {
@@ -75,6 +168,10 @@ impl Component for ContentPage {
         info!("Content Page Create");
 
         let identity_cb = ctx.link().callback(Msg::Identity);
+        let identities = match ctx.link().context::<IdentityStore>(Callback::noop()) {
+            Some((store, _)) => store,
+            None => IdentityStore::new(),
+        };
 
         let mut subcriptions = get_sub_list();
 
@@ -86,13 +183,14 @@ impl Component for ContentPage {
         let (crawl_handle, regis) = AbortHandle::new_pair();
 
         let comment_cb = ctx.link().callback(Msg::Comment);
+        let signed_cb = ctx.link().callback(Msg::Signed);
 
         let commentary = CommentaryContext {
-            callback: comment_cb.clone(),
+            callback: comment_cb,
         };
 
         if let Some((context, _)) = ctx.link().context::<IPFSContext>(Callback::noop()) {
-            let ipfs = context.client;
+            let ipfs = context.api;
 
             spawn_local(get_content(
                 ipfs.clone(),
@@ -103,6 +201,33 @@ impl Component for ContentPage {
             spawn_local(web_crawl(ipfs, subcriptions, crawl_cb, regis));
         }
 
+        let _location_handle = ctx
+            .link()
+            .add_location_listener(ctx.link().callback(Msg::Location));
+
+        let target = ctx
+            .link()
+            .location()
+            .and_then(|location| location.query::<ContentQuery>().ok())
+            .and_then(|query| query.target());
+
+        let toaster = ctx
+            .link()
+            .context::<Toaster>(Callback::noop())
+            .map(|(toaster, _)| toaster)
+            .unwrap_or_default();
+
+        let (reaction_context, _reaction_handle) = match ctx
+            .link()
+            .context::<ReactionContext>(ctx.link().callback(Msg::Reactions))
+        {
+            Some((context, handle)) => (context, Some(handle)),
+            None => (ReactionContext::default(), None),
+        };
+
+        ctx.link()
+            .send_message(Msg::Reactions(reaction_context.clone()));
+
         Self {
             media: None,
             dt: String::new(),
@@ -111,11 +236,37 @@ impl Component for ContentPage {
             indexes: HashSet::default(),
             crawl_handle,
 
-            comment_cb,
-            comments: HashMap::default(),
+            live_handle: None,
+
+            signed_cb,
+            thread: Thread::default(),
+            verified: HashSet::default(),
+
+            order: Order::default(),
+            collapse_depth: commentary::get_collapse_depth(),
+            toggled: HashSet::default(),
+            limits: HashMap::default(),
+
+            _location_handle,
+            target,
+            path: HashSet::default(),
+            scrolled: false,
 
             identity_cb,
-            identities: HashMap::default(),
+            identities,
+
+            mutes: MuteList::load(),
+
+            moderation: Moderation::default(),
+            show_hidden: false,
+
+            toaster,
+
+            reaction_context,
+            _reaction_handle,
+            checked_reactions: HashSet::default(),
+            valid_reactions: HashSet::default(),
+            reacting: false,
 
             commentary,
         }
@@ -126,7 +277,7 @@ impl Component for ContentPage {
         info!("Content Page Update");
 
         let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.client,
+            Some((context, _)) => context.api,
             None => return false,
         };
 
@@ -140,20 +291,40 @@ impl Component for ContentPage {
                     ipfs,
                     index,
                     ctx.props().cid,
-                    self.comment_cb.clone(),
+                    self.signed_cb.clone(),
                 ));
 
                 false
             }
             Msg::Media((media, addr)) => {
-                if !self.identities.contains_key(&media.identity().link) {
-                    spawn_local(utils::r#async::dag_get(
-                        ipfs,
+                if !self.identities.contains(&media.identity().link) {
+                    self.identities.request(
+                        ipfs.clone(),
                         media.identity().link,
                         self.identity_cb.clone(),
+                    );
+                }
+
+                if self.live_handle.is_none() {
+                    let (handle, regis) = AbortHandle::new_pair();
+
+                    spawn_local(live_comments(
+                        ipfs.clone(),
+                        media.identity().link,
+                        ctx.link().callback(Msg::Live),
+                        regis,
                     ));
+
+                    self.live_handle = Some(handle);
                 }
 
+                spawn_local(get_moderation(
+                    ipfs,
+                    media.identity().link,
+                    addr.clone(),
+                    ctx.link().callback(Msg::Moderation),
+                ));
+
                 self.dt = timestamp_to_datetime(media.user_timestamp());
                 self.media = Some(media);
                 self.addr = addr;
@@ -161,7 +332,7 @@ impl Component for ContentPage {
                 true
             }
             Msg::Comment((cid, comment)) => {
-                if self.comments.contains_key(&cid) {
+                if self.thread.contains(&cid) {
                     return false;
                 }
 
@@ -170,23 +341,137 @@ impl Component for ContentPage {
                         ipfs.clone(),
                         *index,
                         cid,
-                        self.comment_cb.clone(),
+                        self.signed_cb.clone(),
                     ));
                 }
 
-                if !self.identities.contains_key(&comment.identity.link) {
-                    spawn_local(utils::r#async::dag_get(
-                        ipfs,
-                        comment.identity.link,
-                        self.identity_cb.clone(),
-                    ));
+                if !self.identities.contains(&comment.identity.link) {
+                    self.identities
+                        .request(ipfs, comment.identity.link, self.identity_cb.clone());
+                }
+
+                self.thread.insert(cid, comment);
+
+                if self.target.is_some() && self.path.is_empty() {
+                    self.update_path(ctx);
+                }
+
+                true
+            }
+            Msg::Signed((cid, comment, verified)) => {
+                if verified {
+                    self.verified.insert(cid);
                 }
 
-                self.comments.insert(cid, comment);
+                self.update(ctx, Msg::Comment((cid, comment)))
+            }
+            Msg::Live((cid, comment, verified)) => {
+                // The topic is shared by all the channel content
+                let in_thread = match comment.origin {
+                    Some(origin) => origin == ctx.props().cid || self.thread.contains(&origin),
+                    None => false,
+                };
+
+                if in_thread {
+                    ctx.link()
+                        .send_message(Msg::Signed((cid, comment, verified)));
+                }
+
+                false
+            }
+            Msg::Identity(_) => true,
+            Msg::Order(order) => {
+                self.order = order;
+
+                true
+            }
+            Msg::Depth(depth) => {
+                self.collapse_depth = depth;
+                self.toggled.clear();
+
+                commentary::set_collapse_depth(depth);
+
+                true
+            }
+            Msg::Toggle(cid) => {
+                if !self.toggled.remove(&cid) {
+                    self.toggled.insert(cid);
+                }
+
+                true
+            }
+            Msg::More(origin) => {
+                *self.limits.entry(origin).or_insert(PAGE_SIZE) += PAGE_SIZE;
+
+                true
+            }
+            Msg::Location(location) => {
+                let target = location
+                    .query::<ContentQuery>()
+                    .ok()
+                    .and_then(|query| query.target());
+
+                if target == self.target {
+                    return false;
+                }
+
+                self.target = target;
+                self.scrolled = false;
+                self.update_path(ctx);
+
+                true
+            }
+            Msg::Moderation(moderation) => {
+                self.moderation = moderation;
+
+                true
+            }
+            Msg::ShowHidden => {
+                self.show_hidden = !self.show_hidden;
+
+                true
+            }
+            Msg::Hide(cid) => {
+                self.moderation.toggle_hidden(cid);
+
+                self.publish_moderation(ctx, ipfs);
+
+                true
+            }
+            Msg::Block(identity) => {
+                self.moderation.toggle_blocked(identity);
+
+                self.publish_moderation(ctx, ipfs);
+
+                true
+            }
+            Msg::Reactions(context) => {
+                self.reaction_context = context;
+
+                self.check_reactions(ctx, ipfs);
+
+                true
+            }
+            Msg::Reaction((signed, identity)) => {
+                self.valid_reactions.insert(signed);
+
+                if !self.identities.contains(&identity) {
+                    self.identities
+                        .request(ipfs, identity, self.identity_cb.clone());
+                }
+
+                true
+            }
+            Msg::React(emoji) => {
+                self.reacting = self.react(ctx, ipfs, emoji);
+
+                self.reacting
+            }
+            Msg::Reacted => {
+                self.reacting = false;
 
                 true
             }
-            Msg::Identity((cid, identity)) => self.identities.insert(cid, identity).is_none(),
         }
     }
 
@@ -206,7 +491,7 @@ impl Component for ContentPage {
                     }
                 }
 
-                content = html! { <Content key={ctx.props().cid.to_string()} cid={ctx.props().cid} media={media.clone()} identity={identity.clone()} {verified} /> };
+                content = html! { <Content key={ctx.props().cid.to_string()} cid={ctx.props().cid} media={media.clone()} {identity} {verified} /> };
             }
         }
 
@@ -216,55 +501,451 @@ impl Component for ContentPage {
             <Section>
                 <Container>
                     { content }
+                    if self.media.is_some() {
+                        { self.render_reactions(ctx) }
+                    }
                 </Container>
             </Section>
             <Section>
                 <Container>
-                    { self.render_comments(ctx.props().cid)}
+                    { self.render_header(ctx) }
+                    { self.render_comments(ctx, ctx.props().cid, 1) }
                 </Container>
             </Section>
         </ContextProvider<CommentaryContext>>
         }
     }
 
+    fn rendered(&mut self, _ctx: &Context<Self>, _first_render: bool) {
+        if self.scrolled {
+            return;
+        }
+
+        let target = match self.target {
+            Some(target) => target,
+            None => return,
+        };
+
+        let element = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.get_element_by_id(&comment_id(&target)));
+
+        if let Some(element) = element {
+            element.scroll_into_view();
+            self.scrolled = true;
+        }
+    }
+
     fn destroy(&mut self, _ctx: &Context<Self>) {
         #[cfg(debug_assertions)]
         info!("Content Page Destroy");
 
         self.crawl_handle.abort();
+
+        if let Some(handle) = self.live_handle.take() {
+            handle.abort();
+        }
     }
 }
 
 impl ContentPage {
-    /// Recursively render all comments
-    fn render_comments(&self, origin: Cid) -> Html {
-        self.comments
+    /// Expand the linked comment parents.
+    fn update_path(&mut self, ctx: &Context<Self>) {
+        self.path = match self.target {
+            Some(target) => self
+                .thread
+                .path_to(ctx.props().cid, target)
+                .into_iter()
+                .collect(),
+            None => HashSet::default(),
+        };
+
+        for cid in self.path.iter() {
+            self.toggled.remove(cid);
+        }
+    }
+
+    /// Sign and publish the updated moderation list.
+    fn publish_moderation(&self, ctx: &Context<Self>, ipfs: Rc<dyn IpfsClient>) {
+        let channel = match ctx.link().context::<ChannelContext>(Callback::noop()) {
+            Some((context, _)) => context,
+            None => return,
+        };
+
+        let signer = match ctx.link().context::<Web3Context>(Callback::noop()) {
+            Some((context, _)) => context.signer,
+            None => return,
+        };
+
+        let moderation = self.moderation.clone();
+        let toaster = self.toaster.clone();
+
+        spawn_local(async move {
+            match moderation::publish(&ipfs, &channel, &signer, &moderation).await {
+                Ok(()) => toaster.success("Moderation list updated."),
+                Err(e) => toaster.error_with("Moderation list not updated.", e, None),
+            }
+        });
+    }
+
+    /// Verify new reactions to this content.
+    fn check_reactions(&mut self, ctx: &Context<Self>, ipfs: Rc<dyn IpfsClient>) {
+        let content = ctx.props().cid;
+
+        for (identity, emoji, signed) in self.reaction_context.reactions(&content) {
+            if !self.checked_reactions.insert(signed) {
+                continue;
+            }
+
+            spawn_local(verify_reaction(
+                ipfs.clone(),
+                signed,
+                identity,
+                content,
+                emoji,
+                ctx.link().callback(Msg::Reaction),
+            ));
+        }
+    }
+
+    /// Sign a reaction or remove it, then share the updated index with other pages.
+    ///
+    /// Returns false if the user cannot react.
+    fn react(&self, ctx: &Context<Self>, ipfs: Rc<dyn IpfsClient>, emoji: String) -> bool {
+        if self.reacting {
+            return false;
+        }
+
+        let user = match ctx.link().context::<UserContext>(Callback::noop()) {
+            Some((context, _)) => context.user,
+            None => return false,
+        };
+
+        let channel = match ctx.link().context::<ChannelContext>(Callback::noop()) {
+            Some((context, _)) => context,
+            None => return false,
+        };
+
+        let signer = match ctx.link().context::<Web3Context>(Callback::noop()) {
+            Some((context, _)) => context.signer,
+            None => return false,
+        };
+
+        let content = ctx.props().cid;
+        let callback = self.reaction_context.callback.clone();
+        let reacted_cb = ctx.link().callback(|_| Msg::Reacted);
+        let toaster = self.toaster.clone();
+
+        spawn_local(async move {
+            let identity = user.get_identity();
+
+            match reactions::toggle(&ipfs, &channel, &signer, identity, content, emoji).await {
+                Ok(index) => callback.emit((channel.channel.get_address(), identity, index)),
+                Err(e) => toaster.error_with("Reaction not published.", e, None),
+            }
+
+            reacted_cb.emit(());
+        });
+
+        true
+    }
+
+    /// Only the content author can moderate, from their own channel.
+    fn is_moderator(&self, ctx: &Context<Self>) -> bool {
+        let media = match self.media.as_ref() {
+            Some(media) => media,
+            None => return false,
+        };
+
+        let user = match ctx.link().context::<UserContext>(Callback::noop()) {
+            Some((context, _)) => context.user,
+            None => return false,
+        };
+
+        let channel = match ctx.link().context::<ChannelContext>(Callback::noop()) {
+            Some((context, _)) => context.channel,
+            None => return false,
+        };
+
+        let identity = match self.identities.get(&media.identity().link) {
+            Some(identity) => identity,
+            None => return false,
+        };
+
+        media.identity().link == user.get_identity()
+            && identity.ipns_addr == Some(channel.get_address())
+    }
+
+    /// Muted comments, hidden ones and those without identity yet are not displayed.
+    fn visible_identity(&self, cid: &Cid) -> Option<Identity> {
+        let comment = self.thread.get(cid)?;
+        let identity = self.identities.get(&comment.identity.link)?;
+
+        if !self.show_hidden && self.moderation.hides(*cid, comment) {
+            return None;
+        }
+
+        let media = Media::Comment(comment.clone());
+
+        if self.mutes.mutes_media(&media, Some(&identity)) {
+            return None;
+        }
+
+        Some(identity)
+    }
+
+    /// Visible replies, recursively.
+    fn reply_count(&self, origin: &Cid) -> usize {
+        self.thread
+            .replies(origin, self.order)
             .iter()
-            .filter_map(|(cid, comment)| {
-                let comment_origin = comment.origin?;
+            .filter(|cid| self.visible_identity(cid).is_some())
+            .map(|cid| 1 + self.reply_count(cid))
+            .sum()
+    }
 
-                if origin != comment_origin {
-                    return None;
+    fn is_expanded(&self, cid: &Cid, depth: usize) -> bool {
+        let default = depth < self.collapse_depth || self.path.contains(cid);
+
+        default != self.toggled.contains(cid)
+    }
+
+    fn render_header(&self, ctx: &Context<Self>) -> Html {
+        let count = self.reply_count(&ctx.props().cid);
+
+        let depth_cb = ctx.link().callback(|e: Event| {
+            let depth = e
+                .target_unchecked_into::<HtmlSelectElement>()
+                .value()
+                .parse()
+                .unwrap_or(commentary::DEFAULT_COLLAPSE_DEPTH);
+
+            Msg::Depth(depth)
+        });
+
+        html! {
+        <Level>
+            <LevelLeft>
+                <LevelItem>
+                    <span class="icon-text">
+                        <span class="icon"><i class="fa-solid fa-comments"></i></span>
+                        <span>{ format!("{} comments", count) }</span>
+                    </span>
+                </LevelItem>
+                if !self.moderation.hidden.is_empty() || !self.moderation.blocked.is_empty() {
+                <LevelItem>
+                    <Button size={Size::Small} classes={classes!("is-text")} selected={self.show_hidden} onclick={ctx.link().callback(|_| Msg::ShowHidden)} >
+                    if self.show_hidden {
+                        {"Hide moderated comments"}
+                    } else {
+                        {"Show moderated comments"}
+                    }
+                    </Button>
+                </LevelItem>
                 }
+            </LevelLeft>
+            <LevelRight>
+                <LevelItem>
+                    <Buttons classes={classes!("has-addons")} >
+                        <Button size={Size::Small} selected={self.order == Order::Newest} onclick={ctx.link().callback(|_| Msg::Order(Order::Newest))} >
+                            {"Newest"}
+                        </Button>
+                        <Button size={Size::Small} selected={self.order == Order::Oldest} onclick={ctx.link().callback(|_| Msg::Order(Order::Oldest))} >
+                            {"Oldest"}
+                        </Button>
+                    </Buttons>
+                </LevelItem>
+                <LevelItem>
+                    <div class="select is-small">
+                        <select onchange={depth_cb} >
+                        {
+                            (1..=10usize).map(|depth| html! {
+                                <option value={depth.to_string()} selected={self.collapse_depth == depth} >
+                                    { format!("Collapse at depth {}", depth) }
+                                </option>
+                            }).collect::<Html>()
+                        }
+                        </select>
+                    </div>
+                </LevelItem>
+            </LevelRight>
+        </Level>
+        }
+    }
+
+    /// Verified reactions of followed channels, per emoji.
+    fn render_reactions(&self, ctx: &Context<Self>) -> Html {
+        let own = ctx
+            .link()
+            .context::<UserContext>(Callback::noop())
+            .map(|(context, _)| context.user.get_identity());
+
+        let can_react = own.is_some()
+            && ctx
+                .link()
+                .context::<ChannelContext>(Callback::noop())
+                .is_some()
+            && ctx
+                .link()
+                .context::<Web3Context>(Callback::noop())
+                .is_some();
+
+        let mut groups: BTreeMap<String, Vec<Cid>> = BTreeMap::new();
+
+        for (identity, emoji, signed) in self.reaction_context.reactions(&ctx.props().cid) {
+            if self.valid_reactions.contains(&signed) {
+                groups.entry(emoji).or_default().push(identity);
+            }
+        }
 
-                let identity = self.identities.get(&comment.identity.link)?;
-                let identity = identity.clone();
+        let buttons = EMOJIS.iter().map(|emoji| {
+            let emoji = emoji.to_string();
 
-                let cid = *cid;
-                let media = Media::Comment(comment.clone());
+            let identities = groups.get(&emoji).cloned().unwrap_or_default();
 
-                return Some(html! {
-                    <components::pure::Content key={cid.to_string()} {cid} {media} {identity} >
-                        { self.render_comments(cid) }
-                    </components::pure::Content>
-                });
-            })
-            .collect::<Html>()
+            let selected = own.map_or(false, |own| identities.contains(&own));
+
+            let names = identities
+                .iter()
+                .filter_map(|cid| self.identities.get(cid))
+                .map(|identity| identity.name)
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let label = match identities.len() {
+                0 => emoji.clone(),
+                count => format!("{} {}", emoji, count),
+            };
+
+            let key = emoji.clone();
+            let onclick = ctx.link().callback(move |_| Msg::React(emoji.clone()));
+
+            html! {
+            <Button {key} size={Size::Small} {selected} disabled={!can_react || self.reacting} {onclick} >
+                <span title={names}>{ label }</span>
+            </Button>
+            }
+        }).collect::<Html>();
+
+        html! {
+        <Buttons>
+            { buttons }
+        </Buttons>
+        }
     }
+
+    /// Recursively render comments, top level comments are at depth 1.
+    fn render_comments(&self, ctx: &Context<Self>, origin: Cid, depth: usize) -> Html {
+        let replies: Vec<(Cid, Identity)> = self
+            .thread
+            .replies(&origin, self.order)
+            .into_iter()
+            .filter_map(|cid| Some((cid, self.visible_identity(&cid)?)))
+            .collect();
+
+        let limit = self.limits.get(&origin).copied().unwrap_or(PAGE_SIZE);
+        let remaining = replies.len().saturating_sub(limit);
+
+        let comments = replies
+            .into_iter()
+            .enumerate()
+            .filter(|(i, (cid, _))| *i < limit || self.path.contains(cid))
+            .map(|(_, (cid, identity))| self.render_comment(ctx, cid, identity, depth))
+            .collect::<Html>();
+
+        html! {
+        <>
+            { comments }
+            if remaining > 0 {
+                <Button size={Size::Small} classes={classes!("is-text")} onclick={ctx.link().callback(move |_| Msg::More(origin))} >
+                    { format!("Load more ({})", remaining) }
+                </Button>
+            }
+        </>
+        }
+    }
+
+    fn render_comment(
+        &self,
+        ctx: &Context<Self>,
+        cid: Cid,
+        identity: Identity,
+        depth: usize,
+    ) -> Html {
+        let comment = match self.thread.get(&cid) {
+            Some(comment) => comment.clone(),
+            None => return html! {},
+        };
+
+        let author = comment.identity;
+        let hidden = self.moderation.hides(cid, &comment);
+        let blocked = self.moderation.is_blocked(&author);
+        let moderator = self.is_moderator(ctx);
+
+        let media = Media::Comment(comment);
+
+        let count = self.reply_count(&cid);
+        let expanded = self.is_expanded(&cid, depth);
+
+        let mut classes = classes!();
+
+        if self.target == Some(cid) {
+            classes.push("has-background-light");
+        }
+
+        if hidden {
+            classes.push("has-text-grey-light");
+        }
+
+        let query = ContentQuery::new(cid);
+
+        html! {
+        <div key={cid.to_string()} id={comment_id(&cid)} class={classes} >
+            <Content {cid} {media} {identity} verified={self.verified.contains(&cid)} >
+                <Buttons>
+                    if count > 0 {
+                    <Button size={Size::Small} classes={classes!("is-text")} onclick={ctx.link().callback(move |_| Msg::Toggle(cid))} >
+                        <span class="icon is-small">
+                        if expanded {
+                            <i class="fa-solid fa-chevron-up"></i>
+                        } else {
+                            <i class="fa-solid fa-chevron-down"></i>
+                        }
+                        </span>
+                        <span>{ format!("{} {}", count, if count == 1 { "reply" } else { "replies" }) }</span>
+                    </Button>
+                    }
+                    <Link<Route, ContentQuery> classes={classes!("button", "is-small", "is-text")} to={Route::Content{ cid: ctx.props().cid }} query={Some(query)} >
+                        <span class="icon is-small"><i class="fa-solid fa-link"></i></span>
+                    </Link<Route, ContentQuery>>
+                    if moderator {
+                    <Button size={Size::Small} classes={classes!("is-text")} onclick={ctx.link().callback(move |_| Msg::Hide(cid))} >
+                        <span class="icon is-small"><i class="fa-solid fa-eye-slash"></i></span>
+                        <span>{ if self.moderation.hidden.contains(&cid.into()) { "Unhide" } else { "Hide" } }</span>
+                    </Button>
+                    <Button size={Size::Small} classes={classes!("is-text")} onclick={ctx.link().callback(move |_| Msg::Block(author))} >
+                        <span class="icon is-small"><i class="fa-solid fa-ban"></i></span>
+                        <span>{ if blocked { "Unblock author" } else { "Block author" } }</span>
+                    </Button>
+                    } else if hidden {
+                    <span class="tag is-light">{"Hidden by the author"}</span>
+                    }
+                </Buttons>
+                if expanded {
+                    { self.render_comments(ctx, cid, depth + 1) }
+                }
+            </Content>
+        </div>
+        }
+    }
+}
+
+fn comment_id(cid: &Cid) -> String {
+    format!("comment-{}", cid)
 }
 
-async fn get_content(ipfs: IpfsService, callback: Callback<(Media, String)>, cid: Cid) {
-    let signed_link = match ipfs.dag_get::<&str, SignedLink>(cid, None).await {
+async fn get_content(ipfs: Rc<dyn IpfsClient>, callback: Callback<(Media, String)>, cid: Cid) {
+    let signed_link = match utils::r#async::cached_dag_get::<SignedLink>(&*ipfs, cid, None).await {
         Ok(dag) => dag,
         Err(e) => {
             error!(&format!("{:#?}", e));
@@ -279,10 +960,26 @@ async fn get_content(ipfs: IpfsService, callback: Callback<(Media, String)>, cid
 
     let addr = signed_link.get_address();
 
-    let media = match ipfs
-        .dag_get::<&str, Media>(signed_link.link.link, None)
-        .await
-    {
+    let media =
+        match utils::r#async::cached_dag_get::<Media>(&*ipfs, signed_link.link.link, None).await {
+            Ok(dag) => dag,
+            Err(e) => {
+                error!(&format!("{:#?}", e));
+                return;
+            }
+        };
+
+    callback.emit((media, addr));
+}
+
+/// Moderation list of the content author channel.
+async fn get_moderation(
+    ipfs: Rc<dyn IpfsClient>,
+    identity: Cid,
+    owner: String,
+    callback: Callback<Moderation>,
+) {
+    let identity = match utils::r#async::cached_dag_get::<Identity>(&*ipfs, identity, None).await {
         Ok(dag) => dag,
         Err(e) => {
             error!(&format!("{:#?}", e));
@@ -290,19 +987,128 @@ async fn get_content(ipfs: IpfsService, callback: Callback<(Media, String)>, cid
         }
     };
 
-    callback.emit((media, addr));
+    let addr = match identity.ipns_addr {
+        Some(addr) => addr,
+        None => return,
+    };
+
+    match moderation::fetch(&ipfs, addr, &owner).await {
+        Ok(Some(moderation)) => callback.emit(moderation),
+        Ok(None) => {}
+        Err(e) => error!(&format!("{:#?}", e)),
+    }
+}
+
+/// Reactions not matching their index entry are dropped.
+async fn verify_reaction(
+    ipfs: Rc<dyn IpfsClient>,
+    signed: Cid,
+    identity: Cid,
+    content: Cid,
+    emoji: String,
+    callback: Callback<(Cid, Cid)>,
+) {
+    match reactions::verify(&*ipfs, signed, identity, content, &emoji).await {
+        Ok(true) => callback.emit((signed, identity)),
+        Ok(false) => error!("Reaction Verification Failed!"),
+        Err(e) => error!(&format!("{:#?}", e)),
+    }
+}
+
+/// Listen to the content author aggregation topic for new comments.
+async fn live_comments(
+    ipfs: Rc<dyn IpfsClient>,
+    identity: Cid,
+    callback: Callback<(Cid, Comment, bool)>,
+    regis: AbortRegistration,
+) {
+    let identity = match utils::r#async::cached_dag_get::<Identity>(&*ipfs, identity, None).await {
+        Ok(dag) => dag,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    };
+
+    let addr = match identity.ipns_addr {
+        Some(addr) => addr,
+        None => return,
+    };
+
+    let root = match ipfs.name_resolve(addr.into()).await {
+        Ok(cid) => cid,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    };
+
+    let topic = match ipfs.dag_get::<ChannelMetadata>(root, None).await {
+        Ok(meta) => match meta.agregation_channel {
+            Some(topic) => topic,
+            None => return,
+        },
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    };
+
+    let stream = Abortable::new(ipfs.pubsub_sub(topic), regis);
+
+    futures_util::pin_mut!(stream);
+
+    while let Some(result) = stream.next().await {
+        let msg = match result {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!(&format!("{:#?}", e));
+                continue;
+            }
+        };
+
+        let cid = match Cid::try_from(msg.data) {
+            Ok(cid) => cid,
+            Err(e) => {
+                error!(&format!("{:#?}", e));
+                continue;
+            }
+        };
+
+        spawn_local(validate_comment(ipfs.clone(), cid, callback.clone()));
+    }
+}
+
+/// Comments with forged signatures are dropped.
+async fn validate_comment(
+    ipfs: Rc<dyn IpfsClient>,
+    cid: Cid,
+    callback: Callback<(Cid, Comment, bool)>,
+) {
+    match get_signed_comment(&*ipfs, cid).await {
+        Ok(tuple) => callback.emit(tuple),
+        Err(e) => error!(&format!("{:#?}", e)),
+    }
+}
+
+/// Comment and whether it is signed by its identity wallet.
+async fn get_signed_comment(
+    ipfs: &dyn IpfsClient,
+    cid: Cid,
+) -> Result<(Cid, Comment, bool), ClientError> {
+    match utils::r#async::get_signed_media(ipfs, cid).await? {
+        (Media::Comment(comment), verified) => Ok((cid, comment, verified)),
+        _ => Err(ClientError::Decode(format!("{} is not a comment", cid))),
+    }
 }
 
 async fn web_crawl(
-    ipfs: IpfsService,
+    ipfs: Rc<dyn IpfsClient>,
     follows: HashSet<IPNSAddress>,
     callback: Callback<Cid>,
     regis: AbortRegistration,
 ) {
-    let defluencer = Defluencer::from(ipfs);
-
-    let stream = defluencer
-        .streaming_web_crawl(follows.into_iter())
+    let stream = utils::r#async::web_crawl(ipfs, follows)
         .try_filter_map(|(_, channel)| async move { Ok(channel.comment_index) });
 
     let stream = Abortable::new(stream, regis);
@@ -318,26 +1124,21 @@ async fn web_crawl(
 }
 
 async fn stream_comments(
-    ipfs: IpfsService,
+    ipfs: Rc<dyn IpfsClient>,
     index: Cid,
     content_cid: Cid,
-    callback: Callback<(Cid, Comment)>,
+    callback: Callback<(Cid, Comment, bool)>,
 ) {
-    let defluencer = Defluencer::from(ipfs.clone());
+    let defluencer = ipfs.service().map(Defluencer::from);
 
-    let stream = defluencer
-        .stream_content_comments(index.into(), content_cid)
-        .map_ok(|cid| {
-            let ipfs = ipfs.clone();
+    let stream =
+        utils::r#async::comment_stream(&ipfs, defluencer.as_ref(), index.into(), content_cid)
+            .map_ok(|cid| {
+                let ipfs = ipfs.clone();
 
-            async move {
-                match ipfs.dag_get::<&str, Comment>(cid, Some("/link")).await {
-                    Ok(dag) => Ok((cid, dag)),
-                    Err(e) => Err(e.into()),
-                }
-            }
-        })
-        .try_buffer_unordered(10);
+                async move { get_signed_comment(&*ipfs, cid).await }
+            })
+            .try_buffer_unordered(10);
 
     futures_util::pin_mut!(stream);
 
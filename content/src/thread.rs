@@ -0,0 +1,203 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::collections::HashMap;
+
+use cid::Cid;
+
+use linked_data::media::comments::Comment;
+
+use serde::{Deserialize, Serialize};
+
+/// Route::Content query, e.g. #/content/<CID>?comment=<CID>
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ContentQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl ContentQuery {
+    pub fn new(comment: Cid) -> Self {
+        Self {
+            comment: Some(comment.to_string()),
+        }
+    }
+
+    pub fn target(&self) -> Option<Cid> {
+        self.comment
+            .as_deref()
+            .and_then(|cid| Cid::try_from(cid).ok())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Order {
+    Newest,
+    Oldest,
+}
+
+impl Default for Order {
+    fn default() -> Self {
+        Self::Newest
+    }
+}
+
+/// Comments indexed by the CID they reply to.
+#[derive(Default)]
+pub struct Thread {
+    comments: HashMap<Cid, Comment>,
+
+    replies: HashMap<Cid, Vec<Cid>>,
+}
+
+impl Thread {
+    /// Returns false if already known or not a reply.
+    pub fn insert(&mut self, cid: Cid, comment: Comment) -> bool {
+        if self.comments.contains_key(&cid) {
+            return false;
+        }
+
+        let origin = match comment.origin {
+            Some(origin) => origin,
+            None => return false,
+        };
+
+        self.replies.entry(origin).or_default().push(cid);
+        self.comments.insert(cid, comment);
+
+        true
+    }
+
+    pub fn contains(&self, cid: &Cid) -> bool {
+        self.comments.contains_key(cid)
+    }
+
+    pub fn get(&self, cid: &Cid) -> Option<&Comment> {
+        self.comments.get(cid)
+    }
+
+    /// Direct replies, sorted by timestamp.
+    pub fn replies(&self, origin: &Cid, order: Order) -> Vec<Cid> {
+        let mut replies = self.replies.get(origin).cloned().unwrap_or_default();
+
+        replies.sort_unstable_by_key(|cid| (self.comments[cid].user_timestamp, cid.to_bytes()));
+
+        if order == Order::Newest {
+            replies.reverse();
+        }
+
+        replies
+    }
+
+    /// Comments from the root of the thread down to the target, both included.
+    ///
+    /// Empty if the target or one of its parent is not known yet.
+    pub fn path_to(&self, root: Cid, target: Cid) -> Vec<Cid> {
+        let mut path = vec![target];
+        let mut current = target;
+
+        while current != root {
+            let origin = match self
+                .comments
+                .get(&current)
+                .and_then(|comment| comment.origin)
+            {
+                Some(origin) => origin,
+                None => return Vec::default(),
+            };
+
+            // A comment cannot reply to itself but the data is untrusted
+            if path.contains(&origin) {
+                return Vec::default();
+            }
+
+            path.push(origin);
+            current = origin;
+        }
+
+        path.reverse();
+
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use multihash::{Code, MultihashDigest};
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn cid(seed: &str) -> Cid {
+        Cid::new_v1(0x55, Code::Sha2_256.digest(seed.as_bytes()))
+    }
+
+    fn comment(origin: Option<Cid>, user_timestamp: i64) -> Comment {
+        Comment {
+            identity: cid("identity").into(),
+            user_timestamp,
+            origin,
+            text: String::from("text"),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn replies_sorted_by_timestamp() {
+        let content = cid("content");
+        let (early, late) = (cid("early"), cid("late"));
+
+        let mut thread = Thread::default();
+        assert!(thread.insert(late, comment(Some(content), 20)));
+        assert!(thread.insert(early, comment(Some(content), 10)));
+
+        assert_eq!(thread.replies(&content, Order::Oldest), vec![early, late]);
+        assert_eq!(thread.replies(&content, Order::Newest), vec![late, early]);
+    }
+
+    #[wasm_bindgen_test]
+    fn insert_rejects_duplicates_and_orphans() {
+        let content = cid("content");
+        let reply = cid("reply");
+
+        let mut thread = Thread::default();
+        assert!(thread.insert(reply, comment(Some(content), 10)));
+        assert!(!thread.insert(reply, comment(Some(content), 10)));
+        assert!(!thread.insert(cid("orphan"), comment(None, 10)));
+
+        assert_eq!(thread.replies(&content, Order::Newest), vec![reply]);
+    }
+
+    #[wasm_bindgen_test]
+    fn nested_replies_path() {
+        let content = cid("content");
+        let (first, second, third) = (cid("first"), cid("second"), cid("third"));
+
+        let mut thread = Thread::default();
+        thread.insert(third, comment(Some(second), 30));
+        thread.insert(first, comment(Some(content), 10));
+        thread.insert(second, comment(Some(first), 20));
+
+        assert_eq!(thread.replies(&first, Order::Newest), vec![second]);
+        assert_eq!(
+            thread.path_to(content, third),
+            vec![content, first, second, third]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn path_to_unknown_parent_or_cycle() {
+        let content = cid("content");
+        let (a, b) = (cid("a"), cid("b"));
+
+        let mut thread = Thread::default();
+        thread.insert(b, comment(Some(cid("unknown")), 20));
+
+        assert!(thread.path_to(content, b).is_empty());
+
+        let mut thread = Thread::default();
+        thread.insert(a, comment(Some(b), 10));
+        thread.insert(b, comment(Some(a), 20));
+
+        assert!(thread.path_to(content, a).is_empty());
+    }
+}
@@ -0,0 +1,308 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use cid::Cid;
+
+use components::pure::{Followee, NavigationBar, Searching};
+
+#[cfg(debug_assertions)]
+use gloo_console::info;
+
+use linked_data::{channel::ChannelMetadata, identity::Identity, types::IPNSAddress};
+
+use utils::{client::IpfsClient, defluencer::ChannelContext, ipfs::IPFSContext};
+
+use ybc::{
+    Block, Button, Container, HeaderSize, Level, LevelItem, LevelLeft, LevelRight, Section, Title,
+};
+
+use yew::{platform::spawn_local, prelude::*};
+
+/// Maximum number of suggestions displayed.
+const MAX_SUGGESTIONS: usize = 50;
+
+/// A channel followed by some of your followees.
+struct Suggestion {
+    /// Identity CID
+    cid: Cid,
+
+    identity: Identity,
+
+    /// Your followees following this channel.
+    followed_by: HashSet<IPNSAddress>,
+}
+
+/// social.defluencer.eth/#/discover/
+///
+/// Suggest channels followed by the channels you follow.
+pub struct DiscoverPage {
+    subscriptions: HashSet<IPNSAddress>,
+
+    /// Your own channel is never suggested.
+    own_addr: Option<IPNSAddress>,
+
+    /// Followees crawled so far.
+    crawled: HashSet<IPNSAddress>,
+
+    /// All subscriptions were resolved.
+    channels_done: bool,
+
+    /// Followee crawls in flight.
+    pending: usize,
+
+    /// Keyed by channel address.
+    suggestions: HashMap<IPNSAddress, Suggestion>,
+}
+
+pub enum Msg {
+    Channel((IPNSAddress, Cid, ChannelMetadata)),
+    ChannelsDone,
+    Followees((IPNSAddress, HashMap<Cid, Identity>)),
+    Subscribe(IPNSAddress),
+}
+
+impl Component for DiscoverPage {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        #[cfg(debug_assertions)]
+        info!("Discover Page Create");
+
+        let subscriptions = utils::subscriptions::get_sub_list();
+
+        let own_addr = ctx
+            .link()
+            .context::<ChannelContext>(Callback::noop())
+            .map(|(context, _)| context.channel.get_address());
+
+        let channels_done = match ctx.link().context::<IPFSContext>(Callback::noop()) {
+            Some((context, _)) => {
+                let channel_cb = ctx.link().callback(Msg::Channel);
+                let done_cb = ctx.link().callback(|_| Msg::ChannelsDone);
+                let subscriptions = subscriptions.clone();
+
+                spawn_local(async move {
+                    utils::r#async::get_channels(context.api, channel_cb, subscriptions).await;
+
+                    done_cb.emit(());
+                });
+
+                false
+            }
+            None => true,
+        };
+
+        Self {
+            subscriptions,
+            own_addr,
+            crawled: HashSet::default(),
+            channels_done,
+            pending: 0,
+            suggestions: HashMap::default(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        #[cfg(debug_assertions)]
+        info!("Discover Page Update");
+
+        match msg {
+            Msg::Channel((addr, _, metadata)) => self.on_channel(ctx, addr, metadata),
+            Msg::ChannelsDone => {
+                self.channels_done = true;
+                self.pending == 0
+            }
+            Msg::Followees((addr, followees)) => self.on_followees(addr, followees),
+            Msg::Subscribe(addr) => self.on_subscribe(addr),
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        #[cfg(debug_assertions)]
+        info!("Discover Page View");
+
+        let content = if self.subscriptions.is_empty() {
+            html! {
+                <Title classes={classes!("has-text-centered")} size={HeaderSize::Is5} >
+                    {"Subscribe to a channel first, suggestions are based on who they follow."}
+                </Title>
+            }
+        } else if self.suggestions.is_empty() && !self.is_done() {
+            html! { <Searching /> }
+        } else if self.suggestions.is_empty() {
+            html! {
+                <Title classes={classes!("has-text-centered")} size={HeaderSize::Is5} >
+                    {"No suggestions, your subscriptions do not follow any other channel yet."}
+                </Title>
+            }
+        } else {
+            self.render_suggestions(ctx)
+        };
+
+        html! {
+        <>
+        <NavigationBar />
+        <Section>
+            <Container>
+                <Title size={HeaderSize::Is4} >
+                    {"Suggested Channels"}
+                </Title>
+                {content}
+            </Container>
+        </Section>
+        </>
+        }
+    }
+}
+
+impl DiscoverPage {
+    /// Crawl the followees of this followee.
+    fn on_channel(
+        &mut self,
+        ctx: &Context<Self>,
+        addr: IPNSAddress,
+        metadata: ChannelMetadata,
+    ) -> bool {
+        let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
+            Some((context, _)) => context.api,
+            None => return false,
+        };
+
+        if metadata.follows.is_none() || !self.crawled.insert(addr) {
+            return false;
+        }
+
+        spawn_local(get_followees(
+            ipfs,
+            addr,
+            metadata,
+            ctx.link().callback(Msg::Followees),
+        ));
+
+        self.pending += 1;
+
+        false
+    }
+
+    /// Every subscription and their followees were crawled.
+    fn is_done(&self) -> bool {
+        self.channels_done && self.pending == 0
+    }
+
+    fn on_followees(&mut self, addr: IPNSAddress, followees: HashMap<Cid, Identity>) -> bool {
+        self.pending -= 1;
+
+        // The last crawl may end the search without suggestions
+        let mut update = self.is_done();
+
+        for (cid, identity) in followees {
+            let channel = match identity.ipns_addr {
+                Some(channel) => channel,
+                None => continue,
+            };
+
+            if self.subscriptions.contains(&channel) || self.own_addr == Some(channel) {
+                continue;
+            }
+
+            let suggestion = self
+                .suggestions
+                .entry(channel)
+                .or_insert_with(|| Suggestion {
+                    cid,
+                    identity,
+                    followed_by: HashSet::default(),
+                });
+
+            update |= suggestion.followed_by.insert(addr);
+        }
+
+        update
+    }
+
+    fn on_subscribe(&mut self, addr: IPNSAddress) -> bool {
+        let mut list = utils::subscriptions::get_sub_list();
+
+        if !list.insert(addr) {
+            list.remove(&addr);
+        }
+
+        utils::subscriptions::set_sub_list(list);
+
+        true
+    }
+
+    /// Most followed first.
+    fn render_suggestions(&self, ctx: &Context<Self>) -> Html {
+        let mut ranked: Vec<_> = self.suggestions.iter().collect();
+
+        ranked.sort_unstable_by(|(_, a), (_, b)| {
+            b.followed_by
+                .len()
+                .cmp(&a.followed_by.len())
+                .then_with(|| a.identity.name.cmp(&b.identity.name))
+        });
+
+        // Read every time since subscribing here also changes it
+        let subscriptions = utils::subscriptions::get_sub_list();
+
+        ranked
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(addr, suggestion)| {
+                let addr = *addr;
+                let cid = suggestion.cid;
+                let identity = suggestion.identity.clone();
+
+                let subscribed = subscriptions.contains(&addr);
+                let count = suggestion.followed_by.len();
+
+                html! {
+                <Block key={addr.to_string()} >
+                    <Followee {cid} {identity} />
+                    <Level>
+                        <LevelLeft>
+                            <LevelItem>
+                                <small>
+                                {
+                                    if count == 1 {
+                                        "Followed by 1 of your subscriptions".to_owned()
+                                    } else {
+                                        format!("Followed by {} of your subscriptions", count)
+                                    }
+                                }
+                                </small>
+                            </LevelItem>
+                        </LevelLeft>
+                        <LevelRight>
+                            <LevelItem>
+                                <Button classes={classes!("is-small", "is-rounded")} onclick={ctx.link().callback(move |_| Msg::Subscribe(addr))} >
+                                {
+                                    if subscribed {"Unsubscribe"} else {"Subscribe"}
+                                }
+                                </Button>
+                            </LevelItem>
+                        </LevelRight>
+                    </Level>
+                </Block>
+                }
+            })
+            .collect::<Html>()
+    }
+}
+
+async fn get_followees(
+    ipfs: Rc<dyn IpfsClient>,
+    addr: IPNSAddress,
+    metadata: ChannelMetadata,
+    callback: Callback<(IPNSAddress, HashMap<Cid, Identity>)>,
+) {
+    let hash_map = utils::r#async::followees_identity(&*ipfs, &metadata).await;
+
+    callback.emit((addr, hash_map));
+}
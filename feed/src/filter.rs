@@ -0,0 +1,179 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{collections::HashSet, str::FromStr};
+
+use linked_data::{media::Media, types::IPNSAddress};
+
+use serde::{Deserialize, Serialize};
+
+use utils::date_to_timestamp;
+
+/// Seconds in a day.
+const DAY: i64 = 24 * 60 * 60;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum MediaType {
+    MicroPosts,
+    Articles,
+    Videos,
+    Comments,
+}
+
+impl MediaType {
+    pub const ALL: [Self; 4] = [
+        Self::MicroPosts,
+        Self::Articles,
+        Self::Videos,
+        Self::Comments,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MicroPosts => "micro",
+            Self::Articles => "articles",
+            Self::Videos => "videos",
+            Self::Comments => "comments",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::MicroPosts => "Micro Posts",
+            Self::Articles => "Articles",
+            Self::Videos => "Videos",
+            Self::Comments => "Comments",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::MicroPosts => "fa-solid fa-message",
+            Self::Articles => "fa-solid fa-newspaper",
+            Self::Videos => "fas fa-video",
+            Self::Comments => "fa-solid fa-comment",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|ty| ty.as_str() == s)
+    }
+
+    /// Comments without origin are micro posts.
+    fn of(media: &Media) -> Self {
+        match media {
+            Media::Blog(_) => Self::Articles,
+            Media::Video(_) => Self::Videos,
+            Media::Comment(comment) if comment.origin.is_none() => Self::MicroPosts,
+            Media::Comment(_) => Self::Comments,
+        }
+    }
+}
+
+/// Route::Feed query, lists are comma separated.
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Debug)]
+pub struct FeedQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub types: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channels: Option<String>,
+
+    /// YYYY-MM-DD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+
+    /// YYYY-MM-DD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+}
+
+/// Empty sets and missing dates match everything.
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct FeedFilter {
+    pub types: HashSet<MediaType>,
+    pub channels: HashSet<IPNSAddress>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl FeedFilter {
+    pub fn from_query(query: &FeedQuery) -> Self {
+        let types = query
+            .types
+            .iter()
+            .flat_map(|list| list.split(','))
+            .filter_map(MediaType::parse)
+            .collect();
+
+        let channels = query
+            .channels
+            .iter()
+            .flat_map(|list| list.split(','))
+            .filter_map(|addr| IPNSAddress::from_str(addr).ok())
+            .collect();
+
+        let from = query.from.clone().filter(|date| !date.is_empty());
+        let to = query.to.clone().filter(|date| !date.is_empty());
+
+        Self {
+            types,
+            channels,
+            from,
+            to,
+        }
+    }
+
+    pub fn to_query(&self) -> FeedQuery {
+        let mut types: Vec<_> = self.types.iter().map(|ty| ty.as_str()).collect();
+        types.sort_unstable();
+
+        let mut channels: Vec<_> = self.channels.iter().map(|addr| addr.to_string()).collect();
+        channels.sort_unstable();
+
+        FeedQuery {
+            types: (!types.is_empty()).then(|| types.join(",")),
+            channels: (!channels.is_empty()).then(|| channels.join(",")),
+            from: self.from.clone(),
+            to: self.to.clone(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+            && self.channels.is_empty()
+            && self.from.is_none()
+            && self.to.is_none()
+    }
+
+    /// Does this channel content pass the filter.
+    pub fn matches(&self, addr: &IPNSAddress, media: &Media) -> bool {
+        if !self.types.is_empty() && !self.types.contains(&MediaType::of(media)) {
+            return false;
+        }
+
+        if !self.includes_channel(addr) {
+            return false;
+        }
+
+        let timestamp = media.user_timestamp();
+
+        if let Some(from) = self.from.as_deref().and_then(date_to_timestamp) {
+            if timestamp < from {
+                return false;
+            }
+        }
+
+        // The end date is inclusive
+        if let Some(to) = self.to.as_deref().and_then(date_to_timestamp) {
+            if timestamp >= to + DAY {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    pub fn includes_channel(&self, addr: &IPNSAddress) -> bool {
+        self.channels.is_empty() || self.channels.contains(addr)
+    }
+}
@@ -1,102 +1,275 @@
 #![cfg(target_arch = "wasm32")]
 
-use std::collections::HashMap;
+mod filter;
+
+use std::{
+    cell::Cell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
 use cid::Cid;
 
-use components::pure::{NavigationBar, Searching, Thumbnail};
+use components::{
+    pure::{NavigationBar, Searching, Thumbnail},
+    Route,
+};
+
+use futures_util::stream::{AbortHandle, AbortRegistration};
+
+use gloo_console::error;
 
-use futures_util::stream::AbortHandle;
+use gloo_timers::future::TimeoutFuture;
 
 #[cfg(debug_assertions)]
 use gloo_console::info;
 
-use linked_data::{channel::ChannelMetadata, identity::Identity, media::Media, types::IPNSAddress};
+use linked_data::{
+    channel::ChannelMetadata,
+    identity::Identity,
+    media::Media,
+    types::{IPLDLink, IPNSAddress},
+};
+
+use utils::{
+    client::IpfsClient,
+    identities::IdentityStore,
+    ipfs::IPFSContext,
+    mute::MuteList,
+    r#async::{Pager, PagerRegistration},
+};
+
+use wasm_bindgen::{closure::Closure, JsCast};
+
+use web_sys::{Element, HtmlInputElement};
+
+use ybc::{Button, Buttons, Container, Control, Field, HeaderSize, Section, Size, Tabs, Title};
+
+use yew::{context::ContextHandle, platform::spawn_local, prelude::*};
+
+use yew_router::{prelude::*, scope_ext::LocationHandle};
+
+use filter::{FeedFilter, FeedQuery, MediaType};
 
-use utils::ipfs::IPFSContext;
+/// Number of items added to the feed at a time.
+const PAGE_SIZE: usize = 20;
 
-use ybc::{Container, HeaderSize, Section, Title};
+/// Pixels from the bottom of the page at which more items are loaded.
+const SCROLL_THRESHOLD: f64 = 800.0;
 
-use yew::{platform::spawn_local, prelude::*};
+/// Milliseconds between handled scroll events.
+const SCROLL_THROTTLE: u32 = 100;
 
-/// social.defluencer.eth/#/feed/
+/// Pixels above the viewport within which dropped items are rendered again.
+const RESTORE_MARGIN: f64 = 2000.0;
+
+/// Pixels above the viewport past which items are replaced by a spacer.
+const DROP_MARGIN: f64 = 2.0 * RESTORE_MARGIN;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// Subscription list name, all subscriptions if none.
+    #[prop_or_default]
+    pub list: Option<String>,
+}
+
+/// social.defluencer.eth/#/feed/ or social.defluencer.eth/#/feed/<LIST_NAME_HERE>
 ///
 /// The Personal Feed Page display all subcribed channel content
+///
+/// Filters are read from the query, e.g. #/feed?types=articles,videos&from=2022-01-31
 pub struct FeedPage {
+    /// Subscription list displayed.
+    list: Option<String>,
+
+    _context_handle: Option<ContextHandle<IPFSContext>>,
+
+    _location_handle: Option<LocationHandle>,
+
+    /// Filters are applied to loaded content, nothing is refetched.
+    filter: FeedFilter,
+
+    /// Muted content is loaded but never displayed.
+    mutes: MuteList,
+
+    channel_cb: Callback<(IPNSAddress, Cid, ChannelMetadata)>,
     latest_roots: HashMap<IPNSAddress, Cid>,
 
+    /// Channel identity used to display filter names.
+    channel_identities: HashMap<IPNSAddress, Cid>,
+
     sub_handles: HashMap<IPNSAddress, AbortHandle>,
     stream_handles: HashMap<IPNSAddress, AbortHandle>,
 
-    content_cb: Callback<(Cid, Media)>,
+    /// Content is fetched one page at a time per channel.
+    pagers: HashMap<IPNSAddress, Pager>,
+    loading: HashSet<IPNSAddress>,
+    exhausted: HashSet<IPNSAddress>,
+
     content: HashMap<Cid, Media>,
+    origins: HashMap<Cid, IPNSAddress>,
+
+    /// Content signed by its identity wallet.
+    verified: HashSet<Cid>,
+
+    /// Followees that shared content signed by someone else.
+    sharers: HashMap<Cid, Vec<IPNSAddress>>,
 
     /// Media Cid sorted by timestamps.
     content_order: Vec<Cid>,
 
+    /// Number of items loaded in the window, newest first.
+    limit: usize,
+
+    /// Items before this one are far above the viewport and replaced by a spacer.
+    first: usize,
+
+    /// Measured heights of the items replaced by the spacer, newest first.
+    dropped_heights: Vec<f64>,
+
+    list_ref: NodeRef,
+
+    /// Timestamps of the newest content seen on the previous visit, per channel.
+    last_seen: HashMap<IPNSAddress, i64>,
+
+    /// Timestamps of the newest content loaded, per channel.
+    newest: HashMap<IPNSAddress, i64>,
+
+    /// Changed to refresh the navbar unread counts.
+    navbar_key: usize,
+
+    scroll_closure: Option<Closure<dyn Fn()>>,
+
     identity_cb: Callback<(Cid, Identity)>,
-    identities: HashMap<Cid, Identity>,
+    identities: IdentityStore,
 }
 
 pub enum Msg {
+    Context(IPFSContext),
     Channel((IPNSAddress, Cid, ChannelMetadata)),
-    Content((Cid, Media)),
+    Content((IPNSAddress, Cid, Media, bool)),
+    Page((IPNSAddress, bool)),
     Identity((Cid, Identity)),
+    Scroll,
+    More,
+    Location(Location),
+    ToggleType(MediaType),
+    ToggleChannel(IPNSAddress),
+    From(String),
+    To(String),
+    ClearFilters,
+    MarkAllRead,
 }
 
 impl Component for FeedPage {
     type Message = Msg;
-    type Properties = ();
+    type Properties = Props;
 
     fn create(ctx: &Context<Self>) -> Self {
         #[cfg(debug_assertions)]
         info!("Feed Page Create");
 
-        let set = utils::subscriptions::get_sub_list();
-
         let channel_cb = ctx.link().callback(Msg::Channel);
 
-        let content_cb = ctx.link().callback(Msg::Content);
         let identity_cb = ctx.link().callback(Msg::Identity);
+        let identities = match ctx.link().context::<IdentityStore>(Callback::noop()) {
+            Some((store, _)) => store,
+            None => IdentityStore::new(),
+        };
 
-        let mut sub_handles = HashMap::with_capacity(set.len());
+        let (context, _context_handle) = match ctx
+            .link()
+            .context::<IPFSContext>(ctx.link().callback(Msg::Context))
+        {
+            Some((context, handle)) => (Some(context), Some(handle)),
+            None => (None, None),
+        };
 
-        if let Some((context, _)) = ctx.link().context::<IPFSContext>(Callback::noop()) {
-            let ipfs = context.client;
+        let _location_handle = ctx
+            .link()
+            .add_location_listener(ctx.link().callback(Msg::Location));
 
-            spawn_local(utils::r#async::get_channels(
-                ipfs.clone(),
-                channel_cb.clone(),
-                set.clone(),
-            ));
+        let filter = match ctx.link().location() {
+            Some(location) => filter_from_location(&location),
+            None => FeedFilter::default(),
+        };
 
-            for addr in set {
-                let (handle, regis) = AbortHandle::new_pair();
+        let mut page = Self {
+            list: ctx.props().list.clone(),
 
-                spawn_local(utils::r#async::channel_subscribe(
-                    ipfs.clone(),
-                    channel_cb.clone(),
-                    addr,
-                    regis,
-                ));
+            _context_handle,
 
-                sub_handles.insert(addr, handle);
-            }
-        }
+            _location_handle,
+            filter,
+            mutes: MuteList::load(),
 
-        Self {
+            channel_cb,
             latest_roots: Default::default(),
+            channel_identities: Default::default(),
 
-            sub_handles,
+            sub_handles: Default::default(),
             stream_handles: Default::default(),
 
-            content_cb,
+            pagers: Default::default(),
+            loading: Default::default(),
+            exhausted: Default::default(),
+
             content: Default::default(),
+            origins: Default::default(),
+            verified: Default::default(),
+            sharers: Default::default(),
             content_order: Default::default(),
 
+            limit: PAGE_SIZE,
+            first: 0,
+            dropped_heights: Default::default(),
+            list_ref: NodeRef::default(),
+
+            last_seen: utils::subscriptions::get_last_seen(),
+            newest: Default::default(),
+            navbar_key: 0,
+
+            scroll_closure: None,
+
             identity_cb,
-            identities: Default::default(),
+            identities,
+        };
+
+        if let Some(context) = context {
+            page.subscribe(context.api);
+        }
+
+        if let Some(window) = web_sys::window() {
+            let cb = ctx.link().callback(|_| Msg::Scroll);
+            let scheduled = Rc::new(Cell::new(false));
+
+            // Throttled, the position is read when the message is handled
+            let closure = Closure::wrap(Box::new(move || {
+                if scheduled.replace(true) {
+                    return;
+                }
+
+                let cb = cb.clone();
+                let scheduled = scheduled.clone();
+
+                spawn_local(async move {
+                    TimeoutFuture::new(SCROLL_THROTTLE).await;
+
+                    scheduled.set(false);
+                    cb.emit(());
+                });
+            }) as Box<dyn Fn()>);
+
+            if let Err(e) =
+                window.add_event_listener_with_callback("scroll", closure.as_ref().unchecked_ref())
+            {
+                error!(&format!("{:#?}", e));
+            }
+
+            page.scroll_closure = Some(closure);
         }
+
+        page
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
@@ -104,24 +277,70 @@ impl Component for FeedPage {
         info!("Feed Page Update");
 
         match msg {
+            Msg::Context(context) => self.on_context(context),
             Msg::Channel((addr, cid, meta)) => self.on_channel_update(ctx, addr, cid, meta),
-            Msg::Content((cid, media)) => self.on_content_discovered(ctx, cid, media),
-            Msg::Identity((cid, identity)) => self.identities.insert(cid, identity).is_none(),
+            Msg::Content((addr, cid, media, verified)) => {
+                self.on_content_discovered(ctx, addr, cid, media, verified)
+            }
+            Msg::Page((addr, more)) => self.on_page(addr, more),
+            Msg::Identity(_) => true,
+            Msg::Scroll => self.on_scroll(),
+            Msg::More => self.on_more(),
+            Msg::Location(location) => self.on_filter(filter_from_location(&location)),
+            Msg::ToggleType(ty) => {
+                let mut filter = self.filter.clone();
+
+                if !filter.types.remove(&ty) {
+                    filter.types.insert(ty);
+                }
+
+                navigate(ctx, &filter)
+            }
+            Msg::ToggleChannel(addr) => {
+                let mut filter = self.filter.clone();
+
+                if !filter.channels.remove(&addr) {
+                    filter.channels.insert(addr);
+                }
+
+                navigate(ctx, &filter)
+            }
+            Msg::From(date) => {
+                let mut filter = self.filter.clone();
+                filter.from = (!date.is_empty()).then_some(date);
+
+                navigate(ctx, &filter)
+            }
+            Msg::To(date) => {
+                let mut filter = self.filter.clone();
+                filter.to = (!date.is_empty()).then_some(date);
+
+                navigate(ctx, &filter)
+            }
+            Msg::ClearFilters => navigate(ctx, &FeedFilter::default()),
+            Msg::MarkAllRead => self.on_mark_all_read(),
         }
     }
 
-    fn view(&self, _ctx: &Context<Self>) -> Html {
+    fn view(&self, ctx: &Context<Self>) -> Html {
         #[cfg(debug_assertions)]
         info!("Feed Page View");
 
         if self.sub_handles.is_empty() {
             return html! {
             <>
-            <NavigationBar />
+            <NavigationBar key={self.navbar_key} />
             <Section>
                 <Container>
+                    { self.render_lists() }
                     <Title classes={classes!("has-text-centered")} size={HeaderSize::Is5} >
-                        {"Subscribe to your favorite channel to build your content feed."}
+                    {
+                        if self.list.is_some() {
+                            "This list is empty, add channels to it from their page."
+                        } else {
+                            "Subscribe to your favorite channel to build your content feed."
+                        }
+                    }
                     </Title>
                 </Container>
             </Section>
@@ -132,7 +351,7 @@ impl Component for FeedPage {
         if self.content_order.is_empty() {
             return html! {
             <>
-            <NavigationBar />
+            <NavigationBar key={self.navbar_key} />
             <Section>
                 <Container>
                     <Searching />
@@ -144,25 +363,24 @@ impl Component for FeedPage {
 
         html! {
         <>
-        <NavigationBar />
+        <NavigationBar key={self.navbar_key} />
         <Section>
             <Container>
-            {
-                self.content_order.iter().rev().filter_map(|&cid| {
-                    let media = match self.content.get(&cid) {
-                        Some(media) => media.clone(),
-                        None => return None,
-                    };
-
-                    let identity = match self.identities.get(&media.identity().link) {
-                        Some(id) => id.clone(),
-                        None => return None,
-                    };
-
-                    Some(html! {
-                        <Thumbnail key={cid.to_string()} {cid} {media} {identity} />
-                    })
-                }).collect::<Html>()
+            { self.render_lists() }
+            { self.render_filters(ctx) }
+            if self.unread_count() > 0 {
+                <Button classes={classes!("is-small", "mb-4")} onclick={ctx.link().callback(|_| Msg::MarkAllRead)} >
+                    <span class="icon-text">
+                        <span class="icon"><i class="fa-solid fa-check-double"></i></span>
+                        <span>{ format!("Mark all read ({})", self.unread_count()) }</span>
+                    </span>
+                </Button>
+            }
+            { self.render_content() }
+            if self.has_more() {
+                <Button classes={classes!("is-fullwidth", (!self.loading.is_empty()).then_some("is-loading"))} onclick={ctx.link().callback(|_| Msg::More)} >
+                    { "Load more" }
+                </Button>
             }
             </Container>
         </Section>
@@ -178,10 +396,80 @@ impl Component for FeedPage {
             sub.abort();
             stream.abort();
         }
+
+        // Content loaded but never shown stays unread
+        let shown = self.newest_shown();
+
+        utils::subscriptions::update_last_seen(&shown);
+
+        if shown == self.newest {
+            self.reset_unread_counts();
+        }
+
+        if let (Some(window), Some(closure)) = (web_sys::window(), self.scroll_closure.take()) {
+            if let Err(e) = window
+                .remove_event_listener_with_callback("scroll", closure.as_ref().unchecked_ref())
+            {
+                error!(&format!("{:#?}", e));
+            }
+        }
     }
 }
 
 impl FeedPage {
+    /// Fetch then subscribe to every followed channel.
+    fn subscribe(&mut self, ipfs: Rc<dyn IpfsClient>) {
+        let set = match self.list.as_deref() {
+            Some(name) => utils::subscriptions::get_list(name),
+            None => utils::subscriptions::get_sub_list(),
+        };
+
+        spawn_local(utils::r#async::get_channels(
+            ipfs.clone(),
+            self.channel_cb.clone(),
+            set.clone(),
+        ));
+
+        for addr in set {
+            let (handle, regis) = AbortHandle::new_pair();
+
+            spawn_local(utils::r#async::channel_subscribe(
+                ipfs.clone(),
+                self.channel_cb.clone(),
+                addr,
+                regis,
+            ));
+
+            if let Some(handle) = self.sub_handles.insert(addr, handle) {
+                handle.abort();
+            }
+        }
+    }
+
+    /// Resume subscriptions and streams when the node comes back online.
+    fn on_context(&mut self, context: IPFSContext) -> bool {
+        if context.is_read_only() {
+            return false;
+        }
+
+        #[cfg(debug_assertions)]
+        info!("Feed Page Reconnected");
+
+        for handle in self.stream_handles.values() {
+            handle.abort();
+        }
+
+        self.stream_handles.clear();
+        self.latest_roots.clear();
+        self.pagers.clear();
+        self.loading.clear();
+        self.exhausted.clear();
+
+        self.subscribe(context.api);
+
+        false
+    }
+
     fn on_channel_update(
         &mut self,
         ctx: &Context<Self>,
@@ -190,7 +478,7 @@ impl FeedPage {
         metadata: ChannelMetadata,
     ) -> bool {
         let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.client,
+            Some((context, _)) => context.api,
             None => return false,
         };
 
@@ -200,24 +488,26 @@ impl FeedPage {
 
         if let Some(index) = metadata.content_index {
             let (handle, regis) = AbortHandle::new_pair();
+            let (pager, pager_regis) = Pager::new_pair();
 
-            spawn_local(utils::r#async::stream_content(
-                ipfs.clone(),
-                self.content_cb.clone(),
-                index,
-                regis,
-            ));
+            self.stream_content(ctx, ipfs.clone(), addr, index, regis, pager_regis);
 
             if let Some(handle) = self.stream_handles.insert(addr, handle) {
                 handle.abort();
             }
 
-            if !self.identities.contains_key(&metadata.identity.link) {
-                spawn_local(utils::r#async::dag_get(
-                    ipfs,
-                    metadata.identity.link,
-                    self.identity_cb.clone(),
-                ));
+            // Restart from the newest content, already known items are skipped
+            pager.request(PAGE_SIZE);
+
+            self.pagers.insert(addr, pager);
+            self.loading.insert(addr);
+            self.exhausted.remove(&addr);
+
+            self.channel_identities.insert(addr, metadata.identity.link);
+
+            if !self.identities.contains(&metadata.identity.link) {
+                self.identities
+                    .request(ipfs, metadata.identity.link, self.identity_cb.clone());
             }
         }
 
@@ -226,22 +516,60 @@ impl FeedPage {
         false
     }
 
-    fn on_content_discovered(&mut self, ctx: &Context<Self>, cid: Cid, media: Media) -> bool {
+    fn stream_content(
+        &self,
+        ctx: &Context<Self>,
+        ipfs: Rc<dyn IpfsClient>,
+        addr: IPNSAddress,
+        index: IPLDLink,
+        regis: AbortRegistration,
+        pager: PagerRegistration,
+    ) {
+        let content_cb = ctx
+            .link()
+            .callback(move |(cid, media, verified)| Msg::Content((addr, cid, media, verified)));
+        let page_cb = ctx.link().callback(move |more| Msg::Page((addr, more)));
+
+        spawn_local(utils::r#async::stream_content_paged(
+            ipfs, content_cb, page_cb, index, regis, pager,
+        ));
+    }
+
+    fn on_content_discovered(
+        &mut self,
+        ctx: &Context<Self>,
+        addr: IPNSAddress,
+        cid: Cid,
+        media: Media,
+        verified: bool,
+    ) -> bool {
         let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.client,
+            Some((context, _)) => context.api,
             None => return false,
         };
 
+        // Content signed by someone else was shared by this channel
+        let shared = self
+            .channel_identities
+            .get(&addr)
+            .map_or(false, |identity| *identity != media.identity().link);
+
+        if shared {
+            let sharers = self.sharers.entry(cid).or_default();
+
+            if !sharers.contains(&addr) {
+                sharers.push(addr);
+            }
+        }
+
+        // Content shared by many is displayed once
         if self.content.contains_key(&cid) {
-            return false;
+            return shared;
         }
 
-        if !self.identities.contains_key(&media.identity().link) {
-            spawn_local(utils::r#async::dag_get(
-                ipfs,
-                media.identity().link,
-                self.identity_cb.clone(),
-            ));
+        if !self.identities.contains(&media.identity().link) {
+            self.identities
+                .request(ipfs, media.identity().link, self.identity_cb.clone());
         }
 
         let index = self
@@ -253,9 +581,456 @@ impl FeedPage {
             })
             .unwrap_or_else(|x| x);
 
+        let visible = self.filter.matches(&addr, &media);
+
+        let newest = self.newest.entry(addr).or_insert(i64::MIN);
+        *newest = (*newest).max(media.user_timestamp());
+
         self.content_order.insert(index, cid);
         self.content.insert(cid, media);
+        self.origins.insert(cid, addr);
+
+        if verified {
+            self.verified.insert(cid);
+        }
+
+        visible
+    }
+
+    fn on_page(&mut self, addr: IPNSAddress, more: bool) -> bool {
+        self.loading.remove(&addr);
+
+        if !more {
+            self.exhausted.insert(addr);
+        }
+
+        self.request_pages();
+
+        true
+    }
+
+    fn on_scroll(&mut self) -> bool {
+        let window = match web_sys::window() {
+            Some(window) => window,
+            None => return false,
+        };
+
+        let moved = self.move_window();
+
+        let height = match window.document().and_then(|doc| doc.body()) {
+            Some(body) => body.offset_height() as f64,
+            None => return false,
+        };
+
+        let bottom = window
+            .inner_height()
+            .ok()
+            .and_then(|h| h.as_f64())
+            .unwrap_or_default()
+            + window.scroll_y().unwrap_or_default();
+
+        if height - bottom > SCROLL_THRESHOLD {
+            return moved;
+        }
+
+        self.on_more() || moved
+    }
+
+    /// Replace items far above the viewport by a spacer, render them again when scrolling back up.
+    fn move_window(&mut self) -> bool {
+        let list = match self.list_ref.cast::<Element>() {
+            Some(list) => list,
+            None => return false,
+        };
+
+        let children = list.children();
+
+        // The spacer then one wrapper per rendered item
+        let tops: Vec<f64> = (0..children.length())
+            .filter_map(|i| children.item(i))
+            .map(|child| child.get_bounding_client_rect().top())
+            .collect();
+
+        if tops.len() < 2 {
+            return false;
+        }
+
+        let mut top = tops[1];
+        let mut restored = false;
+
+        while top > -RESTORE_MARGIN {
+            let height = match self.dropped_heights.pop() {
+                Some(height) => height,
+                None => break,
+            };
+
+            top -= height;
+            self.first -= 1;
+            restored = true;
+        }
+
+        if restored {
+            return true;
+        }
+
+        let rendered = tops.len() - 1;
+        let mut dropped = 0;
+
+        // Items are measured from their top to the next one, keeping a page rendered
+        while dropped + PAGE_SIZE < rendered && tops[dropped + 2] < -DROP_MARGIN {
+            self.dropped_heights
+                .push(tops[dropped + 2] - tops[dropped + 1]);
+            dropped += 1;
+        }
+
+        self.first += dropped;
+
+        dropped > 0
+    }
+
+    fn reset_window(&mut self) {
+        self.limit = PAGE_SIZE;
+        self.first = 0;
+        self.dropped_heights.clear();
+    }
+
+    fn on_more(&mut self) -> bool {
+        // Wait for the window to fill before growing it
+        if self.visible().count() < self.limit {
+            return false;
+        }
+
+        self.limit += PAGE_SIZE;
+
+        self.request_pages();
+
+        true
+    }
+
+    /// Channels with less than a page of content below the window fetch the next one.
+    ///
+    /// Streams of channels with enough content buffered stay paused.
+    fn request_pages(&mut self) {
+        let mut buffered: HashMap<IPNSAddress, usize> = HashMap::with_capacity(self.pagers.len());
+
+        for cid in self.visible().skip(self.limit) {
+            if let Some(addr) = self.origins.get(cid) {
+                *buffered.entry(*addr).or_default() += 1;
+            }
+        }
+
+        for (addr, pager) in self.pagers.iter() {
+            if self.loading.contains(addr) || self.exhausted.contains(addr) {
+                continue;
+            }
+
+            if !self.filter.includes_channel(addr) {
+                continue;
+            }
+
+            if buffered.get(addr).copied().unwrap_or_default() >= PAGE_SIZE {
+                continue;
+            }
+
+            pager.request(PAGE_SIZE);
+
+            self.loading.insert(*addr);
+        }
+    }
+
+    fn has_more(&self) -> bool {
+        self.visible().nth(self.limit).is_some()
+            || self
+                .pagers
+                .keys()
+                .any(|addr| self.filter.includes_channel(addr) && !self.exhausted.contains(addr))
+    }
+
+    /// Content passing the filters, newest first.
+    fn visible(&self) -> impl Iterator<Item = &Cid> {
+        self.content_order.iter().rev().filter(|cid| {
+            let media = match self.content.get(cid) {
+                Some(media) => media,
+                None => return false,
+            };
+
+            let identity = self.identities.get(&media.identity().link);
+
+            if self.mutes.mutes_media(media, identity.as_ref()) {
+                return false;
+            }
+
+            self.sources(cid)
+                .any(|addr| !self.mutes.mutes_channel(addr) && self.filter.matches(addr, media))
+        })
+    }
+
+    /// Channels this content was received from.
+    fn sources<'a>(&'a self, cid: &Cid) -> impl Iterator<Item = &'a IPNSAddress> {
+        self.origins
+            .get(cid)
+            .into_iter()
+            .chain(self.sharers.get(cid).into_iter().flatten())
+    }
+
+    /// Content is new if published after the newest content seen on the previous visit.
+    fn is_unread(&self, cid: &Cid) -> bool {
+        match (self.origins.get(cid), self.content.get(cid)) {
+            (Some(addr), Some(media)) => match self.last_seen.get(addr) {
+                Some(timestamp) => media.user_timestamp() > *timestamp,
+                None => true,
+            },
+            _ => false,
+        }
+    }
+
+    /// Timestamps of the newest content shown, per channel.
+    fn newest_shown(&self) -> HashMap<IPNSAddress, i64> {
+        let mut newest = HashMap::with_capacity(self.newest.len());
+
+        for cid in self.visible().take(self.limit) {
+            if let (Some(addr), Some(media)) = (self.origins.get(cid), self.content.get(cid)) {
+                let timestamp = newest.entry(*addr).or_insert(i64::MIN);
+                *timestamp = (*timestamp).max(media.user_timestamp());
+            }
+        }
+
+        newest
+    }
+
+    /// First read item after the newest unread one, where the last visit divider goes.
+    fn last_visit(&self) -> Option<Cid> {
+        self.visible()
+            .take(self.limit)
+            .skip_while(|cid| !self.is_unread(cid))
+            .find(|cid| !self.is_unread(cid))
+            .copied()
+    }
+
+    fn unread_count(&self) -> usize {
+        self.visible().filter(|cid| self.is_unread(cid)).count()
+    }
+
+    fn on_mark_all_read(&mut self) -> bool {
+        self.last_seen = utils::subscriptions::update_last_seen(&self.newest);
+        self.reset_unread_counts();
+
+        self.navbar_key += 1;
 
         true
     }
+
+    fn reset_unread_counts(&self) {
+        if self.list.is_none() {
+            utils::subscriptions::clear_unread_counts();
+        } else {
+            // Followees outside this list may still have unread content
+            utils::subscriptions::set_unread_counts(&Default::default());
+        }
+    }
+
+    /// Thumbnails with a divider at the last visit point.
+    ///
+    /// Items above the window are replaced by a spacer of the same height.
+    fn render_content(&self) -> Html {
+        let mut items = Vec::with_capacity(self.limit + 1 - self.first);
+
+        let last_visit = self.last_visit();
+        let spacer: f64 = self.dropped_heights.iter().sum();
+
+        items.push(html! {
+            <div key={"spacer"} style={format!("height: {}px", spacer)} />
+        });
+
+        for &cid in self.visible().take(self.limit).skip(self.first) {
+            let divider = last_visit == Some(cid);
+
+            // Wrapped so that each rendered item is one child of the list
+            items.push(html! {
+                <div key={cid.to_string()} >
+                if divider {
+                    <div class="is-flex is-align-items-center my-4">
+                        <hr class="is-flex-grow-1 my-0" />
+                        <span class="tag is-info is-light mx-2">{ "Last visit" }</span>
+                        <hr class="is-flex-grow-1 my-0" />
+                    </div>
+                }
+                { self.render_thumbnail(cid) }
+                </div>
+            });
+        }
+
+        html! {
+            <div ref={self.list_ref.clone()} >
+                { items.into_iter().collect::<Html>() }
+            </div>
+        }
+    }
+
+    fn render_thumbnail(&self, cid: Cid) -> Html {
+        let media = match self.content.get(&cid) {
+            Some(media) => media.clone(),
+            None => return html! {},
+        };
+
+        let identity = match self.identities.get(&media.identity().link) {
+            Some(id) => id,
+            None => return html! {},
+        };
+
+        let shared_by = self
+            .sharers
+            .get(&cid)
+            .into_iter()
+            .flatten()
+            .filter(|addr| !self.mutes.mutes_channel(addr))
+            .filter_map(|addr| self.channel_identities.get(addr))
+            .filter_map(|cid| self.identities.get(cid))
+            .collect::<Vec<_>>();
+
+        let verified = self.verified.contains(&cid);
+
+        html! {
+            <Thumbnail {cid} {media} {identity} {shared_by} {verified} />
+        }
+    }
+
+    fn on_filter(&mut self, filter: FeedFilter) -> bool {
+        if self.filter == filter {
+            return false;
+        }
+
+        self.filter = filter;
+        self.reset_window();
+
+        self.request_pages();
+
+        true
+    }
+
+    /// Tabs for every subscription list.
+    fn render_lists(&self) -> Html {
+        let lists = utils::subscriptions::get_lists();
+
+        if lists.len() < 2 {
+            return html! {};
+        }
+
+        html! {
+        <Tabs size={Size::Small} >
+            <li class={classes!(self.list.is_none().then_some("is-active"))} >
+                <Link<Route> to={Route::Feed} >{ "All" }</Link<Route>>
+            </li>
+            {
+                lists.into_keys().map(|name| {
+                    let class = (self.list.as_ref() == Some(&name)).then_some("is-active");
+
+                    html! {
+                    <li key={name.clone()} class={classes!(class)} >
+                        <Link<Route> to={Route::FeedList { list: name.clone() }} >{ name }</Link<Route>>
+                    </li>
+                    }
+                }).collect::<Html>()
+            }
+        </Tabs>
+        }
+    }
+
+    fn render_filters(&self, ctx: &Context<Self>) -> Html {
+        let mut channels: Vec<(IPNSAddress, String)> = self
+            .channel_identities
+            .iter()
+            .map(|(addr, cid)| {
+                let name = match self.identities.get(cid) {
+                    Some(identity) => identity.name,
+                    None => addr.to_string(),
+                };
+
+                (*addr, name)
+            })
+            .collect();
+
+        channels.sort_unstable_by(|(_, a), (_, b)| a.cmp(b));
+
+        let from_cb = ctx
+            .link()
+            .callback(|e: Event| Msg::From(e.target_unchecked_into::<HtmlInputElement>().value()));
+        let to_cb = ctx
+            .link()
+            .callback(|e: Event| Msg::To(e.target_unchecked_into::<HtmlInputElement>().value()));
+
+        html! {
+        <ybc::Box>
+            <Buttons>
+            {
+                MediaType::ALL.into_iter().map(|ty| {
+                    let class = self.filter.types.contains(&ty).then_some("is-info");
+
+                    html! {
+                    <Button classes={classes!("is-small", class)} onclick={ctx.link().callback(move |_| Msg::ToggleType(ty))} >
+                        <span class="icon-text">
+                            <span class="icon"><i class={ty.icon()}></i></span>
+                            <span>{ ty.label() }</span>
+                        </span>
+                    </Button>
+                    }
+                }).collect::<Html>()
+            }
+            </Buttons>
+            <Buttons>
+            {
+                channels.into_iter().map(|(addr, name)| {
+                    let class = self.filter.channels.contains(&addr).then_some("is-info");
+
+                    html! {
+                    <Button classes={classes!("is-small", class)} onclick={ctx.link().callback(move |_| Msg::ToggleChannel(addr))} >
+                        { name }
+                    </Button>
+                    }
+                }).collect::<Html>()
+            }
+            </Buttons>
+            <Field grouped=true >
+                <Control>
+                    <input class="input is-small" type="date" value={self.filter.from.clone().unwrap_or_default()} onchange={from_cb} />
+                </Control>
+                <Control>
+                    <input class="input is-small" type="date" value={self.filter.to.clone().unwrap_or_default()} onchange={to_cb} />
+                </Control>
+                if !self.filter.is_empty() {
+                <Control>
+                    <Button classes={classes!("is-small")} onclick={ctx.link().callback(|_| Msg::ClearFilters)} >
+                        { "Clear filters" }
+                    </Button>
+                </Control>
+                }
+            </Field>
+        </ybc::Box>
+        }
+    }
+}
+
+fn filter_from_location(location: &Location) -> FeedFilter {
+    let query = location.query::<FeedQuery>().unwrap_or_default();
+
+    FeedFilter::from_query(&query)
+}
+
+/// Filters are saved in the route query so that they can be bookmarked.
+fn navigate(ctx: &Context<FeedPage>, filter: &FeedFilter) -> bool {
+    let navigator = match ctx.link().navigator() {
+        Some(navigator) => navigator,
+        None => return false,
+    };
+
+    let route = match ctx.props().list.clone() {
+        Some(list) => Route::FeedList { list },
+        None => Route::Feed,
+    };
+
+    if let Err(e) = navigator.push_with_query(&route, &filter.to_query()) {
+        error!(&format!("{:#?}", e));
+    }
+
+    false
 }
@@ -1,29 +1,48 @@
 #![cfg(target_arch = "wasm32")]
 
 use channel::ChannelPage;
-use components::Route;
+use components::{toast::ToastProvider, Route};
 use content::ContentPage;
+use discover::DiscoverPage;
 use feed::FeedPage;
 use home::HomePage;
 use live::LivePage;
 use settings::SettingPage;
 
-use linked_data::identity::Identity;
+use linked_data::{identity::Identity, types::IPNSAddress};
+
+use cid::Cid;
+
+use std::rc::Rc;
+
+use gloo_console::error;
+
+use gloo_timers::future::TimeoutFuture;
 
 use yew::{platform::spawn_local, prelude::*};
 
 use yew_router::prelude::*;
 
 use utils::{
+    aggregation::Aggregator,
+    client::IpfsClient,
     defluencer::{ChannelContext, UserContext},
+    follow_sync::SYNC_INTERVAL,
+    identities::IdentityStore,
     identity::get_current_identity,
-    ipfs::{get_ipfs_addr, set_ipfs_addr, IPFSContext},
+    ipfs::{discover, get_gateway_addr, get_ipfs_addrs, probe, IPFSContext},
+    notifications::Watcher,
+    reactions::{self, ReactionContext, ReactionIndex},
+    subscriptions::get_sub_list,
     web3::{get_wallet_addr, Web3Context},
 };
 
 #[cfg(debug_assertions)]
 use gloo_console::info;
 
+/// Milliseconds between node health checks.
+const HEALTH_CHECK_INTERVAL: u32 = 5_000;
+
 pub enum Msg {
     Context(
         (
@@ -33,6 +52,8 @@ pub enum Msg {
             Option<ChannelContext>,
         ),
     ),
+    Replay,
+    Reaction((IPNSAddress, Cid, ReactionIndex)),
 }
 
 pub struct App {
@@ -47,6 +68,21 @@ pub struct App {
     web3_context: Option<Web3Context>,
     user_context: Option<UserContext>,
     channel_context: Option<ChannelContext>,
+
+    /// Identities are kept across route changes.
+    identities: IdentityStore,
+
+    /// Unix time in milliseconds of the last follow sync.
+    last_sync: f64,
+
+    /// Notify new content while the tab is open.
+    watcher: Option<Watcher>,
+
+    /// Index comments sent to the user channel.
+    aggregator: Option<Aggregator>,
+
+    /// Reactions of followed channels, counted on every page.
+    reactions: ReactionContext,
 }
 
 impl Component for App {
@@ -58,8 +94,17 @@ impl Component for App {
         info!("App Create");
 
         let context_cb = ctx.link().callback(Msg::Context);
+        let replay_cb = ctx.link().callback(|_| Msg::Replay);
+
+        spawn_local({
+            let cb = context_cb.clone();
 
-        spawn_local(get_context(context_cb.clone()));
+            async move {
+                let endpoint = get_context(cb.clone()).await;
+
+                health_monitor(endpoint, cb, replay_cb).await;
+            }
+        });
 
         Self {
             context_cb,
@@ -68,6 +113,16 @@ impl Component for App {
             web3_context: None,
             user_context: None,
             channel_context: None,
+
+            identities: IdentityStore::new(),
+
+            last_sync: 0.0,
+
+            watcher: None,
+
+            aggregator: None,
+
+            reactions: ReactionContext::new(ctx.link().callback(Msg::Reaction)),
         }
     }
 
@@ -79,8 +134,19 @@ impl Component for App {
 
         match msg {
             Msg::Context((ipfs, web3, user, channel)) => {
-                if ipfs.is_some() {
-                    self.ipfs_context = ipfs;
+                let restart = ipfs.is_some() || channel.is_some();
+
+                if let Some(context) = ipfs {
+                    // Without a node, signing & publishing are not possible
+                    if context.is_read_only() {
+                        self.user_context = None;
+                        self.channel_context = None;
+                        self.watcher = None;
+                    } else {
+                        self.watcher = Some(Watcher::new(context.api.clone()));
+                    }
+
+                    self.ipfs_context = Some(context);
                     update = true;
                 }
 
@@ -96,8 +162,58 @@ impl Component for App {
 
                 if channel.is_some() {
                     self.channel_context = channel;
+                    self.last_sync = 0.0;
                     update = true;
                 }
+
+                if restart {
+                    // Channel context is only set when the node is online
+                    self.aggregator = match (&self.ipfs_context, &self.channel_context) {
+                        (Some(ipfs), Some(context)) => {
+                            Some(Aggregator::new(ipfs.api.clone(), context.clone()))
+                        }
+                        _ => None,
+                    };
+
+                    self.crawl_reactions();
+                }
+            }
+            Msg::Replay => {
+                // Opt-ins may have changed since the last check
+                if let Some(watcher) = self.watcher.as_mut() {
+                    watcher.refresh();
+                }
+
+                // Channel context is only set when the node is online
+                if let (Some(context), Some(ipfs)) =
+                    (self.channel_context.as_ref(), self.ipfs_context.as_ref())
+                {
+                    let context = context.clone();
+                    let ipfs = ipfs.api.clone();
+
+                    let now = js_sys::Date::now();
+                    let sync = now - self.last_sync > SYNC_INTERVAL;
+
+                    if sync {
+                        self.last_sync = now;
+
+                        self.crawl_reactions();
+                    }
+
+                    spawn_local(async move {
+                        utils::outbox::replay(&context, false).await;
+
+                        // Follows are synced once queued updates are published
+                        if sync {
+                            sync_follows(ipfs, context).await;
+                        }
+                    });
+                }
+            }
+            Msg::Reaction((addr, identity, index)) => {
+                self.reactions.insert(addr, identity, index);
+
+                update = true;
             }
         }
 
@@ -112,6 +228,23 @@ impl Component for App {
 
         // If IPFS is not working displaying pages is pointless
         let app = match self.ipfs_context.as_ref() {
+            // Without a node only static pages can be displayed
+            Some(context) if context.is_read_only() => html! {
+                <ContextProvider<IPFSContext> context={context.clone()} >
+                    <HashRouter>
+                        <Switch<Route> render={move |route| {
+                            match route {
+                                Route::Channel { addr } => html!{ <ChannelPage {addr} /> },
+                                Route::Content { cid } => html!{ <ContentPage {cid} /> },
+                                Route::Feed => html!{ <FeedPage /> },
+                                Route::FeedList { list } => html!{ <FeedPage key={list.clone()} list={Some(list)} /> },
+                                Route::Home => html!{ <HomePage /> },
+                                _ => html!{ <SettingPage context_cb={context_cb.clone()} /> },
+                            }}}
+                        />
+                    </HashRouter>
+                </ContextProvider<IPFSContext>>
+            },
             Some(context) => html! {
                 <ContextProvider<IPFSContext> context={context.clone()} >
                     <HashRouter>
@@ -120,6 +253,8 @@ impl Component for App {
                                 Route::Channel { addr } => html!{ <ChannelPage {addr} /> },
                                 Route::Content { cid } => html!{ <ContentPage {cid} /> },
                                 Route::Feed => html!{ <FeedPage /> },
+                                Route::FeedList { list } => html!{ <FeedPage key={list.clone()} list={Some(list)} /> },
+                                Route::Discover => html!{ <DiscoverPage /> },
                                 Route::Home => html!{ <HomePage /> },
                                 Route::Live { cid } => html!{ <LivePage {cid} />},
                                 Route::Settings => html!{ <SettingPage context_cb={context_cb.clone()} /> },
@@ -167,10 +302,41 @@ impl Component for App {
             None => app,
         };
 
-        app
+        html! {
+            <ToastProvider>
+                <ContextProvider<IdentityStore> context={self.identities.clone()} >
+                    <ContextProvider<ReactionContext> context={self.reactions.clone()} >
+                        {app}
+                    </ContextProvider<ReactionContext>>
+                </ContextProvider<IdentityStore>>
+            </ToastProvider>
+        }
+    }
+}
+
+impl App {
+    /// Fetch the reaction indexes of followed channels and the user channel.
+    fn crawl_reactions(&self) {
+        let ipfs = match self.ipfs_context.as_ref() {
+            Some(context) => context.api.clone(),
+            None => return,
+        };
+
+        let mut addresses = get_sub_list();
+
+        if let Some(context) = self.channel_context.as_ref() {
+            addresses.insert(context.channel.get_address());
+        }
+
+        spawn_local(reactions::crawl(
+            ipfs,
+            addresses,
+            self.reactions.callback.clone(),
+        ));
     }
 }
 
+/// Return the active endpoint if an IPFS node is online.
 async fn get_context(
     callback: Callback<(
         Option<IPFSContext>,
@@ -178,14 +344,16 @@ async fn get_context(
         Option<UserContext>,
         Option<ChannelContext>,
     )>,
-) {
-    let addr = get_ipfs_addr();
-
-    let ipfs = IPFSContext::new(Some(&addr)).await;
+) -> Option<String> {
+    let ipfs = match discover(&get_ipfs_addrs()).await {
+        Some(url) => IPFSContext::new(Some(&url)).await,
+        None => None,
+    };
 
-    if ipfs.is_some() {
-        set_ipfs_addr(&addr);
-    }
+    let ipfs = match ipfs {
+        Some(context) => context,
+        None => IPFSContext::read_only(&get_gateway_addr()),
+    };
 
     let mut web3 = None;
 
@@ -193,24 +361,26 @@ async fn get_context(
         web3 = Web3Context::new().await;
     }
 
-    let user = match (&ipfs, &web3, get_current_identity()) {
-        (Some(ipfs), Some(web3), Some(ipld)) => {
-            let context = UserContext::new(ipfs.client.clone(), web3.signer.clone(), ipld.link);
+    let client = ipfs.api.service();
+
+    let user = match (&client, &web3, get_current_identity()) {
+        (Some(client), Some(web3), Some(ipld)) => {
+            let context = UserContext::new(client.clone(), web3.signer.clone(), ipld.link);
 
             Some(context)
         }
         _ => None,
     };
 
-    let channel = match (&ipfs, get_current_identity()) {
-        (Some(ipfs), Some(ipld)) => {
-            match ipfs.client.dag_get::<&str, Identity>(ipld.link, None).await {
+    let channel = match (client, get_current_identity()) {
+        (Some(client), Some(ipld)) => {
+            match utils::r#async::cached_dag_get::<Identity>(&*ipfs.api, ipld.link, None).await {
                 Ok(identity) => {
                     if let Some(addr) = identity.ipns_addr {
                         use heck::ToSnakeCase;
                         let key = identity.name.to_snake_case();
 
-                        let context = ChannelContext::new(ipfs.client.clone(), key, addr);
+                        let context = ChannelContext::new(client, key, addr);
 
                         Some(context)
                     } else {
@@ -223,5 +393,63 @@ async fn get_context(
         _ => None,
     };
 
-    callback.emit((ipfs, web3, user, channel));
+    let endpoint = ipfs.endpoint.clone();
+
+    callback.emit((Some(ipfs), web3, user, channel));
+
+    endpoint
+}
+
+/// Reconcile the local follow set with the user channel follows.
+async fn sync_follows(ipfs: Rc<dyn IpfsClient>, context: ChannelContext) {
+    match utils::follow_sync::sync_follows(ipfs, &context).await {
+        Ok(_report) => {
+            #[cfg(debug_assertions)]
+            info!(&format!("Follows Synced {:?}", _report));
+        }
+        Err(e) => error!(&format!("{:#?}", e)),
+    }
+}
+
+/// Periodically probe the active IPFS endpoint.
+///
+/// Fails over to the next endpoint that answers when the active one drops,
+/// falling back to read-only mode if none does.
+/// Queued channel updates are replayed while the node is healthy.
+async fn health_monitor(
+    mut endpoint: Option<String>,
+    callback: Callback<(
+        Option<IPFSContext>,
+        Option<Web3Context>,
+        Option<UserContext>,
+        Option<ChannelContext>,
+    )>,
+    replay_cb: Callback<()>,
+) {
+    loop {
+        TimeoutFuture::new(HEALTH_CHECK_INTERVAL).await;
+
+        if let Some(url) = endpoint.as_ref() {
+            if probe(url).await {
+                replay_cb.emit(());
+                continue;
+            }
+
+            #[cfg(debug_assertions)]
+            info!(&format!("IPFS Endpoint {} Offline", url));
+        }
+
+        if discover(&get_ipfs_addrs()).await.is_some() {
+            #[cfg(debug_assertions)]
+            info!("IPFS Node Online");
+
+            endpoint = get_context(callback.clone()).await;
+        } else if endpoint.is_some() {
+            let context = IPFSContext::read_only(&get_gateway_addr());
+
+            callback.emit((Some(context), None, None, None));
+
+            endpoint = None;
+        }
+    }
 }
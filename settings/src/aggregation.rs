@@ -0,0 +1,231 @@
+#![cfg(target_arch = "wasm32")]
+
+use utils::{
+    aggregation::{self, AggregationSettings as Settings},
+    mute::MuteEntry,
+};
+
+use ybc::{
+    Block, Button, Checkbox, Container, Control, Field, Input, Level, LevelItem, LevelLeft,
+    LevelRight, Section, Subtitle,
+};
+
+use yew::prelude::*;
+
+#[cfg(debug_assertions)]
+use gloo_console::info;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Rule {
+    Allow,
+    Deny,
+}
+
+/// Rules for comments sent to the user channel.
+pub struct AggregationSettings {
+    settings: Settings,
+
+    enabled_cb: Callback<bool>,
+    allow_only_cb: Callback<bool>,
+
+    allow_input: String,
+    allow_cb: Callback<String>,
+
+    deny_input: String,
+    deny_cb: Callback<String>,
+
+    invalid: Option<Rule>,
+}
+
+pub enum Msg {
+    Enabled(bool),
+    AllowOnly(bool),
+    Input((Rule, String)),
+    Add(Rule),
+    Remove((Rule, usize)),
+}
+
+impl Component for AggregationSettings {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        #[cfg(debug_assertions)]
+        info!("Aggregation Setting Create");
+
+        Self {
+            settings: aggregation::get_settings(),
+
+            enabled_cb: ctx.link().callback(Msg::Enabled),
+            allow_only_cb: ctx.link().callback(Msg::AllowOnly),
+
+            allow_input: String::new(),
+            allow_cb: ctx
+                .link()
+                .callback(|input| Msg::Input((Rule::Allow, input))),
+
+            deny_input: String::new(),
+            deny_cb: ctx.link().callback(|input| Msg::Input((Rule::Deny, input))),
+
+            invalid: None,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        #[cfg(debug_assertions)]
+        info!("Aggregation Setting Update");
+
+        match msg {
+            Msg::Enabled(enabled) => self.settings.enabled = enabled,
+            Msg::AllowOnly(allow_only) => self.settings.allow_only = allow_only,
+            Msg::Input((rule, input)) => {
+                match rule {
+                    Rule::Allow => self.allow_input = input,
+                    Rule::Deny => self.deny_input = input,
+                }
+
+                self.invalid = None;
+
+                return true;
+            }
+            Msg::Add(rule) => {
+                let input = match rule {
+                    Rule::Allow => &mut self.allow_input,
+                    Rule::Deny => &mut self.deny_input,
+                };
+
+                let entry = match MuteEntry::parse(input) {
+                    Some(entry) => entry,
+                    None => {
+                        self.invalid = Some(rule);
+                        return true;
+                    }
+                };
+
+                input.clear();
+
+                let entries = self.entries_mut(rule);
+
+                if entries.contains(&entry) {
+                    return true;
+                }
+
+                entries.push(entry);
+            }
+            Msg::Remove((rule, index)) => {
+                let entries = self.entries_mut(rule);
+
+                if index >= entries.len() {
+                    return false;
+                }
+
+                entries.remove(index);
+            }
+        }
+
+        aggregation::set_settings(&self.settings);
+
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        #[cfg(debug_assertions)]
+        info!("Aggregation Setting View");
+
+        html! {
+        <Section>
+            <Container>
+                <Subtitle>
+                    {"Comment Aggregation"}
+                </Subtitle>
+                <Block>
+                <small>
+                    {"Once enabled, comments sent to your channel are verified then added to your comment index while the app is open, so that anyone can see them."}
+                </small>
+                </Block>
+                <Field>
+                    <Control>
+                        <Checkbox name="aggregation" checked={self.settings.enabled} update={self.enabled_cb.clone()} >
+                            {" Index comments on my content"}
+                        </Checkbox>
+                    </Control>
+                </Field>
+                <Field help={"Otherwise, any comment not denied is indexed."} >
+                    <Control>
+                        <Checkbox name="allow_only" checked={self.settings.allow_only} update={self.allow_only_cb.clone()} >
+                            {" Only from followed channels and allowed entries"}
+                        </Checkbox>
+                    </Control>
+                </Field>
+                { self.render_rule(ctx, Rule::Allow) }
+                { self.render_rule(ctx, Rule::Deny) }
+            </Container>
+        </Section>
+        }
+    }
+}
+
+impl AggregationSettings {
+    fn entries_mut(&mut self, rule: Rule) -> &mut Vec<MuteEntry> {
+        match rule {
+            Rule::Allow => &mut self.settings.allow,
+            Rule::Deny => &mut self.settings.deny,
+        }
+    }
+
+    fn render_rule(&self, ctx: &Context<Self>, rule: Rule) -> Html {
+        let (label, entries, input, input_cb) = match rule {
+            Rule::Allow => (
+                "Allow",
+                &self.settings.allow,
+                &self.allow_input,
+                &self.allow_cb,
+            ),
+            Rule::Deny => ("Deny", &self.settings.deny, &self.deny_input, &self.deny_cb),
+        };
+
+        let help = if self.invalid == Some(rule) {
+            "Invalid entry, check the regular expression."
+        } else {
+            "Channel address, identity CID, Ethereum address, keyword or /regex/."
+        };
+
+        html! {
+        <>
+            {
+                entries.iter().enumerate().map(|(index, entry)| html! {
+                <Level key={format!("{}:{}", entry.kind(), entry)} >
+                    <LevelLeft>
+                        <LevelItem>
+                            <span class="tag">{ entry.kind() }</span>
+                        </LevelItem>
+                        <LevelItem>
+                            <code>{ entry.to_string() }</code>
+                        </LevelItem>
+                    </LevelLeft>
+                    <LevelRight>
+                        <LevelItem>
+                            <Button onclick={ctx.link().callback(move |_| Msg::Remove((rule, index)))} >
+                                <span class="icon is-small">
+                                    <i class="fa-solid fa-trash-can"></i>
+                                </span>
+                            </Button>
+                        </LevelItem>
+                    </LevelRight>
+                </Level>
+                }).collect::<Html>()
+            }
+            <Field addons=true help={help} >
+                <Control expanded=true >
+                    <Input name={label.to_lowercase()} value={input.clone()} update={input_cb.clone()} placeholder={label} />
+                </Control>
+                <Control>
+                    <Button onclick={ctx.link().callback(move |_| Msg::Add(rule))} disabled={input.trim().is_empty()} >
+                        { label }
+                    </Button>
+                </Control>
+            </Field>
+        </>
+        }
+    }
+}
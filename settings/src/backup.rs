@@ -0,0 +1,235 @@
+#![cfg(target_arch = "wasm32")]
+
+use components::toast::Toaster;
+
+use gloo_console::error;
+
+use utils::{
+    backup::{self, ImportReport},
+    errors::AppError,
+};
+
+use wasm_bindgen_futures::JsFuture;
+
+use web_sys::File as SysFile;
+
+use ybc::{Block, Button, Container, Control, Field, File, Section, Subtitle};
+
+use yew::{platform::spawn_local, prelude::*};
+
+#[cfg(debug_assertions)]
+use gloo_console::info;
+
+/// Export and import local app state as a file.
+pub struct BackupSettings {
+    toaster: Toaster,
+
+    files: Vec<SysFile>,
+    files_cb: Callback<Vec<SysFile>>,
+
+    report: Option<ImportReport>,
+}
+
+pub enum Msg {
+    Export,
+    Files(Vec<SysFile>),
+    Import,
+    Text(String),
+    AddIpfsAddrs,
+}
+
+impl Component for BackupSettings {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        #[cfg(debug_assertions)]
+        info!("Backup Setting Create");
+
+        let toaster = ctx
+            .link()
+            .context::<Toaster>(Callback::noop())
+            .map(|(toaster, _)| toaster)
+            .unwrap_or_default();
+
+        Self {
+            toaster,
+
+            files: Vec::new(),
+            files_cb: ctx.link().callback(Msg::Files),
+
+            report: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        #[cfg(debug_assertions)]
+        info!("Backup Setting Update");
+
+        match msg {
+            Msg::Export => {
+                let json = backup::to_json(&backup::export());
+
+                if let Err(e) = utils::save_file(&json, backup::FILE_NAME, "application/json") {
+                    error!(&format!("{:#?}", e));
+                }
+
+                false
+            }
+            Msg::Files(files) => {
+                self.files = files;
+
+                true
+            }
+            Msg::Import => {
+                let file = match self.files.pop() {
+                    Some(file) => file,
+                    None => return false,
+                };
+
+                spawn_local(read_file(
+                    file,
+                    self.toaster.clone(),
+                    ctx.link().callback(Msg::Text),
+                ));
+
+                true
+            }
+            Msg::Text(text) => {
+                let backup = match backup::from_json(&text) {
+                    Ok(backup) => backup,
+                    Err(e) => {
+                        self.toaster
+                            .error_with(e.to_string(), AppError::decode(e), None);
+                        return false;
+                    }
+                };
+
+                let report = backup::import(backup);
+
+                if !report.remote_ipfs_addrs.is_empty() {
+                    self.toaster
+                        .warning("Backup imported, confirm the remote IPFS APIs below.");
+                } else if report.invalid.is_empty() {
+                    self.toaster.success("Backup imported.");
+                } else {
+                    self.toaster
+                        .warning("Backup imported, some entries were invalid and skipped.");
+                }
+
+                self.report = Some(report);
+
+                true
+            }
+            Msg::AddIpfsAddrs => {
+                let report = match self.report.as_mut() {
+                    Some(report) => report,
+                    None => return false,
+                };
+
+                let count = backup::add_ipfs_addrs(&report.remote_ipfs_addrs);
+
+                report.remote_ipfs_addrs.clear();
+                report.settings += count;
+
+                self.toaster.success(format!("{} IPFS APIs added.", count));
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        #[cfg(debug_assertions)]
+        info!("Backup Setting View");
+
+        html! {
+        <Section>
+            <Container>
+                <Subtitle>
+                    {"Backup"}
+                </Subtitle>
+                <Block>
+                <small>
+                    {"Subscriptions, identities, wallet, IPFS and mute settings are stored in this browser only. Export them to a file to move to another machine."}
+                </small>
+                </Block>
+                <Field>
+                    <Control>
+                        <Button onclick={ctx.link().callback(|_| Msg::Export)} >
+                            {"Export"}
+                        </Button>
+                    </Control>
+                </Field>
+                <Field label="Import" help={"Imported data is merged with the current settings."} >
+                    <Control>
+                        <File name="backup" files={self.files.clone()} update={self.files_cb.clone()} selector_label={"Choose a backup..."} selector_icon={html!{<i class="fas fa-upload"></i>}} has_name={Some(backup::FILE_NAME)} fullwidth=true />
+                    </Control>
+                </Field>
+                <Field>
+                    <Control>
+                        <Button disabled={self.files.is_empty()} onclick={ctx.link().callback(|_| Msg::Import)} >
+                            {"Import"}
+                        </Button>
+                    </Control>
+                </Field>
+                if let Some(report) = self.report.as_ref() {
+                    { render_report(report) }
+                    if !report.remote_ipfs_addrs.is_empty() {
+                        { self.render_remote_addrs(ctx, &report.remote_ipfs_addrs) }
+                    }
+                }
+            </Container>
+        </Section>
+        }
+    }
+}
+
+impl BackupSettings {
+    fn render_remote_addrs(&self, ctx: &Context<Self>, urls: &[String]) -> Html {
+        html! {
+        <Block>
+            <p>{"These IPFS APIs are on other machines. They would receive all your requests, only add them if you trust them."}</p>
+            <pre>
+            { urls.iter().map(|url| html! { <>{ url }<br/></> }).collect::<Html>() }
+            </pre>
+            <Button classes={classes!("is-warning")} onclick={ctx.link().callback(|_| Msg::AddIpfsAddrs)} >
+                {"Add these IPFS APIs"}
+            </Button>
+        </Block>
+        }
+    }
+}
+
+fn render_report(report: &ImportReport) -> Html {
+    html! {
+    <Block>
+        <p>{ format!("{} new channels, {} new identities and {} settings imported.", report.channels, report.identities, report.settings) }</p>
+        if report.identities > 0 || report.settings > 0 {
+            <p><small>{"Reload the page to use the imported identities and settings."}</small></p>
+        }
+        if !report.invalid.is_empty() {
+        <details>
+            <summary><small>{ format!("{} invalid entries skipped", report.invalid.len()) }</small></summary>
+            <pre style="max-height: 10rem; overflow: auto;" >
+            { report.invalid.iter().map(|entry| html! { <>{ entry }<br/></> }).collect::<Html>() }
+            </pre>
+        </details>
+        }
+    </Block>
+    }
+}
+
+async fn read_file(file: SysFile, toaster: Toaster, callback: Callback<String>) {
+    let text = match JsFuture::from(file.text()).await {
+        Ok(text) => text,
+        Err(e) => {
+            return toaster.error_with("Backup could not be read.", AppError::decode(e), None)
+        }
+    };
+
+    match text.as_string() {
+        Some(text) => callback.emit(text),
+        None => toaster.warning("Backup could not be read."),
+    }
+}
@@ -1,6 +1,9 @@
 #![cfg(target_arch = "wasm32")]
 
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
 use cid::Cid;
 
@@ -9,6 +12,7 @@ use components::{pure::DagExplorer, Route};
 use defluencer::channel::Channel;
 
 use utils::{
+    client::IpfsClient,
     defluencer::{ChannelContext, UserContext},
     display_address,
     identity::{
@@ -134,7 +138,7 @@ impl Component for IdentitySettings {
         let cb = ctx.link().callback(Msg::GetIDs);
 
         if let Some((context, _)) = ctx.link().context::<IPFSContext>(Callback::noop()) {
-            let ipfs = context.client;
+            let ipfs = context.api;
 
             for identity in identity_set {
                 spawn_local({
@@ -142,7 +146,7 @@ impl Component for IdentitySettings {
                     let ipfs = ipfs.clone();
 
                     async move {
-                        match ipfs.dag_get::<&str, Identity>(identity.link, None).await {
+                        match ipfs.dag_get::<Identity>(identity.link, None).await {
                             Ok(id) => cb.emit((identity.link, id)),
                             Err(e) => error!(&format!("{:?}", e)),
                         }
@@ -436,8 +440,12 @@ impl IdentitySettings {
     }
 
     fn on_set_identity(&mut self, cid: Cid, ctx: &Context<Self>) -> bool {
-        let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.client,
+        let ipfs = match ctx
+            .link()
+            .context::<IPFSContext>(Callback::noop())
+            .and_then(|(context, _)| context.api.service())
+        {
+            Some(ipfs) => ipfs,
             None => return false,
         };
 
@@ -477,8 +485,12 @@ impl IdentitySettings {
 
     /// Callback when a new identity was created
     fn on_identity_created(&mut self, ctx: &Context<Self>, cid: Cid, identity: Identity) -> bool {
-        let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.client,
+        let ipfs = match ctx
+            .link()
+            .context::<IPFSContext>(Callback::noop())
+            .and_then(|(context, _)| context.api.service())
+        {
+            Some(ipfs) => ipfs,
             None => return false,
         };
 
@@ -581,8 +593,12 @@ impl IdentitySettings {
     }
 
     fn on_create(&mut self, ctx: &Context<Self>) -> bool {
-        let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.client,
+        let ipfs = match ctx
+            .link()
+            .context::<IPFSContext>(Callback::noop())
+            .and_then(|(context, _)| context.api.service())
+        {
+            Some(ipfs) => ipfs,
             None => return false,
         };
 
@@ -609,7 +625,7 @@ impl IdentitySettings {
 
     fn on_import(&mut self, ctx: &Context<Self>) -> bool {
         let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.client,
+            Some((context, _)) => context.api,
             None => return false,
         };
 
@@ -660,7 +676,7 @@ impl IdentitySettings {
 
     fn on_confirm_delete(&mut self, ctx: &Context<Self>) -> bool {
         let ipfs = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, _)) => context.client,
+            Some((context, _)) => context.api,
             None => return false,
         };
 
@@ -784,7 +800,7 @@ async fn create_identity(
     cb.emit((cid, identity));
 }
 
-async fn delete_channel(ipfs: IpfsService, key: String) {
+async fn delete_channel(ipfs: Rc<dyn IpfsClient>, key: String) {
     let key_list = match ipfs.key_list().await {
         Ok(list) => list,
         Err(e) => {
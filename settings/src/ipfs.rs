@@ -1,14 +1,22 @@
 #![cfg(target_arch = "wasm32")]
 
+use std::collections::HashMap;
+
 use linked_data::types::PeerId;
 
-use ybc::{Block, Container, Control, Field, Input, Section, Subtitle, Tabs};
+use ybc::{
+    Block, Button, Container, Control, Field, Input, Level, LevelItem, LevelLeft, LevelRight,
+    Section, Subtitle, Tabs,
+};
 
 use yew::{context::ContextHandle, platform::spawn_local, prelude::*};
 
 use utils::{
     defluencer::{ChannelContext, UserContext},
-    ipfs::{get_ipfs_addr, set_ipfs_addr, IPFSContext},
+    ipfs::{
+        default_ipfs_addrs, discover, get_gateway_addr, get_ipfs_addrs, probe, set_gateway_addr,
+        set_ipfs_addrs, IPFSContext, WELL_KNOWN_APIS,
+    },
     web3::Web3Context,
 };
 
@@ -29,11 +37,21 @@ pub struct Props {
 
 pub struct IPFSSettings {
     peer_id: Option<PeerId>,
+    read_only: bool,
+    endpoint: Option<String>,
     _context_handle: Option<ContextHandle<IPFSContext>>,
 
+    /// API endpoints in order of preference.
+    addresses: Vec<String>,
+    health: HashMap<String, bool>,
+    health_cb: Callback<(String, bool)>,
+
     address: String,
     address_cb: Callback<String>,
 
+    gateway: String,
+    gateway_cb: Callback<String>,
+
     os_type: OsType,
     win_cb: Callback<MouseEvent>,
     unix_cb: Callback<MouseEvent>,
@@ -42,7 +60,13 @@ pub struct IPFSSettings {
 }
 
 pub enum Msg {
+    Context(IPFSContext),
+    Health((String, bool)),
     Addrs(String),
+    AddAddrs,
+    RaiseAddrs(usize),
+    RemoveAddrs(usize),
+    Gateway(String),
     OsType(OsType),
 }
 
@@ -54,15 +78,31 @@ impl Component for IPFSSettings {
         #[cfg(debug_assertions)]
         info!("IPFS Setting Create");
 
-        let (peer_id, _context_handle) = match ctx.link().context::<IPFSContext>(Callback::noop()) {
-            Some((context, handle)) => (Some(context.peer_id.into()), Some(handle)),
-            None => (None, None),
+        let (peer_id, read_only, endpoint, _context_handle) = match ctx
+            .link()
+            .context::<IPFSContext>(ctx.link().callback(Msg::Context))
+        {
+            Some((context, handle)) => (
+                context.peer_id,
+                context.is_read_only(),
+                context.endpoint,
+                Some(handle),
+            ),
+            None => (None, false, None, None),
         };
 
-        let address = get_ipfs_addr();
+        let addresses = get_ipfs_addrs();
+
+        let health_cb = ctx.link().callback(Msg::Health);
+
+        probe_all(&addresses, &health_cb);
 
         let address_cb = ctx.link().callback(Msg::Addrs);
 
+        let gateway = get_gateway_addr();
+
+        let gateway_cb = ctx.link().callback(Msg::Gateway);
+
         let win_cb = ctx
             .link()
             .callback(|_e: MouseEvent| Msg::OsType(OsType::Windows));
@@ -86,11 +126,20 @@ impl Component for IPFSSettings {
 
         Self {
             peer_id,
+            read_only,
+            endpoint,
             _context_handle,
 
-            address,
+            addresses,
+            health: HashMap::default(),
+            health_cb,
+
+            address: String::default(),
             address_cb,
 
+            gateway,
+            gateway_cb,
+
             os_type: OsType::Unix,
             win_cb,
             unix_cb,
@@ -104,22 +153,76 @@ impl Component for IPFSSettings {
         info!("IPFS Setting Update");
 
         match msg {
+            Msg::Context(context) => {
+                self.peer_id = context.peer_id;
+                self.read_only = context.is_read_only();
+                self.endpoint = context.endpoint;
+
+                probe_all(&self.addresses, &self.health_cb);
+
+                true
+            }
+            Msg::Health((url, online)) => self.health.insert(url, online) != Some(online),
             Msg::Addrs(msg) => {
-                if msg != self.address {
-                    set_ipfs_addr(&msg);
+                self.address = msg;
 
-                    spawn_local({
-                        let cb = ctx.props().context_cb.clone();
-                        let url = msg.clone();
+                true
+            }
+            Msg::AddAddrs => {
+                let url = self.address.trim().to_owned();
 
-                        async move {
-                            if let Some(context) = IPFSContext::new(Some(&url)).await {
-                                cb.emit((Some(context), None, None, None));
-                            }
-                        }
-                    });
+                if url.is_empty() || self.addresses.contains(&url) {
+                    return false;
+                }
 
-                    self.address = msg;
+                self.addresses.push(url);
+                self.address.clear();
+
+                self.on_addresses_changed(ctx);
+
+                true
+            }
+            Msg::RaiseAddrs(index) => {
+                if index == 0 || index >= self.addresses.len() {
+                    return false;
+                }
+
+                self.addresses.swap(index - 1, index);
+
+                self.on_addresses_changed(ctx);
+
+                true
+            }
+            Msg::RemoveAddrs(index) => {
+                if index >= self.addresses.len() {
+                    return false;
+                }
+
+                let url = self.addresses.remove(index);
+                self.health.remove(&url);
+
+                // Never leave the app without an endpoint to try
+                if self.addresses.is_empty() {
+                    self.addresses = default_ipfs_addrs();
+                }
+
+                self.on_addresses_changed(ctx);
+
+                true
+            }
+            Msg::Gateway(msg) => {
+                if msg != self.gateway {
+                    set_gateway_addr(&msg);
+
+                    if self.read_only {
+                        let context = IPFSContext::read_only(&msg);
+
+                        ctx.props()
+                            .context_cb
+                            .emit((Some(context), None, None, None));
+                    }
+
+                    self.gateway = msg;
                 }
 
                 true
@@ -132,11 +235,11 @@ impl Component for IPFSSettings {
                 }
 
                 false
-            } //Msg::NodeType(msg) => self.on_node_type(msg),
+            }
         }
     }
 
-    fn view(&self, _ctx: &Context<Self>) -> Html {
+    fn view(&self, ctx: &Context<Self>) -> Html {
         #[cfg(debug_assertions)]
         info!("IPFS Setting View");
 
@@ -152,21 +255,22 @@ impl Component for IPFSSettings {
                         None => self.render_unconnected(),
                     }
                 }
-                /* <div class="field">
-                    <label class="label"> { "IPFS Node" } </label>
-                    <div class="control is-expanded">
-                        <div class="select is-fullwidth">
-                            <select id="node_type" onchange=self.node_cb.clone() >
-                                <option selected=brave_slct value="Brave"> { "Brave" } </option>
-                                <option selected=ext_slct value="External"> { "External" } </option>
-                            </select>
-                        </div>
-                    </div>
-                    <p class="help"> { "External nodes can be configured for better performace but Brave browser nodes are more conveniant." } </p>
-                </div> */
-                <Field label="API Address" help={"Refresh (F5) to apply changes."} >
+                <Field label="API Endpoints" help={"Endpoints are tried in order, the next one answering is used if the active endpoint goes offline."} >
+                    { self.render_endpoints(ctx) }
+                </Field>
+                <Field addons=true >
+                    <Control expanded=true >
+                        <Input name="ipfs_addrs" value={self.address.clone()} update={self.address_cb.clone()} placeholder="http://127.0.0.1:5001/api/v0/" />
+                    </Control>
+                    <Control>
+                        <Button onclick={ctx.link().callback(|_| Msg::AddAddrs)} disabled={self.address.trim().is_empty()} >
+                            { "Add" }
+                        </Button>
+                    </Control>
+                </Field>
+                <Field label="Gateway Address" help={"Used in read-only mode when no IPFS node is available."} >
                     <Control expanded=true >
-                        <Input name="ipfs_addrs" value={self.address.clone()} update={self.address_cb.clone()} />
+                        <Input name="gateway_addrs" value={self.gateway.clone()} update={self.gateway_cb.clone()} />
                     </Control>
                 </Field>
             </Container>
@@ -176,6 +280,93 @@ impl Component for IPFSSettings {
 }
 
 impl IPFSSettings {
+    /// Save the endpoints then connect if in read-only mode.
+    fn on_addresses_changed(&self, ctx: &Context<Self>) {
+        set_ipfs_addrs(&self.addresses);
+
+        probe_all(&self.addresses, &self.health_cb);
+
+        if !self.read_only {
+            return;
+        }
+
+        spawn_local({
+            let cb = ctx.props().context_cb.clone();
+            let addresses = self.addresses.clone();
+
+            async move {
+                let url = match discover(&addresses).await {
+                    Some(url) => url,
+                    None => return,
+                };
+
+                if let Some(context) = IPFSContext::new(Some(&url)).await {
+                    cb.emit((Some(context), None, None, None));
+                }
+            }
+        });
+    }
+
+    fn render_endpoints(&self, ctx: &Context<Self>) -> Html {
+        self.addresses
+            .iter()
+            .enumerate()
+            .map(|(index, url)| {
+                let name = WELL_KNOWN_APIS
+                    .iter()
+                    .find(|(_, addr)| *addr == url.as_str())
+                    .map(|(name, _)| *name);
+
+                let active = self.endpoint.as_ref() == Some(url);
+
+                let (class, status) = match self.health.get(url) {
+                    Some(true) => ("tag is-success", "Online"),
+                    Some(false) => ("tag is-danger", "Offline"),
+                    None => ("tag", "Checking"),
+                };
+
+                html! {
+                <Level>
+                    <LevelLeft>
+                        <LevelItem>
+                            <span class={class}>{ status }</span>
+                        </LevelItem>
+                        if active {
+                        <LevelItem>
+                            <span class="tag is-info">{ "Active" }</span>
+                        </LevelItem>
+                        }
+                        <LevelItem>
+                            <code>{ url }</code>
+                        </LevelItem>
+                        if let Some(name) = name {
+                        <LevelItem>
+                            { name }
+                        </LevelItem>
+                        }
+                    </LevelLeft>
+                    <LevelRight>
+                        <LevelItem>
+                            <Button disabled={index == 0} onclick={ctx.link().callback(move |_| Msg::RaiseAddrs(index))} >
+                                <span class="icon is-small">
+                                    <i class="fas fa-arrow-up"></i>
+                                </span>
+                            </Button>
+                        </LevelItem>
+                        <LevelItem>
+                            <Button onclick={ctx.link().callback(move |_| Msg::RemoveAddrs(index))} >
+                                <span class="icon is-small">
+                                    <i class="fa-solid fa-trash-can"></i>
+                                </span>
+                            </Button>
+                        </LevelItem>
+                    </LevelRight>
+                </Level>
+                }
+            })
+            .collect::<Html>()
+    }
+
     /// Os dependent render of console commands.
     fn render_code(&self) -> Html {
         let (deliminator, separator) = match self.os_type {
@@ -202,12 +393,6 @@ impl IPFSSettings {
     }
 
     fn render_unconnected(&self) -> Html {
-        /* let port = if self.node_type == NodeType::Brave {
-            "45005"
-        } else {
-            "5001"
-        }; */
-
         html! {
             <>
                 <Block>
@@ -216,6 +401,11 @@ impl IPFSSettings {
                     <span class="title"> { "Cannot connect to IPFS" } </span>
                 </span>
                 </Block>
+                if self.read_only {
+                <Block>
+                    <p>{ "Browsing in read-only mode through a gateway. Publishing, chat and comments are disabled." }</p>
+                </Block>
+                }
                 <Block>
                 <ol>
                     <li>
@@ -283,3 +473,19 @@ pub enum OsType {
     Unix,
     Windows,
 }
+
+/// Probe every endpoint, reporting their health.
+fn probe_all(addresses: &[String], callback: &Callback<(String, bool)>) {
+    for url in addresses {
+        spawn_local({
+            let cb = callback.clone();
+            let url = url.clone();
+
+            async move {
+                let online = probe(&url).await;
+
+                cb.emit((url, online));
+            }
+        });
+    }
+}
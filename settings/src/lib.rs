@@ -1,7 +1,12 @@
 #![cfg(target_arch = "wasm32")]
 
+mod aggregation;
+mod backup;
 mod identity;
 mod ipfs;
+mod mute;
+mod notifications;
+mod outbox;
 mod wallet;
 
 use utils::{
@@ -18,6 +23,16 @@ use wallet::WalletSettings;
 
 use identity::IdentitySettings;
 
+use outbox::OutboxSettings;
+
+use mute::MuteSettings;
+
+use notifications::NotificationSettings;
+
+use backup::BackupSettings;
+
+use aggregation::AggregationSettings;
+
 use components::pure::NavigationBar;
 
 #[derive(Properties, PartialEq)]
@@ -36,9 +51,10 @@ pub fn settings(props: &SettingPageProps) -> Html {
 
     let ipfs_context = use_context::<IPFSContext>();
     let web3_context = use_context::<Web3Context>();
+    let channel_context = use_context::<ChannelContext>();
 
     let identity_settings = match (ipfs_context, web3_context) {
-        (Some(_), Some(_)) => {
+        (Some(ipfs), Some(_)) if !ipfs.is_read_only() => {
             html! {<IdentitySettings context_cb={context_cb.clone()} />}
         }
         _ => html! {},
@@ -50,6 +66,13 @@ pub fn settings(props: &SettingPageProps) -> Html {
         <IPFSSettings context_cb={context_cb.clone()} />
         <WalletSettings {context_cb} />
         {identity_settings}
+        <OutboxSettings />
+        <MuteSettings />
+        <NotificationSettings />
+        if channel_context.is_some() {
+            <AggregationSettings />
+        }
+        <BackupSettings />
         </>
     }
 }
@@ -0,0 +1,154 @@
+#![cfg(target_arch = "wasm32")]
+
+use ybc::{
+    Button, Container, Control, Field, Input, Level, LevelItem, LevelLeft, LevelRight, Section,
+    Subtitle,
+};
+
+use yew::prelude::*;
+
+use utils::mute::{self, MuteEntry};
+
+#[cfg(debug_assertions)]
+use gloo_console::info;
+
+/// Manage channels, identities and keywords hidden everywhere.
+pub struct MuteSettings {
+    entries: Vec<MuteEntry>,
+
+    input: String,
+    input_cb: Callback<String>,
+    invalid: bool,
+}
+
+pub enum Msg {
+    Input(String),
+    Add,
+    Remove(usize),
+}
+
+impl Component for MuteSettings {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        #[cfg(debug_assertions)]
+        info!("Mute Setting Create");
+
+        Self {
+            entries: mute::get_mute_list(),
+
+            input: String::new(),
+            input_cb: ctx.link().callback(Msg::Input),
+            invalid: false,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        #[cfg(debug_assertions)]
+        info!("Mute Setting Update");
+
+        match msg {
+            Msg::Input(input) => {
+                self.input = input;
+                self.invalid = false;
+
+                true
+            }
+            Msg::Add => {
+                let entry = match MuteEntry::parse(&self.input) {
+                    Some(entry) => entry,
+                    None => {
+                        self.invalid = true;
+                        return true;
+                    }
+                };
+
+                if !self.entries.contains(&entry) {
+                    self.entries.push(entry);
+
+                    mute::set_mute_list(&self.entries);
+                }
+
+                self.input.clear();
+
+                true
+            }
+            Msg::Remove(index) => {
+                if index >= self.entries.len() {
+                    return false;
+                }
+
+                self.entries.remove(index);
+
+                mute::set_mute_list(&self.entries);
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        #[cfg(debug_assertions)]
+        info!("Mute Setting View");
+
+        let help = if self.invalid {
+            "Invalid entry, check the regular expression."
+        } else {
+            "Channel address, identity CID, Ethereum address, keyword or /regex/. Muted content is hidden in feeds, channels, comments and chats."
+        };
+
+        html! {
+        <Section>
+            <Container>
+                <Subtitle>
+                    {"Mute List"}
+                </Subtitle>
+                { self.render_entries(ctx) }
+                <Field addons=true help={help} >
+                    <Control expanded=true >
+                        <Input name="mute_entry" value={self.input.clone()} update={self.input_cb.clone()} placeholder="/crypto|nft/" />
+                    </Control>
+                    <Control>
+                        <Button onclick={ctx.link().callback(|_| Msg::Add)} disabled={self.input.trim().is_empty()} >
+                            {"Mute"}
+                        </Button>
+                    </Control>
+                </Field>
+            </Container>
+        </Section>
+        }
+    }
+}
+
+impl MuteSettings {
+    fn render_entries(&self, ctx: &Context<Self>) -> Html {
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                html! {
+                <Level key={format!("{}:{}", entry.kind(), entry)} >
+                    <LevelLeft>
+                        <LevelItem>
+                            <span class="tag">{ entry.kind() }</span>
+                        </LevelItem>
+                        <LevelItem>
+                            <code>{ entry.to_string() }</code>
+                        </LevelItem>
+                    </LevelLeft>
+                    <LevelRight>
+                        <LevelItem>
+                            <Button onclick={ctx.link().callback(move |_| Msg::Remove(index))} >
+                                <span class="icon is-small">
+                                    <i class="fa-solid fa-trash-can"></i>
+                                </span>
+                            </Button>
+                        </LevelItem>
+                    </LevelRight>
+                </Level>
+                }
+            })
+            .collect::<Html>()
+    }
+}
@@ -0,0 +1,195 @@
+#![cfg(target_arch = "wasm32")]
+
+use linked_data::types::IPNSAddress;
+
+use utils::notifications::{self, NotificationSettings as Settings};
+
+use web_sys::HtmlSelectElement;
+
+use ybc::{
+    Block, Button, Container, Control, Field, Level, LevelItem, LevelLeft, LevelRight, Section,
+    Subtitle,
+};
+
+use yew::{platform::spawn_local, prelude::*};
+
+#[cfg(debug_assertions)]
+use gloo_console::info;
+
+/// Permission, quiet hours and channels opted in to new content notifications.
+pub struct NotificationSettings {
+    settings: Settings,
+
+    granted: bool,
+}
+
+pub enum Msg {
+    Request,
+    Granted(bool),
+    QuietStart(Option<u8>),
+    QuietEnd(Option<u8>),
+    Remove(IPNSAddress),
+}
+
+impl Component for NotificationSettings {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        #[cfg(debug_assertions)]
+        info!("Notification Setting Create");
+
+        Self {
+            settings: notifications::get_settings(),
+            granted: notifications::is_granted(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        #[cfg(debug_assertions)]
+        info!("Notification Setting Update");
+
+        match msg {
+            Msg::Request => {
+                let cb = ctx.link().callback(Msg::Granted);
+
+                spawn_local(async move {
+                    cb.emit(notifications::request_permission().await);
+                });
+
+                false
+            }
+            Msg::Granted(granted) => {
+                self.granted = granted;
+
+                true
+            }
+            Msg::QuietStart(hour) => {
+                self.settings.quiet_start = hour;
+
+                notifications::set_settings(&self.settings);
+
+                true
+            }
+            Msg::QuietEnd(hour) => {
+                self.settings.quiet_end = hour;
+
+                notifications::set_settings(&self.settings);
+
+                true
+            }
+            Msg::Remove(addr) => {
+                self.settings.channels.remove(&addr);
+
+                notifications::set_settings(&self.settings);
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        #[cfg(debug_assertions)]
+        info!("Notification Setting View");
+
+        if !notifications::is_supported() {
+            return html! {};
+        }
+
+        let start_cb = ctx
+            .link()
+            .callback(|e: Event| Msg::QuietStart(selected_hour(e)));
+        let end_cb = ctx
+            .link()
+            .callback(|e: Event| Msg::QuietEnd(selected_hour(e)));
+
+        html! {
+        <Section>
+            <Container>
+                <Subtitle>
+                    {"Notifications"}
+                </Subtitle>
+                <Block>
+                <small>
+                    {"Opt in from a channel page with the bell button. New content is notified while this tab is open."}
+                </small>
+                </Block>
+                if !self.granted {
+                <Block>
+                    <Button onclick={ctx.link().callback(|_| Msg::Request)} >
+                        {"Allow notifications"}
+                    </Button>
+                </Block>
+                }
+                <Field label="Quiet Hours" help={"No notifications between these hours."} grouped=true >
+                    <Control>
+                        { render_hours(self.settings.quiet_start, start_cb) }
+                    </Control>
+                    <Control>
+                        { render_hours(self.settings.quiet_end, end_cb) }
+                    </Control>
+                </Field>
+                { self.render_channels(ctx) }
+            </Container>
+        </Section>
+        }
+    }
+}
+
+impl NotificationSettings {
+    fn render_channels(&self, ctx: &Context<Self>) -> Html {
+        let mut channels: Vec<_> = self.settings.channels.iter().copied().collect();
+        channels.sort_unstable_by_key(|addr| addr.to_string());
+
+        channels
+            .into_iter()
+            .map(|addr| {
+                html! {
+                <Level key={addr.to_string()} >
+                    <LevelLeft>
+                        <LevelItem>
+                            <span class="icon"><i class="fa-solid fa-bell"></i></span>
+                        </LevelItem>
+                        <LevelItem>
+                            <code>{ addr.to_string() }</code>
+                        </LevelItem>
+                    </LevelLeft>
+                    <LevelRight>
+                        <LevelItem>
+                            <Button onclick={ctx.link().callback(move |_| Msg::Remove(addr))} >
+                                <span class="icon is-small">
+                                    <i class="fa-solid fa-trash-can"></i>
+                                </span>
+                            </Button>
+                        </LevelItem>
+                    </LevelRight>
+                </Level>
+                }
+            })
+            .collect::<Html>()
+    }
+}
+
+fn render_hours(selected: Option<u8>, onchange: Callback<Event>) -> Html {
+    html! {
+    <div class="select is-small">
+        <select {onchange} >
+            <option value="" selected={selected.is_none()} >{"Off"}</option>
+            {
+                (0..24u8).map(|hour| html! {
+                    <option value={hour.to_string()} selected={selected == Some(hour)} >
+                        { format!("{:02}:00", hour) }
+                    </option>
+                }).collect::<Html>()
+            }
+        </select>
+    </div>
+    }
+}
+
+fn selected_hour(e: Event) -> Option<u8> {
+    e.target_unchecked_into::<HtmlSelectElement>()
+        .value()
+        .parse()
+        .ok()
+}
@@ -0,0 +1,188 @@
+#![cfg(target_arch = "wasm32")]
+
+use ybc::{Block, Button, Container, Level, LevelItem, LevelLeft, LevelRight, Section, Subtitle};
+
+use yew::{platform::spawn_local, prelude::*};
+
+use utils::{
+    defluencer::ChannelContext,
+    outbox::{self, Pending},
+    timestamp_to_datetime,
+};
+
+#[cfg(debug_assertions)]
+use gloo_console::info;
+
+/// List channel updates waiting to be published.
+pub struct OutboxSettings {
+    _handle: Option<ContextHandle<ChannelContext>>,
+    channel: Option<ChannelContext>,
+
+    pendings_cb: Callback<Vec<Pending>>,
+    pendings: Vec<Pending>,
+
+    loading: bool,
+}
+
+pub enum Msg {
+    Channel(ChannelContext),
+    Pendings(Vec<Pending>),
+    RetryAll,
+    Remove(String),
+}
+
+impl Component for OutboxSettings {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        #[cfg(debug_assertions)]
+        info!("Outbox Setting Create");
+
+        let (channel, _handle) = match ctx
+            .link()
+            .context::<ChannelContext>(ctx.link().callback(Msg::Channel))
+        {
+            Some((context, handle)) => (Some(context), Some(handle)),
+            None => (None, None),
+        };
+
+        let pendings_cb = ctx.link().callback(Msg::Pendings);
+
+        spawn_local(refresh(pendings_cb.clone()));
+
+        Self {
+            _handle,
+            channel,
+
+            pendings_cb,
+            pendings: Vec::new(),
+
+            loading: true,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        #[cfg(debug_assertions)]
+        info!("Outbox Setting Update");
+
+        match msg {
+            Msg::Channel(context) => {
+                self.channel = Some(context);
+
+                true
+            }
+            Msg::Pendings(pendings) => {
+                self.pendings = pendings;
+                self.loading = false;
+
+                true
+            }
+            Msg::RetryAll => {
+                let channel = match self.channel.as_ref() {
+                    Some(context) => context.clone(),
+                    None => return false,
+                };
+
+                spawn_local({
+                    let cb = self.pendings_cb.clone();
+
+                    async move {
+                        outbox::replay(&channel, true).await;
+
+                        refresh(cb).await;
+                    }
+                });
+
+                self.loading = true;
+
+                true
+            }
+            Msg::Remove(id) => {
+                spawn_local({
+                    let cb = self.pendings_cb.clone();
+
+                    async move {
+                        outbox::remove(&id).await;
+
+                        refresh(cb).await;
+                    }
+                });
+
+                false
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        #[cfg(debug_assertions)]
+        info!("Outbox Setting View");
+
+        if self.pendings.is_empty() {
+            return html! {};
+        }
+
+        html! {
+        <Section>
+            <Container>
+                <Subtitle>
+                    {"Outbox"}
+                </Subtitle>
+                <Block>
+                <small>
+                    {"Channel updates that could not be published are retried automatically while your node is online."}
+                </small>
+                </Block>
+                <Block>
+                    { self.pendings.iter().map(|pending| self.render_pending(ctx, pending)).collect::<Html>() }
+                </Block>
+                <Button classes={classes!(self.loading.then_some("is-loading"))} disabled={self.channel.is_none()} onclick={ctx.link().callback(|_| Msg::RetryAll)} >
+                    { "Retry all" }
+                </Button>
+            </Container>
+        </Section>
+        }
+    }
+}
+
+impl OutboxSettings {
+    fn render_pending(&self, ctx: &Context<Self>, pending: &Pending) -> Html {
+        let id = pending.id.clone();
+        let next_attempt = timestamp_to_datetime((pending.next_attempt / 1000.0) as i64);
+
+        html! {
+        <Level key={pending.id.clone()} >
+            <LevelLeft>
+                <LevelItem>
+                    <span class="tag">{ format!("{} attempts", pending.attempts) }</span>
+                </LevelItem>
+                <LevelItem>
+                    <div>
+                        <p>{ pending.operation.describe() }</p>
+                        <p><small>{ format!("Next attempt {}", next_attempt) }</small></p>
+                        if let Some(error) = pending.last_error.as_ref() {
+                        <details>
+                            <summary><small>{ "Last error" }</small></summary>
+                            <pre style="max-height: 10rem; overflow: auto;" >{ error }</pre>
+                        </details>
+                        }
+                    </div>
+                </LevelItem>
+            </LevelLeft>
+            <LevelRight>
+                <LevelItem>
+                    <Button onclick={ctx.link().callback(move |_| Msg::Remove(id.clone()))} >
+                        <span class="icon is-small">
+                            <i class="fa-solid fa-trash-can"></i>
+                        </span>
+                    </Button>
+                </LevelItem>
+            </LevelRight>
+        </Level>
+        }
+    }
+}
+
+async fn refresh(callback: Callback<Vec<Pending>>) {
+    callback.emit(outbox::list().await);
+}
@@ -0,0 +1,410 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{
+    collections::{HashSet, VecDeque},
+    rc::Rc,
+};
+
+use cid::Cid;
+
+use defluencer::{crypto::signed_link::SignedLink, Defluencer};
+
+use futures_util::stream::{
+    self, AbortHandle, AbortRegistration, Abortable, StreamExt, TryStreamExt,
+};
+
+use gloo_console::error;
+
+#[cfg(debug_assertions)]
+use gloo_console::info;
+
+use gloo_storage::{LocalStorage, Storage};
+
+use gloo_timers::future::IntervalStream;
+
+use linked_data::{
+    channel::ChannelMetadata,
+    identity::Identity,
+    media::{comments::Comment, Media},
+    types::{IPLDLink, IPNSAddress},
+};
+
+use serde::{Deserialize, Serialize};
+
+use wasm_bindgen_futures::spawn_local;
+
+use crate::{
+    client::{ClientError, IpfsClient, PubSubMessage},
+    defluencer::ChannelContext,
+    errors::AppError,
+    mute::{MuteEntry, MuteList},
+    outbox::{self, Operation},
+    r#async::{cached_dag_get, index_stream, is_signer},
+    subscriptions,
+};
+
+const AGGREGATION_SETTINGS: &str = "aggregation_settings";
+
+/// Replies deeper than this are not traced back to the channel content.
+const MAX_THREAD_DEPTH: usize = 32;
+
+/// Milliseconds between channel updates adding the aggregated comments.
+const FLUSH_INTERVAL: u32 = 60_000;
+
+/// Comments added per channel update, the others wait for the next one.
+const MAX_BATCH: usize = 10;
+
+/// Comments waiting for a channel update, newer ones are dropped.
+const MAX_PENDING: usize = 1_000;
+
+/// Comments remembered to skip messages sent again.
+const MAX_SEEN: usize = 10_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AggregationSettings {
+    /// Index comments received on the channel aggregation topic.
+    pub enabled: bool,
+
+    /// Only index comments from followed channels or matching an allow rule.
+    pub allow_only: bool,
+
+    pub allow: Vec<MuteEntry>,
+
+    /// Comments matching a deny rule are never indexed.
+    pub deny: Vec<MuteEntry>,
+}
+
+impl Default for AggregationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allow_only: true,
+            allow: Vec::new(),
+            deny: Vec::new(),
+        }
+    }
+}
+
+pub fn get_settings() -> AggregationSettings {
+    LocalStorage::get(AGGREGATION_SETTINGS).unwrap_or_default()
+}
+
+pub fn set_settings(settings: &AggregationSettings) {
+    if let Err(e) = LocalStorage::set(AGGREGATION_SETTINGS, settings) {
+        error!(&format!("{:?}", e));
+    }
+}
+
+/// Settings ready for matching.
+struct Rules {
+    enabled: bool,
+    allow_only: bool,
+
+    // Rules share the mute list matching
+    allow: MuteList,
+    deny: MuteList,
+
+    follows: HashSet<IPNSAddress>,
+}
+
+impl Rules {
+    fn load() -> Self {
+        let settings = get_settings();
+
+        Self {
+            enabled: settings.enabled,
+            allow_only: settings.allow_only,
+            allow: MuteList::new(settings.allow),
+            deny: MuteList::new(settings.deny),
+            follows: subscriptions::get_sub_list(),
+        }
+    }
+
+    fn accepts(&self, comment: &Comment, identity: &Identity) -> bool {
+        let media = Media::Comment(comment.clone());
+
+        if self.deny.mutes_media(&media, Some(identity)) {
+            return false;
+        }
+
+        if !self.allow_only {
+            return true;
+        }
+
+        let followed = identity
+            .ipns_addr
+            .map_or(false, |addr| self.follows.contains(&addr));
+
+        followed || self.allow.mutes_media(&media, Some(identity))
+    }
+}
+
+/// Index comments sent to the channel aggregation topic.
+///
+/// The listener stops when dropped.
+pub struct Aggregator {
+    handle: AbortHandle,
+}
+
+impl Aggregator {
+    pub fn new(ipfs: Rc<dyn IpfsClient>, channel: ChannelContext) -> Self {
+        let (handle, regis) = AbortHandle::new_pair();
+
+        spawn_local(listen(ipfs, channel, regis));
+
+        Self { handle }
+    }
+}
+
+impl Drop for Aggregator {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+enum Event {
+    Message(Result<PubSubMessage, ClientError>),
+    Flush,
+}
+
+async fn listen(ipfs: Rc<dyn IpfsClient>, channel: ChannelContext, regis: AbortRegistration) {
+    let addr = channel.channel.get_address();
+
+    let metadata = match channel_metadata(&ipfs, addr).await {
+        Ok(metadata) => metadata,
+        Err(e) => return error!(&format!("{:#?}", e)),
+    };
+
+    let topic = match metadata.agregation_channel {
+        Some(topic) => topic,
+        None => return,
+    };
+
+    let mut content = ChannelContent::default();
+    let mut seen = Seen::default();
+    let mut batch = Vec::new();
+
+    let messages = ipfs.pubsub_sub(topic).map(Event::Message);
+    let flushes = IntervalStream::new(FLUSH_INTERVAL).map(|_| Event::Flush);
+
+    let stream = Abortable::new(stream::select(messages, flushes), regis);
+
+    futures_util::pin_mut!(stream);
+
+    while let Some(event) = stream.next().await {
+        let result = match event {
+            Event::Message(result) => result,
+            Event::Flush => {
+                flush(&channel, &mut batch).await;
+                continue;
+            }
+        };
+
+        let msg = match result {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!(&format!("{:#?}", e));
+                continue;
+            }
+        };
+
+        let cid = match Cid::try_from(msg.data) {
+            Ok(cid) => cid,
+            Err(e) => {
+                error!(&format!("{:#?}", e));
+                continue;
+            }
+        };
+
+        let rules = Rules::load();
+
+        if !rules.enabled {
+            continue;
+        }
+
+        if batch.len() >= MAX_PENDING {
+            error!("Too Many Comments Pending Aggregation!");
+            continue;
+        }
+
+        if seen.contains(&cid) {
+            continue;
+        }
+
+        // Comments that failed to be checked can be sent again
+        match on_comment(&ipfs, &channel, &mut content, &rules, cid).await {
+            Ok(true) => {
+                seen.insert(cid);
+                batch.push(cid.into());
+            }
+            Ok(false) => seen.insert(cid),
+            Err(e) => error!(&format!("{:#?}", e)),
+        }
+    }
+}
+
+/// Add a batch of aggregated comments to the channel comment index.
+///
+/// Failed updates stay queued in the outbox.
+async fn flush(channel: &ChannelContext, batch: &mut Vec<IPLDLink>) {
+    if batch.is_empty() {
+        return;
+    }
+
+    let count = batch.len().min(MAX_BATCH);
+    let comments = batch.drain(..count).collect();
+
+    match outbox::submit(channel, Operation::AddComments(comments)).await {
+        Ok(_) => {
+            #[cfg(debug_assertions)]
+            info!(&format!("{} Comments Aggregated", count));
+        }
+        Err(e) => error!(&format!("{:#?}", e)),
+    }
+}
+
+/// Verify the comment.
+///
+/// Returns true if the comment should be indexed.
+async fn on_comment(
+    ipfs: &Rc<dyn IpfsClient>,
+    channel: &ChannelContext,
+    content: &mut ChannelContent,
+    rules: &Rules,
+    cid: Cid,
+) -> Result<bool, AppError> {
+    let signed_link = cached_dag_get::<SignedLink>(&**ipfs, cid, None).await?;
+
+    if !signed_link.verify() {
+        error!("Comment Signature Verification Failed!");
+        return Ok(false);
+    }
+
+    let comment = cached_dag_get::<Comment>(&**ipfs, signed_link.link.link, None).await?;
+
+    let identity = cached_dag_get::<Identity>(&**ipfs, comment.identity.link, None).await?;
+
+    if !is_signer(&identity, &signed_link) {
+        error!("Comment Signer Verification Failed!");
+        return Ok(false);
+    }
+
+    let origin = match comment.origin {
+        Some(origin) => origin,
+        None => return Ok(false),
+    };
+
+    let metadata = channel_metadata(ipfs, channel.channel.get_address()).await?;
+
+    // The owner comments are already indexed
+    if comment.identity == metadata.identity {
+        return Ok(false);
+    }
+
+    if !rules.accepts(&comment, &identity) {
+        return Ok(false);
+    }
+
+    content.refresh(ipfs, metadata.content_index).await?;
+
+    if !content.is_thread_of(ipfs, origin).await {
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+/// Most recent comment CIDs received.
+#[derive(Default)]
+struct Seen {
+    set: HashSet<Cid>,
+    order: VecDeque<Cid>,
+}
+
+impl Seen {
+    fn contains(&self, cid: &Cid) -> bool {
+        self.set.contains(cid)
+    }
+
+    fn insert(&mut self, cid: Cid) {
+        if !self.set.insert(cid) {
+            return;
+        }
+
+        self.order.push_back(cid);
+
+        if self.order.len() > MAX_SEEN {
+            if let Some(oldest) = self.order.pop_front() {
+                self.set.remove(&oldest);
+            }
+        }
+    }
+}
+
+async fn channel_metadata(
+    ipfs: &Rc<dyn IpfsClient>,
+    addr: IPNSAddress,
+) -> Result<ChannelMetadata, AppError> {
+    let cid = ipfs
+        .name_resolve(addr.into())
+        .await
+        .map_err(AppError::ipns)?;
+
+    let metadata = ipfs.dag_get::<ChannelMetadata>(cid, None).await?;
+
+    Ok(metadata)
+}
+
+/// Content CIDs of the channel, rebuilt when the content index changes.
+#[derive(Default)]
+struct ChannelContent {
+    index: Option<IPLDLink>,
+
+    content: HashSet<Cid>,
+}
+
+impl ChannelContent {
+    async fn refresh(
+        &mut self,
+        ipfs: &Rc<dyn IpfsClient>,
+        index: Option<IPLDLink>,
+    ) -> Result<(), AppError> {
+        if self.index == index {
+            return Ok(());
+        }
+
+        let mut content = HashSet::new();
+
+        if let Some(index) = index {
+            let defluencer = ipfs.service().map(Defluencer::from);
+
+            content = index_stream(ipfs, defluencer.as_ref(), index)
+                .try_collect()
+                .await?;
+        }
+
+        self.index = index;
+        self.content = content;
+
+        Ok(())
+    }
+
+    /// Follow replies up to the content they belong to.
+    async fn is_thread_of(&self, ipfs: &Rc<dyn IpfsClient>, mut origin: Cid) -> bool {
+        for _ in 0..MAX_THREAD_DEPTH {
+            if self.content.contains(&origin) {
+                return true;
+            }
+
+            origin = match cached_dag_get::<Comment>(&**ipfs, origin, Some("/link")).await {
+                Ok(comment) => match comment.origin {
+                    Some(origin) => origin,
+                    None => return false,
+                },
+                Err(_) => return false,
+            };
+        }
+
+        false
+    }
+}
@@ -1,32 +1,44 @@
 #![cfg(target_arch = "wasm32")]
 
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    rc::Rc,
+};
 
 use cid::Cid;
 
 use gloo_console::error;
 
-use ipfs_api::IpfsService;
+use gloo_timers::future::TimeoutFuture;
 
 use linked_data::{
-    channel::ChannelMetadata,
+    channel::{follows::Follows, ChannelMetadata},
+    identity::Identity,
     media::Media,
     types::{IPLDLink, IPNSAddress},
 };
 
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Serialize};
 
 use yew::Callback;
 
-use defluencer::Defluencer;
+use defluencer::{crypto::signed_link::SignedLink, Defluencer};
+
+use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
 
 use futures_util::stream::{
-    AbortRegistration, Abortable, FuturesUnordered, StreamExt, TryStreamExt,
+    self, AbortRegistration, Abortable, FuturesUnordered, LocalBoxStream, StreamExt, TryStreamExt,
+};
+
+use crate::{
+    cache,
+    client::{walk_index, ClientError, IpfsClient},
+    subscriptions::{set_unread_counts, Unread, UnreadCounts, MAX_UNREAD},
 };
 
 /// Resolve multiple IPNS addresses then get the channel metadata.
 pub async fn get_channels(
-    ipfs: IpfsService,
+    ipfs: Rc<dyn IpfsClient>,
     callback: Callback<(IPNSAddress, Cid, ChannelMetadata)>,
     addresses: HashSet<IPNSAddress>,
 ) {
@@ -51,7 +63,7 @@ pub async fn get_channels(
             let ipfs = ipfs.clone();
 
             async move {
-                match ipfs.dag_get::<&str, ChannelMetadata>(cid, None).await {
+                match cached_dag_get::<ChannelMetadata>(&*ipfs, cid, None).await {
                     Ok(dag) => Ok((addr, cid, dag)),
                     Err(e) => Err(e),
                 }
@@ -69,23 +81,22 @@ pub async fn get_channels(
 
 /// Subscribe and get latest channel metadata
 pub async fn channel_subscribe(
-    ipfs: IpfsService,
+    ipfs: Rc<dyn IpfsClient>,
     callback: Callback<(IPNSAddress, Cid, ChannelMetadata)>,
     addr: IPNSAddress,
     regis: AbortRegistration,
 ) {
-    let defluencer = Defluencer::from(ipfs.clone());
+    let defluencer = ipfs.service().map(Defluencer::from);
 
-    let stream = defluencer
-        .subscribe_channel_updates(addr)
+    let stream = channel_updates(&ipfs, defluencer.as_ref(), addr)
         .map(|result| {
             let ipfs = ipfs.clone();
 
             async move {
                 match result {
-                    Ok(cid) => match ipfs.dag_get::<&str, ChannelMetadata>(cid, None).await {
+                    Ok(cid) => match cached_dag_get::<ChannelMetadata>(&*ipfs, cid, None).await {
                         Ok(dag) => Ok((addr, cid, dag)),
-                        Err(e) => Err(e.into()),
+                        Err(e) => Err(e),
                     },
                     Err(e) => Err(e),
                 }
@@ -105,24 +116,196 @@ pub async fn channel_subscribe(
     }
 }
 
-/// Stream all content of a channel
+/// Milliseconds between resolutions of a channel address without a node.
+const POLL_INTERVAL: u32 = 30_000;
+
+/// Channel metadata roots as they are published.
+///
+/// IPNS over pubsub records are decoded by defluencer when a node is available,
+/// otherwise the address is resolved periodically.
+fn channel_updates<'a>(
+    ipfs: &Rc<dyn IpfsClient>,
+    defluencer: Option<&'a Defluencer>,
+    addr: IPNSAddress,
+) -> LocalBoxStream<'a, Result<Cid, ClientError>> {
+    if let Some(defluencer) = defluencer {
+        return defluencer
+            .subscribe_channel_updates(addr)
+            .map_err(ClientError::from)
+            .boxed_local();
+    }
+
+    let ipfs = ipfs.clone();
+
+    stream::unfold(None, move |mut last| {
+        let ipfs = ipfs.clone();
+
+        async move {
+            loop {
+                TimeoutFuture::new(POLL_INTERVAL).await;
+
+                match ipfs.name_resolve(addr.into()).await {
+                    Ok(cid) if last == Some(cid) => continue,
+                    Ok(cid) => {
+                        last = Some(cid);
+
+                        return Some((Ok(cid), last));
+                    }
+                    Err(e) => return Some((Err(e), last)),
+                }
+            }
+        }
+    })
+    .boxed_local()
+}
+
+/// Content CIDs of an index, newest first when a node is available.
+pub(crate) fn index_stream<'a>(
+    ipfs: &Rc<dyn IpfsClient>,
+    defluencer: Option<&'a Defluencer>,
+    index: IPLDLink,
+) -> LocalBoxStream<'a, Result<Cid, ClientError>> {
+    match defluencer {
+        Some(defluencer) => defluencer
+            .stream_content_rev_chrono(index)
+            .map_err(ClientError::from)
+            .boxed_local(),
+        None => walk_index(ipfs.clone(), index.link),
+    }
+}
+
+/// Comment CIDs of a comment index.
+///
+/// Without a node the whole index is walked, comments on other content must be filtered out.
+pub fn comment_stream<'a>(
+    ipfs: &Rc<dyn IpfsClient>,
+    defluencer: Option<&'a Defluencer>,
+    index: IPLDLink,
+    content: Cid,
+) -> LocalBoxStream<'a, Result<Cid, ClientError>> {
+    match defluencer {
+        Some(defluencer) => defluencer
+            .stream_content_comments(index, content)
+            .map_err(ClientError::from)
+            .boxed_local(),
+        None => walk_index(ipfs.clone(), index.link),
+    }
+}
+
+/// Crawl channels through their follows, breadth first.
+///
+/// Each channel is visited once.
+pub fn web_crawl(
+    ipfs: Rc<dyn IpfsClient>,
+    follows: HashSet<IPNSAddress>,
+) -> LocalBoxStream<'static, Result<(IPNSAddress, ChannelMetadata), ClientError>> {
+    let queue: VecDeque<IPNSAddress> = follows.iter().copied().collect();
+
+    stream::unfold((queue, follows), move |(mut queue, mut visited)| {
+        let ipfs = ipfs.clone();
+
+        async move {
+            let addr = queue.pop_front()?;
+
+            let result = get_metadata(&*ipfs, addr).await;
+
+            if let Ok(metadata) = &result {
+                match get_follows(&*ipfs, metadata).await {
+                    Ok(followees) => {
+                        for followee in followees {
+                            if visited.insert(followee) {
+                                queue.push_back(followee);
+                            }
+                        }
+                    }
+                    Err(e) => error!(&format!("{:#?}", e)),
+                }
+            }
+
+            Some((result.map(|metadata| (addr, metadata)), (queue, visited)))
+        }
+    })
+    .boxed_local()
+}
+
+/// Identities of the channels followed by this one, keyed by CID.
+pub async fn followees_identity(
+    ipfs: &dyn IpfsClient,
+    metadata: &ChannelMetadata,
+) -> HashMap<Cid, Identity> {
+    let followees = match get_follows(ipfs, metadata).await {
+        Ok(followees) => followees,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return HashMap::default();
+        }
+    };
+
+    let mut pool: FuturesUnordered<_> = followees
+        .into_iter()
+        .map(|addr| async move {
+            let metadata = get_metadata(ipfs, addr).await?;
+
+            let identity = cached_dag_get::<Identity>(ipfs, metadata.identity.link, None).await?;
+
+            Ok::<_, ClientError>((metadata.identity.link, identity))
+        })
+        .collect();
+
+    let mut identities = HashMap::default();
+
+    while let Some(result) = pool.next().await {
+        match result {
+            Ok((cid, identity)) => {
+                identities.insert(cid, identity);
+            }
+            Err(e) => error!(&format!("{:#?}", e)),
+        }
+    }
+
+    identities
+}
+
+async fn get_metadata(
+    ipfs: &dyn IpfsClient,
+    addr: IPNSAddress,
+) -> Result<ChannelMetadata, ClientError> {
+    let cid = ipfs.name_resolve(addr.into()).await?;
+
+    cached_dag_get::<ChannelMetadata>(ipfs, cid, None).await
+}
+
+async fn get_follows(
+    ipfs: &dyn IpfsClient,
+    metadata: &ChannelMetadata,
+) -> Result<HashSet<IPNSAddress>, ClientError> {
+    let follows = match metadata.follows.as_ref() {
+        Some(follows) => follows,
+        None => return Ok(HashSet::default()),
+    };
+
+    let follows = cached_dag_get::<Follows>(ipfs, follows.link, None).await?;
+
+    Ok(follows.followees)
+}
+
+/// Stream all content of a channel, with verification status.
 pub async fn stream_content(
-    ipfs: IpfsService,
-    callback: Callback<(Cid, Media)>,
+    ipfs: Rc<dyn IpfsClient>,
+    callback: Callback<(Cid, Media, bool)>,
     index: IPLDLink,
     regis: AbortRegistration,
 ) {
-    let defluencer = Defluencer::from(ipfs.clone());
+    let defluencer = ipfs.service().map(Defluencer::from);
 
-    let stream = defluencer
-        .stream_content_rev_chrono(index)
+    let stream = index_stream(&ipfs, defluencer.as_ref(), index)
         .map_ok(|cid| {
             let ipfs = ipfs.clone();
 
             async move {
-                match ipfs.dag_get::<&str, Media>(cid, Some("/link")).await {
-                    Ok(dag) => Ok((cid, dag)),
-                    Err(e) => Err(e.into()),
+                match get_signed_media(&*ipfs, cid).await {
+                    Ok((media, verified)) => Ok((cid, media, verified)),
+                    Err(e) => Err(e),
                 }
             }
         })
@@ -140,12 +323,225 @@ pub async fn stream_content(
     }
 }
 
-pub async fn dag_get<T>(ipfs: IpfsService, cid: Cid, callback: Callback<(Cid, T)>)
+/// Request more items from a paged stream.
+#[derive(Clone, Debug)]
+pub struct Pager {
+    sender: UnboundedSender<usize>,
+}
+
+/// Receiving half of a pager.
+#[derive(Debug)]
+pub struct PagerRegistration {
+    receiver: UnboundedReceiver<usize>,
+}
+
+impl Pager {
+    pub fn new_pair() -> (Self, PagerRegistration) {
+        let (sender, receiver) = unbounded();
+
+        (Self { sender }, PagerRegistration { receiver })
+    }
+
+    /// Fetch the next items.
+    pub fn request(&self, count: usize) {
+        if let Err(e) = self.sender.unbounded_send(count) {
+            error!(&format!("{:#?}", e));
+        }
+    }
+}
+
+/// Stream the content of a channel, newest first, one page at a time.
+///
+/// Nothing is fetched until a page is requested.
+/// The page callback receive false once the channel has no more content.
+pub async fn stream_content_paged(
+    ipfs: Rc<dyn IpfsClient>,
+    callback: Callback<(Cid, Media, bool)>,
+    page_cb: Callback<bool>,
+    index: IPLDLink,
+    regis: AbortRegistration,
+    pager: PagerRegistration,
+) {
+    let defluencer = ipfs.service().map(Defluencer::from);
+
+    let stream = index_stream(&ipfs, defluencer.as_ref(), index)
+        .map_ok(|cid| {
+            let ipfs = ipfs.clone();
+
+            async move {
+                match get_signed_media(&*ipfs, cid).await {
+                    Ok((media, verified)) => Ok((cid, media, verified)),
+                    Err(e) => Err(e),
+                }
+            }
+        })
+        .try_buffer_unordered(10);
+
+    let stream = Abortable::new(stream, regis);
+
+    futures_util::pin_mut!(stream);
+
+    let mut requests = pager.receiver;
+
+    // The stream is not polled between pages, pausing the index traversal
+    while let Some(count) = requests.next().await {
+        for _ in 0..count {
+            match stream.next().await {
+                Some(Ok(tuple)) => callback.emit(tuple),
+                Some(Err(e)) => error!(&format!("{:#?}", e)),
+                None => {
+                    if !stream.is_aborted() {
+                        page_cb.emit(false);
+                    }
+
+                    return;
+                }
+            }
+        }
+
+        page_cb.emit(true);
+    }
+}
+
+/// Count content published since last seen for every followee then save the counts.
+pub async fn count_unread(
+    ipfs: Rc<dyn IpfsClient>,
+    addresses: HashSet<IPNSAddress>,
+    last_seen: HashMap<IPNSAddress, i64>,
+    callback: Callback<UnreadCounts>,
+) {
+    let mut pool: FuturesUnordered<_> = addresses
+        .into_iter()
+        .map(|addr| {
+            let since = last_seen.get(&addr).copied().unwrap_or(i64::MIN);
+
+            channel_unread(ipfs.clone(), addr, since)
+        })
+        .collect();
+
+    let mut channels = Vec::new();
+
+    while let Some(result) = pool.next().await {
+        match result {
+            Ok(unread) if unread.count > 0 => channels.push(unread),
+            Ok(_) => {}
+            Err(e) => error!(&format!("{:#?}", e)),
+        }
+    }
+
+    channels.sort_unstable_by(|a, b| b.count.cmp(&a.count));
+
+    let counts = UnreadCounts {
+        updated: js_sys::Date::now(),
+        channels,
+    };
+
+    set_unread_counts(&counts);
+
+    callback.emit(counts);
+}
+
+async fn channel_unread(
+    ipfs: Rc<dyn IpfsClient>,
+    addr: IPNSAddress,
+    since: i64,
+) -> Result<Unread, ClientError> {
+    let root = ipfs.name_resolve(addr.into()).await?;
+
+    let metadata = cached_dag_get::<ChannelMetadata>(&*ipfs, root, None).await?;
+    let identity = cached_dag_get::<Identity>(&*ipfs, metadata.identity.link, None).await?;
+
+    let mut unread = Unread {
+        addr,
+        name: identity.name,
+        count: 0,
+    };
+
+    let index = match metadata.content_index {
+        Some(index) => index,
+        None => return Ok(unread),
+    };
+
+    let defluencer = ipfs.service().map(Defluencer::from);
+
+    // Ordered so that counting can stop at the first seen content
+    let stream = index_stream(&ipfs, defluencer.as_ref(), index)
+        .map_ok(|cid| {
+            let ipfs = ipfs.clone();
+
+            async move { cached_dag_get::<Media>(&*ipfs, cid, Some("/link")).await }
+        })
+        .try_buffered(10);
+
+    futures_util::pin_mut!(stream);
+
+    while let Some(media) = stream.try_next().await? {
+        if media.user_timestamp() <= since || unread.count >= MAX_UNREAD {
+            break;
+        }
+
+        unread.count += 1;
+    }
+
+    Ok(unread)
+}
+
+/// Get a DAG node from the block cache or IPFS.
+pub async fn dag_get<T>(ipfs: Rc<dyn IpfsClient>, cid: Cid, callback: Callback<(Cid, T)>)
 where
-    T: ?Sized + DeserializeOwned,
+    T: Serialize + DeserializeOwned,
 {
-    match ipfs.dag_get::<&str, T>(cid, None).await {
+    match cached_dag_get::<T>(&*ipfs, cid, None).await {
         Ok(dag) => callback.emit((cid, dag)),
         Err(e) => error!(&format!("{:#?}", e)),
     }
 }
+
+/// Get a media from its signed link, forged signatures are an error.
+///
+/// Media signed by another wallet than the one of its identity are not verified.
+pub async fn get_signed_media(
+    ipfs: &dyn IpfsClient,
+    cid: Cid,
+) -> Result<(Media, bool), ClientError> {
+    let signed_link = cached_dag_get::<SignedLink>(ipfs, cid, None).await?;
+
+    if !signed_link.verify() {
+        return Err(ClientError::Signature(cid));
+    }
+
+    let media = cached_dag_get::<Media>(ipfs, signed_link.link.link, None).await?;
+
+    let identity = cached_dag_get::<Identity>(ipfs, media.identity().link, None).await?;
+
+    Ok((media, is_signer(&identity, &signed_link)))
+}
+
+/// True if the identity wallet signed the link.
+pub fn is_signer(identity: &Identity, signed_link: &SignedLink) -> bool {
+    identity.eth_addr.as_deref().map_or(false, |addr| {
+        addr.eq_ignore_ascii_case(&signed_link.get_address())
+    })
+}
+
+/// Since CIDs are immutable, the cache is always checked first.
+pub async fn cached_dag_get<T>(
+    ipfs: &dyn IpfsClient,
+    cid: Cid,
+    path: Option<&'static str>,
+) -> Result<T, ClientError>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let key = cache::cache_key(cid, path);
+
+    if let Some(dag) = cache::get::<T>(&key).await {
+        return Ok(dag);
+    }
+
+    let dag = ipfs.dag_get::<T>(cid, path).await?;
+
+    cache::put(&key, &dag).await;
+
+    Ok(dag)
+}
@@ -0,0 +1,408 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    net::IpAddr,
+    str::FromStr,
+};
+
+use cid::Cid;
+
+use linked_data::types::{IPLDLink, IPNSAddress};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    identity, ipfs,
+    mute::{self, MuteEntry},
+    subscriptions, web3,
+};
+
+/// Incremented when the format changes, older files can still be imported.
+pub const BACKUP_VERSION: u32 = 1;
+
+pub const FILE_NAME: &str = "defluencer_backup.json";
+
+/// Local app state, as a portable file.
+///
+/// Addresses and CIDs are strings so that invalid entries can be skipped one by one.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Backup {
+    pub version: u32,
+
+    /// Unix time in milliseconds
+    #[serde(default)]
+    pub created: f64,
+
+    /// List name to channel addresses.
+    #[serde(default)]
+    pub subscriptions: BTreeMap<String, Vec<String>>,
+
+    /// Identity CIDs
+    #[serde(default)]
+    pub identities: Vec<String>,
+
+    #[serde(default)]
+    pub current_identity: Option<String>,
+
+    #[serde(default)]
+    pub wallet_addr: Option<String>,
+
+    /// Ordered IPFS api urls
+    #[serde(default)]
+    pub ipfs_addrs: Vec<String>,
+
+    #[serde(default)]
+    pub gateway_addr: Option<String>,
+
+    #[serde(default)]
+    pub mute_list: Vec<MuteEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackupError {
+    /// Not a JSON backup file.
+    Json(String),
+
+    /// Made by a newer version of the app.
+    Version(u32),
+}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "Invalid backup file: {}", e),
+            Self::Version(version) => write!(
+                f,
+                "Backup version {} is not supported, update the app first.",
+                version
+            ),
+        }
+    }
+}
+
+/// What changed after an import.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    pub channels: usize,
+    pub identities: usize,
+    pub settings: usize,
+
+    /// Entries that failed validation.
+    pub invalid: Vec<String>,
+
+    /// IPFS APIs on other machines, only added once confirmed with [`add_ipfs_addrs`].
+    pub remote_ipfs_addrs: Vec<String>,
+}
+
+/// Collect the local app state.
+pub fn export() -> Backup {
+    let subscriptions = subscriptions::get_lists()
+        .into_iter()
+        .map(|(name, list)| {
+            let mut addrs: Vec<String> = list.into_iter().map(|addr| addr.to_string()).collect();
+            addrs.sort_unstable();
+
+            (name, addrs)
+        })
+        .collect();
+
+    let identities = identity::get_identities()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|ipld| ipld.link.to_string())
+        .collect();
+
+    let gateway = ipfs::get_gateway_addr();
+
+    Backup {
+        version: BACKUP_VERSION,
+        created: js_sys::Date::now(),
+        subscriptions,
+        identities,
+        current_identity: identity::get_current_identity().map(|ipld| ipld.link.to_string()),
+        wallet_addr: web3::get_wallet_addr(),
+        ipfs_addrs: ipfs::get_ipfs_addrs(),
+        gateway_addr: (gateway != ipfs::DEFAULT_GATEWAY).then_some(gateway),
+        mute_list: mute::get_mute_list(),
+    }
+}
+
+pub fn to_json(backup: &Backup) -> String {
+    serde_json::to_string_pretty(backup).unwrap_or_default()
+}
+
+pub fn from_json(text: &str) -> Result<Backup, BackupError> {
+    let backup: Backup =
+        serde_json::from_str(text).map_err(|e| BackupError::Json(e.to_string()))?;
+
+    if backup.version == 0 || backup.version > BACKUP_VERSION {
+        return Err(BackupError::Version(backup.version));
+    }
+
+    Ok(backup)
+}
+
+/// Merge a backup into the local app state.
+///
+/// Lists and identities are combined, local settings are only replaced when unset.
+/// IPFS APIs receive every request, those not on this machine must be confirmed first.
+pub fn import(backup: Backup) -> ImportReport {
+    let mut report = ImportReport::default();
+
+    let mut lists = subscriptions::get_lists();
+    let before: HashSet<IPNSAddress> = lists.values().flatten().copied().collect();
+
+    for (name, addrs) in backup.subscriptions {
+        let name = name.trim().to_owned();
+
+        if name.is_empty() {
+            report.invalid.push(String::from("Unnamed list"));
+            continue;
+        }
+
+        let list = lists.entry(name).or_default();
+
+        for addr in addrs {
+            match IPNSAddress::from_str(&addr) {
+                Ok(addr) => {
+                    list.insert(addr);
+                }
+                Err(_) => report.invalid.push(addr),
+            }
+        }
+    }
+
+    report.channels = lists.values().flatten().collect::<HashSet<_>>().len() - before.len();
+
+    subscriptions::set_lists(&lists);
+
+    let mut identities = identity::get_identities().unwrap_or_default();
+    let count = identities.len();
+
+    for cid in backup.identities {
+        match Cid::try_from(cid.as_str()) {
+            Ok(cid) => {
+                identities.insert(IPLDLink::from(cid));
+            }
+            Err(_) => report.invalid.push(cid),
+        }
+    }
+
+    report.identities = identities.len() - count;
+
+    if report.identities > 0 {
+        identity::set_identities(identities);
+    }
+
+    if let Some(cid) = backup.current_identity {
+        match Cid::try_from(cid.as_str()) {
+            Ok(cid) if identity::get_current_identity().is_none() => {
+                identity::set_current_identity(cid.into());
+                report.settings += 1;
+            }
+            Ok(_) => {}
+            Err(_) => report.invalid.push(cid),
+        }
+    }
+
+    if let Some(addr) = backup.wallet_addr {
+        if !mute::is_eth_addr(&addr) {
+            report.invalid.push(addr);
+        } else if web3::get_wallet_addr().is_none() {
+            web3::set_wallet_addr(&addr);
+            report.settings += 1;
+        }
+    }
+
+    let mut ipfs_addrs = ipfs::get_ipfs_addrs();
+    let count = ipfs_addrs.len();
+
+    for url in backup.ipfs_addrs {
+        if !is_url(&url) {
+            report.invalid.push(url);
+        } else if ipfs_addrs.contains(&url) {
+            continue;
+        } else if is_loopback(&url) {
+            ipfs_addrs.push(url);
+        } else {
+            report.remote_ipfs_addrs.push(url);
+        }
+    }
+
+    if ipfs_addrs.len() > count {
+        report.settings += ipfs_addrs.len() - count;
+        ipfs::set_ipfs_addrs(&ipfs_addrs);
+    }
+
+    if let Some(url) = backup.gateway_addr {
+        if !is_url(&url) {
+            report.invalid.push(url);
+        } else if ipfs::get_gateway_addr() == ipfs::DEFAULT_GATEWAY {
+            ipfs::set_gateway_addr(&url);
+            report.settings += 1;
+        }
+    }
+
+    let mut mute_list = mute::get_mute_list();
+    let count = mute_list.len();
+
+    for entry in backup.mute_list {
+        if !entry.is_valid() {
+            report.invalid.push(entry.to_string());
+        } else if !mute_list.contains(&entry) {
+            mute_list.push(entry);
+        }
+    }
+
+    if mute_list.len() > count {
+        report.settings += mute_list.len() - count;
+        mute::set_mute_list(&mute_list);
+    }
+
+    report
+}
+
+/// Add IPFS APIs after the current ones.
+///
+/// Returns the number of APIs added.
+pub fn add_ipfs_addrs(urls: &[String]) -> usize {
+    let mut ipfs_addrs = ipfs::get_ipfs_addrs();
+    let count = ipfs_addrs.len();
+
+    for url in urls {
+        if is_url(url) && !ipfs_addrs.contains(url) {
+            ipfs_addrs.push(url.clone());
+        }
+    }
+
+    let added = ipfs_addrs.len() - count;
+
+    if added > 0 {
+        ipfs::set_ipfs_addrs(&ipfs_addrs);
+    }
+
+    added
+}
+
+fn is_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// True if the url host is this machine.
+fn is_loopback(url: &str) -> bool {
+    let authority = url
+        .trim_start_matches("http://")
+        .trim_start_matches("https://")
+        .split(|char| matches!(char, '/' | '?' | '#'))
+        .next()
+        .unwrap_or_default();
+
+    // Credentials could hide the real host
+    if authority.contains('@') {
+        return false;
+    }
+
+    let host = match authority.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+
+    if host.eq_ignore_ascii_case("localhost") {
+        return true;
+    }
+
+    host.parse::<IpAddr>().map_or(false, |ip| ip.is_loopback())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use gloo_storage::{LocalStorage, Storage};
+
+    use multihash::{Code, MultihashDigest};
+
+    use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+    use crate::ipld::{LIBP2P_KEY, RAW};
+
+    // Imports are stored in local storage
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn version_rejection() {
+        let json = |version: u32| format!("{{ \"version\": {} }}", version);
+
+        assert_eq!(from_json(&json(0)), Err(BackupError::Version(0)));
+        assert_eq!(
+            from_json(&json(BACKUP_VERSION + 1)),
+            Err(BackupError::Version(BACKUP_VERSION + 1))
+        );
+        assert!(from_json(&json(BACKUP_VERSION)).is_ok());
+        assert!(matches!(from_json("not json"), Err(BackupError::Json(_))));
+    }
+
+    #[wasm_bindgen_test]
+    fn loopback_urls() {
+        assert!(is_loopback("http://127.0.0.1:5001"));
+        assert!(is_loopback("http://localhost:5001/api/v0"));
+        assert!(is_loopback("http://[::1]:5001"));
+
+        assert!(!is_loopback("https://node.example.com:5001"));
+        assert!(!is_loopback("http://127.0.0.1@node.example.com:5001"));
+        assert!(!is_loopback("http://localhost.example.com"));
+        assert!(!is_loopback("http://192.168.1.2:5001"));
+    }
+
+    #[wasm_bindgen_test]
+    fn import_skips_invalid_entries() {
+        LocalStorage::clear();
+
+        let cid = Cid::new_v1(RAW, Code::Sha2_256.digest(b"identity"));
+        let addr = IPNSAddress::try_from(Cid::new_v1(LIBP2P_KEY, Code::Identity.digest(&[1; 32])))
+            .expect("Valid Address");
+
+        let local = "http://127.0.0.1:5001".to_owned();
+        let remote = "https://node.example.com:5001".to_owned();
+
+        let backup = Backup {
+            version: BACKUP_VERSION,
+            subscriptions: BTreeMap::from([(
+                "Friends".to_owned(),
+                vec![addr.to_string(), "not an address".to_owned()],
+            )]),
+            identities: vec![cid.to_string(), "not a cid".to_owned()],
+            current_identity: Some("not a cid either".to_owned()),
+            ipfs_addrs: vec![local.clone(), remote.clone(), "ftp://node".to_owned()],
+            ..Default::default()
+        };
+
+        let report = import(backup);
+
+        assert_eq!(report.channels, 1);
+        assert_eq!(report.identities, 1);
+        assert_eq!(
+            report.invalid,
+            vec![
+                "not an address".to_owned(),
+                "not a cid".to_owned(),
+                "not a cid either".to_owned(),
+                "ftp://node".to_owned(),
+            ]
+        );
+
+        // Remote APIs wait for confirmation
+        assert_eq!(report.remote_ipfs_addrs, vec![remote.clone()]);
+
+        let ipfs_addrs = ipfs::get_ipfs_addrs();
+        assert!(ipfs_addrs.contains(&local));
+        assert!(!ipfs_addrs.contains(&remote));
+
+        assert_eq!(add_ipfs_addrs(&report.remote_ipfs_addrs), 1);
+        assert!(ipfs::get_ipfs_addrs().contains(&remote));
+
+        LocalStorage::clear();
+    }
+}
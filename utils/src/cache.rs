@@ -0,0 +1,407 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+use cid::Cid;
+
+use gloo_console::error;
+
+use js_sys::JSON;
+
+use rexie::{Index, ObjectStore, Rexie, TransactionMode};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use wasm_bindgen::JsValue;
+
+use wasm_bindgen_futures::spawn_local;
+
+const DB_NAME: &str = "defluencer_cache";
+
+/// Decoded nodes, only read on cache hits.
+const NODE_STORE: &str = "nodes";
+
+/// Size and last access of each node, read when evicting.
+const ENTRY_STORE: &str = "entries";
+const ACCESS_INDEX: &str = "last_access";
+
+/// Maximum number of cached nodes.
+const MAX_ENTRIES: usize = 5000;
+
+/// Maximum total size of cached nodes in bytes.
+const MAX_BYTES: usize = 50 * 1024 * 1024;
+
+/// Eviction is checked once every X writes.
+const EVICTION_INTERVAL: u32 = 100;
+
+/// Last accesses are saved once every X cache hits.
+const ACCESS_FLUSH_INTERVAL: usize = 50;
+
+thread_local! {
+    static DATABASE: RefCell<Option<Rc<Rexie>>> = RefCell::new(None);
+    static WRITE_COUNT: Cell<u32> = Cell::new(0);
+
+    /// Cache hits not saved yet, by key.
+    static ACCESSES: RefCell<HashMap<String, f64>> = RefCell::new(HashMap::new());
+}
+
+/// A decoded DAG node saved in IndexedDB.
+#[derive(Serialize, Deserialize)]
+struct CacheNode {
+    key: String,
+
+    /// Node as JSON
+    data: String,
+}
+
+/// Eviction bookkeeping of a cached node.
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    key: String,
+
+    size: usize,
+
+    /// Unix time in milliseconds
+    last_access: f64,
+}
+
+/// Cache key of a node at path. CIDs are immutable so the key never expire.
+pub fn cache_key(cid: Cid, path: Option<&str>) -> String {
+    match path {
+        Some(path) => format!("{}{}", cid, path),
+        None => cid.to_string(),
+    }
+}
+
+async fn database() -> Option<Rc<Rexie>> {
+    if let Some(rexie) = DATABASE.with(|cell| cell.borrow().clone()) {
+        return Some(rexie);
+    }
+
+    // Version 1 stored nodes and bookkeeping together, its store is dropped on upgrade
+    let result = Rexie::builder(DB_NAME)
+        .version(2)
+        .add_object_store(ObjectStore::new(NODE_STORE).key_path("key"))
+        .add_object_store(
+            ObjectStore::new(ENTRY_STORE)
+                .key_path("key")
+                .add_index(Index::new(ACCESS_INDEX, ACCESS_INDEX)),
+        )
+        .build()
+        .await;
+
+    let rexie = match result {
+        Ok(rexie) => Rc::new(rexie),
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return None;
+        }
+    };
+
+    DATABASE.with(|cell| cell.replace(Some(rexie.clone())));
+
+    Some(rexie)
+}
+
+/// Return the cached node if any and mark it as recently used.
+pub async fn get<T>(key: &str) -> Option<T>
+where
+    T: DeserializeOwned,
+{
+    let rexie = database().await?;
+
+    let transaction = match rexie.transaction(&[NODE_STORE], TransactionMode::ReadOnly) {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return None;
+        }
+    };
+
+    let store = match transaction.store(NODE_STORE) {
+        Ok(store) => store,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return None;
+        }
+    };
+
+    let value = match store.get((&JsValue::from(key)).into()).await {
+        Ok(value) => value,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return None;
+        }
+    };
+
+    if value.is_undefined() || value.is_null() {
+        return None;
+    }
+
+    let entry: CacheNode = from_js(&value)?;
+
+    let node = match serde_json::from_str::<T>(&entry.data) {
+        Ok(node) => node,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return None;
+        }
+    };
+
+    let pending = ACCESSES.with(|cell| {
+        let mut accesses = cell.borrow_mut();
+        accesses.insert(entry.key, js_sys::Date::now());
+        accesses.len()
+    });
+
+    if pending >= ACCESS_FLUSH_INTERVAL {
+        spawn_local(async move { flush_accesses(&rexie).await });
+    }
+
+    Some(node)
+}
+
+/// Save a node in the cache, evicting the least recently used nodes if limits are reached.
+pub async fn put<T>(key: &str, node: &T)
+where
+    T: ?Sized + Serialize,
+{
+    let data = match serde_json::to_string(node) {
+        Ok(data) => data,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    };
+
+    let entry = CacheEntry {
+        key: key.to_owned(),
+        size: data.len(),
+        last_access: js_sys::Date::now(),
+    };
+
+    let node = CacheNode {
+        key: key.to_owned(),
+        data,
+    };
+
+    let (node, entry) = match (to_js(&node), to_js(&entry)) {
+        (Some(node), Some(entry)) => (node, entry),
+        _ => return,
+    };
+
+    let rexie = match database().await {
+        Some(rexie) => rexie,
+        None => return,
+    };
+
+    let transaction =
+        match rexie.transaction(&[NODE_STORE, ENTRY_STORE], TransactionMode::ReadWrite) {
+            Ok(tx) => tx,
+            Err(e) => {
+                error!(&format!("{:#?}", e));
+                return;
+            }
+        };
+
+    for (name, value) in [(NODE_STORE, &node), (ENTRY_STORE, &entry)] {
+        let store = match transaction.store(name) {
+            Ok(store) => store,
+            Err(e) => {
+                error!(&format!("{:#?}", e));
+                return;
+            }
+        };
+
+        if let Err(e) = store.put(value, None).await {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    }
+
+    if let Err(e) = transaction.done().await {
+        error!(&format!("{:#?}", e));
+        return;
+    }
+
+    let count = WRITE_COUNT.with(|cell| {
+        let count = cell.get() + 1;
+        cell.set(count);
+        count
+    });
+
+    if count % EVICTION_INTERVAL == 0 {
+        evict(&rexie).await;
+    }
+}
+
+/// Save pending cache hits in a single transaction.
+async fn flush_accesses(rexie: &Rexie) {
+    let accesses = ACCESSES.with(|cell| cell.take());
+
+    if accesses.is_empty() {
+        return;
+    }
+
+    let transaction = match rexie.transaction(&[ENTRY_STORE], TransactionMode::ReadWrite) {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    };
+
+    let store = match transaction.store(ENTRY_STORE) {
+        Ok(store) => store,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    };
+
+    for (key, last_access) in accesses {
+        let value = match store.get((&JsValue::from(key.as_str())).into()).await {
+            Ok(value) => value,
+            Err(e) => {
+                error!(&format!("{:#?}", e));
+                continue;
+            }
+        };
+
+        // Evicted since
+        if value.is_undefined() || value.is_null() {
+            continue;
+        }
+
+        let mut entry: CacheEntry = match from_js(&value) {
+            Some(entry) => entry,
+            None => continue,
+        };
+
+        entry.last_access = entry.last_access.max(last_access);
+
+        if let Some(value) = to_js(&entry) {
+            if let Err(e) = store.put(&value, None).await {
+                error!(&format!("{:#?}", e));
+            }
+        }
+    }
+
+    if let Err(e) = transaction.done().await {
+        error!(&format!("{:#?}", e));
+    }
+}
+
+/// Remove least recently used nodes until both limits are respected.
+///
+/// Only the bookkeeping entries are read, never the nodes themselves.
+async fn evict(rexie: &Rexie) {
+    flush_accesses(rexie).await;
+
+    let transaction =
+        match rexie.transaction(&[NODE_STORE, ENTRY_STORE], TransactionMode::ReadWrite) {
+            Ok(tx) => tx,
+            Err(e) => {
+                error!(&format!("{:#?}", e));
+                return;
+            }
+        };
+
+    let (nodes, entries) = match (
+        transaction.store(NODE_STORE),
+        transaction.store(ENTRY_STORE),
+    ) {
+        (Ok(nodes), Ok(entries)) => (nodes, entries),
+        (Err(e), _) | (_, Err(e)) => {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    };
+
+    let index = match entries.index(ACCESS_INDEX) {
+        Ok(index) => index,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    };
+
+    // Oldest access first
+    let values = match index.get_all(None, None, None, None).await {
+        Ok(values) => values,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    };
+
+    let values: Vec<CacheEntry> = values
+        .into_iter()
+        .filter_map(|(_, value)| from_js(&value))
+        .collect();
+
+    let mut count = values.len();
+    let mut total: usize = values.iter().map(|entry| entry.size).sum();
+
+    for entry in values {
+        if count <= MAX_ENTRIES && total <= MAX_BYTES {
+            break;
+        }
+
+        let key = JsValue::from(entry.key.as_str());
+
+        for store in [&nodes, &entries] {
+            if let Err(e) = store.delete(&key).await {
+                error!(&format!("{:#?}", e));
+                return;
+            }
+        }
+
+        count -= 1;
+        total -= entry.size;
+    }
+
+    if let Err(e) = transaction.done().await {
+        error!(&format!("{:#?}", e));
+    }
+}
+
+pub(crate) fn to_js<T: Serialize>(value: &T) -> Option<JsValue> {
+    let json = match serde_json::to_string(value) {
+        Ok(json) => json,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return None;
+        }
+    };
+
+    match JSON::parse(&json) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            error!(&format!("{:?}", e));
+            None
+        }
+    }
+}
+
+pub(crate) fn from_js<T: DeserializeOwned>(value: &JsValue) -> Option<T> {
+    let json: String = match JSON::stringify(value) {
+        Ok(json) => json.into(),
+        Err(e) => {
+            error!(&format!("{:?}", e));
+            return None;
+        }
+    };
+
+    match serde_json::from_str(&json) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            None
+        }
+    }
+}
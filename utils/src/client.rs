@@ -0,0 +1,598 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use async_trait::async_trait;
+
+use cid::Cid;
+
+use futures_channel::mpsc::{unbounded, UnboundedSender};
+
+use futures_util::{
+    stream::{self, LocalBoxStream},
+    StreamExt, TryStreamExt,
+};
+
+use ipfs_api::{responses::Codec, IpfsService};
+
+use linked_data::types::{IPNSAddress, PeerId};
+
+use multihash::{Code, MultihashDigest};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use serde_json::Value;
+
+use crate::ipld::{DAG_JSON, LIBP2P_KEY, RAW};
+
+#[derive(Debug)]
+pub enum ClientError {
+    Ipfs(ipfs_api::errors::Error),
+    Defluencer(defluencer::errors::Error),
+    Serde(serde_json::Error),
+    Gateway(String),
+    Decode(String),
+    NotFound(String),
+
+    /// Block data does not match its CID.
+    Verification(Cid),
+
+    /// Signed link or IPNS record signature is invalid.
+    Signature(Cid),
+
+    /// IPNS record of this name is past it's validity.
+    Expired(Cid),
+
+    /// Operation unavailable without a local node.
+    ReadOnly,
+}
+
+impl From<ipfs_api::errors::Error> for ClientError {
+    fn from(e: ipfs_api::errors::Error) -> Self {
+        Self::Ipfs(e)
+    }
+}
+
+impl From<defluencer::errors::Error> for ClientError {
+    fn from(e: defluencer::errors::Error) -> Self {
+        Self::Defluencer(e)
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Serde(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PubSubMessage {
+    pub from: PeerId,
+    pub data: Vec<u8>,
+}
+
+/// Every IPFS operation the app uses.
+///
+/// DAG nodes are passed as JSON values so that the trait stay object safe,
+/// use `dag_get` & `dag_put` on `dyn IpfsClient` for typed nodes.
+#[async_trait(?Send)]
+pub trait IpfsClient {
+    async fn dag_get_value(&self, cid: Cid, path: Option<&str>) -> Result<Value, ClientError>;
+
+    async fn dag_put_value(&self, node: &Value) -> Result<Cid, ClientError>;
+
+    async fn name_resolve(&self, addr: Cid) -> Result<Cid, ClientError>;
+
+    async fn name_publish(&self, cid: Cid, key: String) -> Result<(), ClientError>;
+
+    fn pubsub_sub(
+        &self,
+        topic: String,
+    ) -> LocalBoxStream<'static, Result<PubSubMessage, ClientError>>;
+
+    async fn pubsub_pub(&self, topic: String, data: Vec<u8>) -> Result<(), ClientError>;
+
+    async fn key_list(&self) -> Result<HashMap<String, Cid>, ClientError>;
+
+    async fn key_rm(&self, key: String) -> Result<(), ClientError>;
+
+    async fn pin_rm(&self, cid: Cid, recursive: bool) -> Result<(), ClientError>;
+
+    async fn add(&self, data: Vec<u8>) -> Result<Cid, ClientError>;
+
+    async fn cat(&self, cid: Cid, path: Option<&str>) -> Result<Vec<u8>, ClientError>;
+
+    /// Concrete client for operations delegated to defluencer, if any.
+    fn service(&self) -> Option<IpfsService> {
+        None
+    }
+}
+
+impl<'a> dyn IpfsClient + 'a {
+    pub async fn dag_get<T>(&self, cid: Cid, path: Option<&str>) -> Result<T, ClientError>
+    where
+        T: DeserializeOwned,
+    {
+        let value = self.dag_get_value(cid, path).await?;
+
+        let node = serde_json::from_value(value)?;
+
+        Ok(node)
+    }
+
+    pub async fn dag_put<T>(&self, node: &T) -> Result<Cid, ClientError>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = serde_json::to_value(node)?;
+
+        self.dag_put_value(&value).await
+    }
+}
+
+#[async_trait(?Send)]
+impl IpfsClient for IpfsService {
+    async fn dag_get_value(&self, cid: Cid, path: Option<&str>) -> Result<Value, ClientError> {
+        let value = self.dag_get::<&str, Value>(cid, path).await?;
+
+        Ok(value)
+    }
+
+    async fn dag_put_value(&self, node: &Value) -> Result<Cid, ClientError> {
+        let cid = self.dag_put(node, Codec::default()).await?;
+
+        Ok(cid)
+    }
+
+    async fn name_resolve(&self, addr: Cid) -> Result<Cid, ClientError> {
+        let cid = IpfsService::name_resolve(self, addr).await?;
+
+        Ok(cid)
+    }
+
+    async fn name_publish(&self, cid: Cid, key: String) -> Result<(), ClientError> {
+        IpfsService::name_publish(self, cid, key).await?;
+
+        Ok(())
+    }
+
+    fn pubsub_sub(
+        &self,
+        topic: String,
+    ) -> LocalBoxStream<'static, Result<PubSubMessage, ClientError>> {
+        IpfsService::pubsub_sub(self, topic.into_bytes())
+            .map_ok(|msg| PubSubMessage {
+                from: msg.from,
+                data: msg.data,
+            })
+            .map_err(ClientError::from)
+            .boxed_local()
+    }
+
+    async fn pubsub_pub(&self, topic: String, data: Vec<u8>) -> Result<(), ClientError> {
+        IpfsService::pubsub_pub(self, topic, data).await?;
+
+        Ok(())
+    }
+
+    async fn key_list(&self) -> Result<HashMap<String, Cid>, ClientError> {
+        let list = IpfsService::key_list(self).await?;
+
+        Ok(list.into_iter().collect())
+    }
+
+    async fn key_rm(&self, key: String) -> Result<(), ClientError> {
+        IpfsService::key_rm(self, key).await?;
+
+        Ok(())
+    }
+
+    async fn pin_rm(&self, cid: Cid, recursive: bool) -> Result<(), ClientError> {
+        IpfsService::pin_rm(self, cid, recursive).await?;
+
+        Ok(())
+    }
+
+    async fn add(&self, data: Vec<u8>) -> Result<Cid, ClientError> {
+        let cid = IpfsService::add(self, data.into()).await?;
+
+        Ok(cid)
+    }
+
+    async fn cat(&self, cid: Cid, path: Option<&str>) -> Result<Vec<u8>, ClientError> {
+        let bytes = IpfsService::cat(self, cid, path).await?;
+
+        Ok(bytes.to_vec())
+    }
+
+    fn service(&self) -> Option<IpfsService> {
+        Some(self.clone())
+    }
+}
+
+/// Deterministic peer id, for tests.
+pub fn mock_peer_id(seed: u8) -> PeerId {
+    let hash = Code::Identity.digest(&[seed; 32]);
+
+    PeerId::try_from(Cid::new_v1(LIBP2P_KEY, hash)).expect("Valid Peer Id")
+}
+
+/// In-memory IPFS node, for tests.
+///
+/// Seed it with DAGs, files, IPNS records & pubsub messages.
+#[derive(Clone)]
+pub struct MockIpfs {
+    inner: Rc<RefCell<MockInner>>,
+}
+
+struct MockInner {
+    peer_id: PeerId,
+
+    dags: HashMap<Cid, Value>,
+    files: HashMap<Cid, Vec<u8>>,
+    records: HashMap<Cid, Cid>,
+    keys: HashMap<String, Cid>,
+
+    /// Messages delivered to every new subscriber.
+    seeded: HashMap<String, Vec<PubSubMessage>>,
+    subscribers: HashMap<String, Vec<UnboundedSender<PubSubMessage>>>,
+
+    published: Vec<(String, Vec<u8>)>,
+}
+
+impl MockIpfs {
+    /// Messages published through this node are sent from this peer.
+    pub fn new(peer_id: PeerId) -> Self {
+        let inner = MockInner {
+            peer_id,
+            dags: Default::default(),
+            files: Default::default(),
+            records: Default::default(),
+            keys: Default::default(),
+            seeded: Default::default(),
+            subscribers: Default::default(),
+            published: Default::default(),
+        };
+
+        Self {
+            inner: Rc::new(RefCell::new(inner)),
+        }
+    }
+
+    /// Save a node and return its CID.
+    pub fn seed_dag<T>(&self, node: &T) -> Cid
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = serde_json::to_value(node).expect("Serializable Node");
+
+        self.insert_dag(value)
+    }
+
+    pub fn seed_file(&self, data: Vec<u8>) -> Cid {
+        let cid = Cid::new_v1(RAW, Code::Sha2_256.digest(&data));
+
+        self.inner.borrow_mut().files.insert(cid, data);
+
+        cid
+    }
+
+    /// Point an IPNS address to a CID.
+    pub fn seed_record(&self, addr: IPNSAddress, cid: Cid) {
+        self.inner.borrow_mut().records.insert(addr.into(), cid);
+    }
+
+    pub fn seed_key(&self, name: impl Into<String>, addr: IPNSAddress) {
+        self.inner
+            .borrow_mut()
+            .keys
+            .insert(name.into(), addr.into());
+    }
+
+    /// Queue a message every subscriber to this topic will receive.
+    pub fn seed_message(&self, topic: impl Into<String>, from: PeerId, data: Vec<u8>) {
+        let topic = topic.into();
+        let msg = PubSubMessage { from, data };
+
+        let mut inner = self.inner.borrow_mut();
+
+        if let Some(senders) = inner.subscribers.get_mut(&topic) {
+            senders.retain(|sender| sender.unbounded_send(msg.clone()).is_ok());
+        }
+
+        inner.seeded.entry(topic).or_default().push(msg);
+    }
+
+    /// All messages published through this node, in order.
+    pub fn published(&self) -> Vec<(String, Vec<u8>)> {
+        self.inner.borrow().published.clone()
+    }
+
+    fn insert_dag(&self, value: Value) -> Cid {
+        let bytes = serde_json::to_vec(&value).expect("Serializable Value");
+        let cid = Cid::new_v1(DAG_JSON, Code::Sha2_256.digest(&bytes));
+
+        self.inner.borrow_mut().dags.insert(cid, value);
+
+        cid
+    }
+
+    /// Follow the path inside the node, crossing links if needed.
+    fn resolve(&self, cid: Cid, path: Option<&str>) -> Result<Value, ClientError> {
+        let inner = self.inner.borrow();
+
+        let mut value = match inner.dags.get(&cid) {
+            Some(value) => value,
+            None => return Err(ClientError::NotFound(cid.to_string())),
+        };
+
+        let segments = path
+            .unwrap_or_default()
+            .split('/')
+            .filter(|segment| !segment.is_empty());
+
+        for segment in segments {
+            if let Some(link) = as_link(value) {
+                value = match inner.dags.get(&link) {
+                    Some(value) => value,
+                    None => return Err(ClientError::NotFound(link.to_string())),
+                };
+            }
+
+            let next = match value {
+                Value::Object(map) => map.get(segment),
+                Value::Array(array) => segment.parse::<usize>().ok().and_then(|i| array.get(i)),
+                _ => None,
+            };
+
+            value = match next {
+                Some(value) => value,
+                None => return Err(ClientError::NotFound(format!("{}/{}", cid, segment))),
+            };
+        }
+
+        if let Some(link) = as_link(value) {
+            if let Some(linked) = inner.dags.get(&link) {
+                return Ok(linked.clone());
+            }
+        }
+
+        Ok(value.clone())
+    }
+}
+
+#[async_trait(?Send)]
+impl IpfsClient for MockIpfs {
+    async fn dag_get_value(&self, cid: Cid, path: Option<&str>) -> Result<Value, ClientError> {
+        self.resolve(cid, path)
+    }
+
+    async fn dag_put_value(&self, node: &Value) -> Result<Cid, ClientError> {
+        Ok(self.insert_dag(node.clone()))
+    }
+
+    async fn name_resolve(&self, addr: Cid) -> Result<Cid, ClientError> {
+        match self.inner.borrow().records.get(&addr) {
+            Some(cid) => Ok(*cid),
+            None => Err(ClientError::NotFound(addr.to_string())),
+        }
+    }
+
+    async fn name_publish(&self, cid: Cid, key: String) -> Result<(), ClientError> {
+        let mut inner = self.inner.borrow_mut();
+
+        let addr = match inner.keys.get(&key) {
+            Some(addr) => *addr,
+            None => return Err(ClientError::NotFound(key)),
+        };
+
+        inner.records.insert(addr, cid);
+
+        Ok(())
+    }
+
+    fn pubsub_sub(
+        &self,
+        topic: String,
+    ) -> LocalBoxStream<'static, Result<PubSubMessage, ClientError>> {
+        let (sender, receiver) = unbounded();
+
+        let mut inner = self.inner.borrow_mut();
+
+        let seeded = inner.seeded.get(&topic).cloned().unwrap_or_default();
+
+        inner.subscribers.entry(topic).or_default().push(sender);
+
+        stream::iter(seeded).chain(receiver).map(Ok).boxed_local()
+    }
+
+    async fn pubsub_pub(&self, topic: String, data: Vec<u8>) -> Result<(), ClientError> {
+        let mut inner = self.inner.borrow_mut();
+
+        let msg = PubSubMessage {
+            from: inner.peer_id.clone(),
+            data: data.clone(),
+        };
+
+        if let Some(senders) = inner.subscribers.get_mut(&topic) {
+            senders.retain(|sender| sender.unbounded_send(msg.clone()).is_ok());
+        }
+
+        inner.published.push((topic, data));
+
+        Ok(())
+    }
+
+    async fn key_list(&self) -> Result<HashMap<String, Cid>, ClientError> {
+        Ok(self.inner.borrow().keys.clone())
+    }
+
+    async fn key_rm(&self, key: String) -> Result<(), ClientError> {
+        match self.inner.borrow_mut().keys.remove(&key) {
+            Some(_) => Ok(()),
+            None => Err(ClientError::NotFound(key)),
+        }
+    }
+
+    async fn pin_rm(&self, _cid: Cid, _recursive: bool) -> Result<(), ClientError> {
+        Ok(())
+    }
+
+    async fn add(&self, data: Vec<u8>) -> Result<Cid, ClientError> {
+        Ok(self.seed_file(data))
+    }
+
+    async fn cat(&self, cid: Cid, path: Option<&str>) -> Result<Vec<u8>, ClientError> {
+        let cid = match path {
+            Some(path) => {
+                let value = self.resolve(cid, Some(path))?;
+
+                match as_link(&value) {
+                    Some(link) => link,
+                    None => return Err(ClientError::NotFound(format!("{}{}", cid, path))),
+                }
+            }
+            None => cid,
+        };
+
+        match self.inner.borrow().files.get(&cid) {
+            Some(data) => Ok(data.clone()),
+            None => Err(ClientError::NotFound(cid.to_string())),
+        }
+    }
+}
+
+/// Return the CID if the value is a DAG-JSON link.
+pub fn as_link(value: &Value) -> Option<Cid> {
+    let map = value.as_object()?;
+
+    if map.len() != 1 {
+        return None;
+    }
+
+    let cid = map.get("/")?.as_str()?;
+
+    Cid::try_from(cid).ok()
+}
+
+/// Link to the CID as a DAG-JSON value, the inverse of [`as_link`].
+pub fn link_value(cid: Cid) -> Value {
+    let mut map = serde_json::Map::new();
+    map.insert("/".to_owned(), Value::String(cid.to_string()));
+
+    Value::Object(map)
+}
+
+/// Stream the media CIDs of a content index, newest first.
+///
+/// Index nodes are keyed by date so keys are visited in reverse numeric order.
+/// Any node with a `link` field is a media.
+pub fn walk_index(
+    client: Rc<dyn IpfsClient>,
+    index: Cid,
+) -> LocalBoxStream<'static, Result<Cid, ClientError>> {
+    stream::unfold(vec![index], move |mut stack| {
+        let client = client.clone();
+
+        async move {
+            loop {
+                let cid = stack.pop()?;
+
+                let value = match client.dag_get_value(cid, None).await {
+                    Ok(value) => value,
+                    Err(e) => return Some((Err(e), stack)),
+                };
+
+                let map = match value {
+                    Value::Object(map) => map,
+                    _ => continue,
+                };
+
+                if map.contains_key("link") {
+                    return Some((Ok(cid), stack));
+                }
+
+                let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+
+                entries.sort_by(
+                    |(a, _), (b, _)| match (a.parse::<i64>(), b.parse::<i64>()) {
+                        (Ok(a), Ok(b)) => a.cmp(&b),
+                        _ => a.cmp(b),
+                    },
+                );
+
+                // Oldest pushed first so that newest is popped first
+                for (_, value) in entries {
+                    match value {
+                        Value::Array(array) => stack.extend(array.iter().filter_map(as_link)),
+                        value => stack.extend(as_link(&value)),
+                    }
+                }
+            }
+        }
+    })
+    .boxed_local()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn link(cid: Cid) -> Value {
+        json!({ "/": cid.to_string() })
+    }
+
+    fn seed_media(ipfs: &MockIpfs, name: &str) -> Cid {
+        let file = ipfs.seed_file(name.as_bytes().to_vec());
+
+        ipfs.seed_dag(&json!({ "link": link(file), "name": name }))
+    }
+
+    #[wasm_bindgen_test]
+    async fn walk_index_newest_first() {
+        let ipfs = MockIpfs::new(mock_peer_id(0));
+
+        let july = seed_media(&ipfs, "july");
+        let february = seed_media(&ipfs, "february");
+        let december_1 = seed_media(&ipfs, "december 1");
+        let december_2 = seed_media(&ipfs, "december 2");
+
+        let year_2022 = ipfs.seed_dag(&json!({ "7": link(july) }));
+        let year_2023 = ipfs.seed_dag(&json!({
+            "2": link(february),
+            "12": [link(december_1), link(december_2)],
+        }));
+
+        let root = ipfs.seed_dag(&json!({
+            "2022": link(year_2022),
+            "2023": link(year_2023),
+        }));
+
+        let feed: Vec<Cid> = walk_index(Rc::new(ipfs), root)
+            .try_collect()
+            .await
+            .expect("Complete Index");
+
+        assert_eq!(feed, vec![december_2, december_1, february, july]);
+    }
+
+    #[wasm_bindgen_test]
+    async fn walk_index_missing_node() {
+        let ipfs = MockIpfs::new(mock_peer_id(0));
+
+        let media = seed_media(&ipfs, "media");
+        let missing = ipfs.seed_file(b"not a node".to_vec());
+
+        let root = ipfs.seed_dag(&json!({ "1": link(media), "2": link(missing) }));
+
+        let results: Vec<_> = walk_index(Rc::new(ipfs), root).collect().await;
+
+        assert_eq!(results.len(), 2);
+        assert!(matches!(results[0], Err(ClientError::NotFound(_))));
+        assert_eq!(results[1].as_ref().ok(), Some(&media));
+    }
+}
@@ -2,10 +2,19 @@
 
 use cid::Cid;
 
+use gloo_console::error;
+
+use gloo_storage::{LocalStorage, Storage};
+
 use linked_data::media::comments::Comment;
 
 use yew::Callback;
 
+const COLLAPSE_DEPTH: &str = "comment_collapse_depth";
+
+/// Replies deeper than this are collapsed by default.
+pub const DEFAULT_COLLAPSE_DEPTH: usize = 3;
+
 #[derive(Clone)]
 pub struct CommentaryContext {
     pub callback: Callback<(Cid, Comment)>,
@@ -22,3 +31,14 @@ impl CommentaryContext {
         Self { callback }
     }
 }
+
+/// Get the depth at which comment threads are collapsed.
+pub fn get_collapse_depth() -> usize {
+    LocalStorage::get(COLLAPSE_DEPTH).unwrap_or(DEFAULT_COLLAPSE_DEPTH)
+}
+
+pub fn set_collapse_depth(depth: usize) {
+    if let Err(e) = LocalStorage::set(COLLAPSE_DEPTH, depth) {
+        error!(&format!("{:?}", e));
+    }
+}
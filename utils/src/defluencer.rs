@@ -25,16 +25,28 @@ impl UserContext {
     }
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone)]
 pub struct ChannelContext {
     pub channel: Channel<LocalUpdater>,
+
+    /// IPNS key name of the channel.
+    pub key: String,
+
+    /// Node holding the channel key, for updates outside the defluencer schema.
+    pub ipfs: IpfsService,
 }
 
 impl ChannelContext {
     pub fn new(ipfs: IpfsService, key: String, addr: IPNSAddress) -> Self {
-        let updater = LocalUpdater::new(ipfs.clone(), key);
-        let channel = Channel::new(ipfs, addr, updater);
+        let updater = LocalUpdater::new(ipfs.clone(), key.clone());
+        let channel = Channel::new(ipfs.clone(), addr, updater);
+
+        Self { channel, key, ipfs }
+    }
+}
 
-        Self { channel }
+impl PartialEq for ChannelContext {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && self.channel.get_address() == other.channel.get_address()
     }
 }
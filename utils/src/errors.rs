@@ -0,0 +1,94 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::fmt;
+
+use crate::client::ClientError;
+
+/// Errors shown to the user, classified by what failed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    /// IPFS node or gateway request failed.
+    Ipfs(String),
+
+    /// Wallet signature refused or invalid.
+    Signing(String),
+
+    /// IPNS or ENS name could not be resolved or updated.
+    Ipns(String),
+
+    /// Data could not be decoded.
+    Decode(String),
+
+    /// Content could not be found.
+    NotFound(String),
+
+    /// Operation waiting for older queued operations of the same channel.
+    Queued(String),
+}
+
+impl AppError {
+    pub fn ipfs(e: impl fmt::Debug) -> Self {
+        Self::Ipfs(format!("{:#?}", e))
+    }
+
+    pub fn signing(e: impl fmt::Debug) -> Self {
+        Self::Signing(format!("{:#?}", e))
+    }
+
+    pub fn ipns(e: impl fmt::Debug) -> Self {
+        Self::Ipns(format!("{:#?}", e))
+    }
+
+    pub fn decode(e: impl fmt::Debug) -> Self {
+        Self::Decode(format!("{:#?}", e))
+    }
+
+    /// Human-readable summary.
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::Ipfs(_) => "IPFS request failed. Is your node running?",
+            Self::Signing(_) => "Signature failed. Was it rejected in your wallet?",
+            Self::Ipns(_) => "Name could not be resolved or updated.",
+            Self::Decode(_) => "Received invalid data.",
+            Self::NotFound(_) => "Content not found.",
+            Self::Queued(_) => "Older operations must be published first.",
+        }
+    }
+
+    /// Technical details for debugging.
+    pub fn details(&self) -> &str {
+        match self {
+            Self::Ipfs(details)
+            | Self::Signing(details)
+            | Self::Ipns(details)
+            | Self::Decode(details)
+            | Self::NotFound(details)
+            | Self::Queued(details) => details,
+        }
+    }
+
+    /// Invalid or missing data will stay that way, anything else may succeed later.
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self, Self::Decode(_) | Self::NotFound(_))
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl From<ClientError> for AppError {
+    fn from(e: ClientError) -> Self {
+        match e {
+            ClientError::Serde(_)
+            | ClientError::Decode(_)
+            | ClientError::Verification(_)
+            | ClientError::Signature(_) => Self::decode(e),
+            ClientError::NotFound(path) => Self::NotFound(path),
+            ClientError::Expired(_) => Self::ipns(e),
+            _ => Self::ipfs(e),
+        }
+    }
+}
@@ -0,0 +1,275 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{collections::HashSet, rc::Rc};
+
+use cid::Cid;
+
+use gloo_console::error;
+
+use gloo_storage::{LocalStorage, Storage};
+
+use linked_data::{
+    channel::{follows::Follows, ChannelMetadata},
+    types::IPNSAddress,
+};
+
+use crate::{
+    client::{as_link, link_value, IpfsClient},
+    defluencer::ChannelContext,
+    errors::AppError,
+    outbox::{self, Operation},
+    subscriptions,
+};
+
+/// Follow set as of the last sync, used to tell additions from removals.
+///
+/// Suffixed by the channel address.
+const SYNC_BASE: &str = "follow_sync_base";
+
+/// Milliseconds between follow syncs.
+pub const SYNC_INTERVAL: f64 = 60_000.0;
+
+/// What changed after a sync.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncReport {
+    /// Followees added to the local set.
+    pub pulled: usize,
+
+    /// Followees removed from the local set.
+    pub dropped: usize,
+
+    /// Followees added or removed in the channel update.
+    pub pushed: usize,
+
+    /// Followee changes that failed and were queued.
+    pub queued: usize,
+}
+
+/// Reconcile the local follow set with the channel follows.
+///
+/// Additions on either side are kept and removals on either side are applied to both.
+/// Without a previous sync, both sets are merged.
+pub async fn sync_follows(
+    ipfs: Rc<dyn IpfsClient>,
+    context: &ChannelContext,
+) -> Result<SyncReport, AppError> {
+    let addr = context.channel.get_address();
+
+    let mut remote = remote_follows(&ipfs, addr).await?;
+
+    // Updates not published yet already reflect the user intent
+    let mut pendings: Vec<_> = outbox::list()
+        .await
+        .into_iter()
+        .filter(|pending| pending.channel == addr)
+        .collect();
+    pendings.sort_unstable_by(|a, b| a.created.total_cmp(&b.created));
+
+    for pending in pendings {
+        match pending.operation {
+            Operation::Follow(addr) => {
+                remote.insert(addr);
+            }
+            Operation::Unfollow(addr) => {
+                remote.remove(&addr);
+            }
+            Operation::UpdateFollows { follow, unfollow } => {
+                remote.extend(follow);
+
+                for addr in unfollow {
+                    remote.remove(&addr);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let local = subscriptions::get_sub_list();
+    let base_key = format!("{}_{}", SYNC_BASE, addr);
+    let base = LocalStorage::get::<HashSet<IPNSAddress>>(&base_key).ok();
+
+    let merged = merge(&local, &remote, base.as_ref());
+
+    let mut report = SyncReport {
+        pulled: merged.difference(&local).count(),
+        dropped: local.difference(&merged).count(),
+        ..Default::default()
+    };
+
+    if merged != local {
+        subscriptions::set_sub_list(merged.clone());
+    }
+
+    let follow: Vec<_> = merged.difference(&remote).copied().collect();
+    let unfollow: Vec<_> = remote.difference(&merged).copied().collect();
+
+    report.pushed = follow.len() + unfollow.len();
+
+    if report.pushed > 0 {
+        let operation = Operation::UpdateFollows { follow, unfollow };
+
+        if let Err(e) = outbox::submit(context, operation).await {
+            error!(&format!("{:#?}", e));
+            report.queued = report.pushed;
+        }
+    }
+
+    if let Err(e) = LocalStorage::set(&base_key, &merged) {
+        error!(&format!("{:?}", e));
+    }
+
+    Ok(report)
+}
+
+/// Three-way merge of follow sets.
+fn merge(
+    local: &HashSet<IPNSAddress>,
+    remote: &HashSet<IPNSAddress>,
+    base: Option<&HashSet<IPNSAddress>>,
+) -> HashSet<IPNSAddress> {
+    let base = match base {
+        Some(base) => base,
+        None => return local.union(remote).copied().collect(),
+    };
+
+    local
+        .union(remote)
+        .filter(|addr| {
+            // Present before the last sync and missing now on either side means removed
+            !base.contains(addr) || (local.contains(addr) && remote.contains(addr))
+        })
+        .copied()
+        .collect()
+}
+
+/// Follow and unfollow channels with a single channel update.
+///
+/// Return the new channel root or none if the follows were already up to date.
+pub async fn update_follows(
+    context: &ChannelContext,
+    follow: &[IPNSAddress],
+    unfollow: &[IPNSAddress],
+) -> Result<Option<Cid>, AppError> {
+    let ipfs: &dyn IpfsClient = &context.ipfs;
+
+    let cid = ipfs
+        .name_resolve(context.channel.get_address().into())
+        .await
+        .map_err(AppError::ipns)?;
+
+    // Updated as a value so that fields outside the defluencer schema are kept
+    let mut metadata = ipfs.dag_get_value(cid, None).await?;
+
+    let mut follows = match metadata.get("follows").and_then(as_link) {
+        Some(link) => ipfs.dag_get::<Follows>(link, None).await?,
+        None => Follows {
+            followees: HashSet::default(),
+        },
+    };
+
+    let before = follows.followees.clone();
+
+    follows.followees.extend(follow);
+
+    for addr in unfollow {
+        follows.followees.remove(addr);
+    }
+
+    if follows.followees == before {
+        return Ok(None);
+    }
+
+    let link = ipfs.dag_put(&follows).await?;
+
+    let map = match metadata.as_object_mut() {
+        Some(map) => map,
+        None => return Err(AppError::Decode(cid.to_string())),
+    };
+
+    map.insert("follows".to_owned(), link_value(link));
+
+    let root = ipfs.dag_put_value(&metadata).await?;
+
+    ipfs.name_publish(root, context.key.clone())
+        .await
+        .map_err(AppError::ipns)?;
+
+    Ok(Some(root))
+}
+
+async fn remote_follows(
+    ipfs: &Rc<dyn IpfsClient>,
+    addr: IPNSAddress,
+) -> Result<HashSet<IPNSAddress>, AppError> {
+    let cid = ipfs.name_resolve(addr.into()).await?;
+
+    let metadata = ipfs.dag_get::<ChannelMetadata>(cid, None).await?;
+
+    let follows = match metadata.follows {
+        Some(ipld) => ipfs.dag_get::<Follows>(ipld.link, None).await?,
+        None => return Ok(HashSet::default()),
+    };
+
+    Ok(follows.followees)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use multihash::{Code, MultihashDigest};
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::ipld::LIBP2P_KEY;
+
+    fn addr(seed: u8) -> IPNSAddress {
+        let hash = Code::Identity.digest(&[seed; 32]);
+
+        IPNSAddress::try_from(Cid::new_v1(LIBP2P_KEY, hash)).expect("Valid Address")
+    }
+
+    fn set(seeds: &[u8]) -> HashSet<IPNSAddress> {
+        seeds.iter().copied().map(addr).collect()
+    }
+
+    #[wasm_bindgen_test]
+    fn first_sync_union() {
+        let merged = merge(&set(&[1, 2]), &set(&[2, 3]), None);
+
+        assert_eq!(merged, set(&[1, 2, 3]));
+    }
+
+    #[wasm_bindgen_test]
+    fn local_removal() {
+        let merged = merge(&set(&[1]), &set(&[1, 2]), Some(&set(&[1, 2])));
+
+        assert_eq!(merged, set(&[1]));
+    }
+
+    #[wasm_bindgen_test]
+    fn remote_removal() {
+        let merged = merge(&set(&[1, 2]), &set(&[1]), Some(&set(&[1, 2])));
+
+        assert_eq!(merged, set(&[1]));
+    }
+
+    #[wasm_bindgen_test]
+    fn additions_on_both_sides() {
+        let merged = merge(&set(&[1, 2]), &set(&[1, 3]), Some(&set(&[1])));
+
+        assert_eq!(merged, set(&[1, 2, 3]));
+    }
+
+    #[wasm_bindgen_test]
+    fn removed_then_added_again() {
+        // Removed locally, the removal reaches the channel
+        let base = merge(&set(&[1]), &set(&[1, 2]), Some(&set(&[1, 2])));
+        assert_eq!(base, set(&[1]));
+
+        // Followed again from another device
+        let merged = merge(&set(&[1]), &set(&[1, 2]), Some(&base));
+
+        assert_eq!(merged, set(&[1, 2]));
+    }
+}
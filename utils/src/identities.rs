@@ -0,0 +1,107 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{
+    cell::RefCell,
+    collections::{hash_map::Entry, HashMap},
+    rc::Rc,
+};
+
+use cid::Cid;
+
+use gloo_console::error;
+
+use linked_data::identity::Identity;
+
+use crate::client::IpfsClient;
+
+use yew::{platform::spawn_local, Callback};
+
+/// Identities shared by every page.
+///
+/// Each identity is fetched once then kept for the whole session.
+#[derive(Clone, Default)]
+pub struct IdentityStore {
+    inner: Rc<RefCell<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    identities: HashMap<Cid, Identity>,
+
+    /// Callbacks waiting for an in-flight fetch.
+    pending: HashMap<Cid, Vec<Callback<(Cid, Identity)>>>,
+}
+
+impl PartialEq for IdentityStore {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl IdentityStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the identity if already fetched.
+    pub fn get(&self, cid: &Cid) -> Option<Identity> {
+        self.inner.borrow().identities.get(cid).cloned()
+    }
+
+    pub fn contains(&self, cid: &Cid) -> bool {
+        self.inner.borrow().identities.contains_key(cid)
+    }
+
+    /// Callback is called once the identity is available.
+    ///
+    /// Only one fetch per identity is ever in-flight.
+    pub fn request(&self, ipfs: Rc<dyn IpfsClient>, cid: Cid, callback: Callback<(Cid, Identity)>) {
+        if let Some(identity) = self.get(&cid) {
+            callback.emit((cid, identity));
+            return;
+        }
+
+        let mut inner = self.inner.borrow_mut();
+
+        match inner.pending.entry(cid) {
+            Entry::Occupied(mut entry) => {
+                if !entry.get().contains(&callback) {
+                    entry.get_mut().push(callback);
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(vec![callback]);
+
+                spawn_local(fetch_identity(ipfs, cid, self.clone()));
+            }
+        }
+    }
+
+    fn on_identity(&self, cid: Cid, identity: Identity) {
+        let callbacks = {
+            let mut inner = self.inner.borrow_mut();
+
+            inner.identities.insert(cid, identity.clone());
+
+            inner.pending.remove(&cid).unwrap_or_default()
+        };
+
+        for callback in callbacks {
+            callback.emit((cid, identity.clone()));
+        }
+    }
+
+    fn on_error(&self, cid: Cid) {
+        self.inner.borrow_mut().pending.remove(&cid);
+    }
+}
+
+async fn fetch_identity(ipfs: Rc<dyn IpfsClient>, cid: Cid, store: IdentityStore) {
+    match crate::r#async::cached_dag_get::<Identity>(&*ipfs, cid, None).await {
+        Ok(identity) => store.on_identity(cid, identity),
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            store.on_error(cid);
+        }
+    }
+}
@@ -1,5 +1,19 @@
 #![cfg(target_arch = "wasm32")]
 
+use std::{collections::HashMap, rc::Rc};
+
+use async_trait::async_trait;
+
+use cid::Cid;
+
+use futures_util::{
+    future::LocalBoxFuture,
+    stream::{self, LocalBoxStream},
+    FutureExt, StreamExt,
+};
+
+use gloo_net::http::Request;
+
 use ipfs_api::{IpfsService, DEFAULT_URI};
 
 use linked_data::types::PeerId;
@@ -10,15 +24,32 @@ use gloo_storage::{LocalStorage, Storage};
 
 use serde::Serialize;
 
+use serde_json::Value;
+
+use crate::{
+    client::{as_link, ClientError, IpfsClient, PubSubMessage},
+    ipld::{self, DAG_PB, RAW},
+};
+
 #[derive(Clone)]
 pub struct IPFSContext {
-    pub client: IpfsService,
-    pub peer_id: PeerId,
+    /// Local node or gateway behind the client trait.
+    pub api: Rc<dyn IpfsClient>,
+
+    pub peer_id: Option<PeerId>,
+
+    /// Active API endpoint, none in read-only mode.
+    pub endpoint: Option<String>,
+
+    /// Gateway url, only in read-only mode.
+    pub gateway: Option<String>,
 }
 
 impl PartialEq for IPFSContext {
     fn eq(&self, other: &Self) -> bool {
         self.peer_id == other.peer_id
+            && self.endpoint == other.endpoint
+            && self.gateway == other.gateway
     }
 }
 
@@ -51,29 +82,306 @@ impl IPFSContext {
             }
         };
 
-        Some(Self { client, peer_id })
+        Some(Self {
+            api: Rc::new(client),
+            peer_id: Some(peer_id),
+            endpoint: Some(url.to_owned()),
+            gateway: None,
+        })
+    }
+
+    /// Read-only context backed by a trustless gateway.
+    pub fn read_only(gateway: &str) -> Self {
+        Self {
+            api: Rc::new(GatewayClient::new(gateway)),
+            peer_id: None,
+            endpoint: None,
+            gateway: Some(gateway.to_owned()),
+        }
+    }
+
+    /// Publishing, chat & comments are unavailable in read-only mode.
+    pub fn is_read_only(&self) -> bool {
+        self.endpoint.is_none()
     }
 }
 
+/// Return true if the node at this url answers, without logging errors.
+pub async fn probe(url: &str) -> bool {
+    match IpfsService::new(url) {
+        Ok(client) => client.peer_id().await.is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Return the first endpoint that answers, in order of preference.
+pub async fn discover(addrs: &[String]) -> Option<String> {
+    for url in addrs {
+        if probe(url).await {
+            return Some(url.clone());
+        }
+    }
+
+    None
+}
+
+/// Local nodes probed by default.
+pub const WELL_KNOWN_APIS: [(&str, &str); 2] = [
+    ("Kubo", DEFAULT_URI),
+    ("Brave", "http://127.0.0.1:45005/api/v0/"),
+];
+
 const IPFS_API_ADDRS_KEY: &str = "ipfs_api_addrs";
 
-/// Return IPFS api url from storage or default.
-pub fn get_ipfs_addr() -> String {
-    match LocalStorage::get(IPFS_API_ADDRS_KEY) {
-        Ok(url) => url,
-        Err(e) => {
-            error!(&format!("{:?}", e));
-            DEFAULT_URI.to_owned()
+/// Return ordered IPFS api urls from storage or the well-known defaults.
+///
+/// An empty list falls back to the defaults.
+pub fn get_ipfs_addrs() -> Vec<String> {
+    if let Ok(addrs) = LocalStorage::get::<Vec<String>>(IPFS_API_ADDRS_KEY) {
+        if !addrs.is_empty() {
+            return addrs;
         }
+
+        return default_ipfs_addrs();
+    }
+
+    let mut addrs = Vec::with_capacity(WELL_KNOWN_APIS.len() + 1);
+
+    // Single url saved by previous versions
+    if let Ok(url) = LocalStorage::get::<String>(IPFS_API_ADDRS_KEY) {
+        addrs.push(url);
+    }
+
+    for (_, url) in WELL_KNOWN_APIS {
+        if !addrs.iter().any(|addr| addr == url) {
+            addrs.push(url.to_owned());
+        }
+    }
+
+    addrs
+}
+
+/// Urls of the well-known local nodes.
+pub fn default_ipfs_addrs() -> Vec<String> {
+    WELL_KNOWN_APIS
+        .iter()
+        .map(|(_, url)| url.to_string())
+        .collect()
+}
+
+/// Save ordered IPFS api urls to local storage.
+pub fn set_ipfs_addrs(addrs: &[String]) {
+    if let Err(e) = LocalStorage::set(IPFS_API_ADDRS_KEY, addrs) {
+        error!(&format!("{:?}", e));
     }
 }
 
-/// Save IPFS api url to local storage.
-pub fn set_ipfs_addr<T>(msg: &T)
+pub const DEFAULT_GATEWAY: &str = "https://ipfs.io";
+
+const GATEWAY_ADDRS_KEY: &str = "ipfs_gateway_addrs";
+
+/// Return gateway url from storage or default.
+pub fn get_gateway_addr() -> String {
+    LocalStorage::get(GATEWAY_ADDRS_KEY).unwrap_or_else(|_| DEFAULT_GATEWAY.to_owned())
+}
+
+/// Save gateway url to local storage.
+pub fn set_gateway_addr<T>(msg: &T)
 where
     T: Serialize,
 {
-    if let Err(e) = LocalStorage::set(IPFS_API_ADDRS_KEY, msg) {
+    if let Err(e) = LocalStorage::set(GATEWAY_ADDRS_KEY, msg) {
         error!(&format!("{:?}", e));
     }
 }
+
+/// Read-only client fetching raw blocks from a trustless gateway.
+///
+/// Every block is verified against it's CID before being decoded.
+/// IPNS records are verified against the name key and rejected once expired.
+#[derive(Clone)]
+pub struct GatewayClient {
+    url: String,
+}
+
+impl GatewayClient {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_owned(),
+        }
+    }
+
+    async fn fetch(&self, url: &str, accept: &str) -> Result<Vec<u8>, ClientError> {
+        let response = match Request::get(url).header("Accept", accept).send().await {
+            Ok(response) => response,
+            Err(e) => return Err(ClientError::Gateway(e.to_string())),
+        };
+
+        if !response.ok() {
+            return Err(ClientError::Gateway(format!(
+                "{} {}",
+                response.status(),
+                url
+            )));
+        }
+
+        match response.binary().await {
+            Ok(data) => Ok(data),
+            Err(e) => Err(ClientError::Gateway(e.to_string())),
+        }
+    }
+
+    async fn block(&self, cid: Cid) -> Result<Vec<u8>, ClientError> {
+        let url = format!("{}/ipfs/{}?format=raw", self.url, cid);
+
+        let data = self.fetch(&url, "application/vnd.ipld.raw").await?;
+
+        if !ipld::verify_block(&cid, &data) {
+            return Err(ClientError::Verification(cid));
+        }
+
+        Ok(data)
+    }
+
+    async fn node(&self, cid: Cid) -> Result<Value, ClientError> {
+        let data = self.block(cid).await?;
+
+        ipld::decode_block(&cid, &data)
+    }
+
+    /// Follow the path inside the node, crossing links if needed.
+    async fn resolve(&self, cid: Cid, path: Option<&str>) -> Result<Value, ClientError> {
+        let mut value = self.node(cid).await?;
+
+        let segments = path
+            .unwrap_or_default()
+            .split('/')
+            .filter(|segment| !segment.is_empty());
+
+        for segment in segments {
+            if let Some(link) = as_link(&value) {
+                value = self.node(link).await?;
+            }
+
+            let next = match value {
+                Value::Object(mut map) => map.remove(segment),
+                Value::Array(mut array) => match segment.parse::<usize>() {
+                    Ok(i) if i < array.len() => Some(array.swap_remove(i)),
+                    _ => None,
+                },
+                _ => None,
+            };
+
+            value = match next {
+                Some(value) => value,
+                None => return Err(ClientError::NotFound(format!("{}/{}", cid, segment))),
+            };
+        }
+
+        Ok(value)
+    }
+
+    /// Concatenate the data of a UnixFS file.
+    fn file(&self, cid: Cid) -> LocalBoxFuture<'_, Result<Vec<u8>, ClientError>> {
+        async move {
+            let data = self.block(cid).await?;
+
+            match cid.codec() {
+                RAW => Ok(data),
+                DAG_PB => {
+                    let (node_data, links) = ipld::pb_node(&data)?;
+
+                    let mut file = match node_data {
+                        Some(node_data) => ipld::unixfs_data(&node_data)?,
+                        None => Vec::new(),
+                    };
+
+                    for link in links {
+                        file.extend(self.file(link.cid).await?);
+                    }
+
+                    Ok(file)
+                }
+                codec => Err(ClientError::Decode(format!("Not a file {:#x}", codec))),
+            }
+        }
+        .boxed_local()
+    }
+}
+
+#[async_trait(?Send)]
+impl IpfsClient for GatewayClient {
+    async fn dag_get_value(&self, cid: Cid, path: Option<&str>) -> Result<Value, ClientError> {
+        let value = self.resolve(cid, path).await?;
+
+        match as_link(&value) {
+            Some(link) => self.node(link).await,
+            None => Ok(value),
+        }
+    }
+
+    async fn dag_put_value(&self, _node: &Value) -> Result<Cid, ClientError> {
+        Err(ClientError::ReadOnly)
+    }
+
+    async fn name_resolve(&self, addr: Cid) -> Result<Cid, ClientError> {
+        let url = format!("{}/ipns/{}", self.url, addr);
+
+        let record = self.fetch(&url, "application/vnd.ipfs.ipns-record").await?;
+
+        let path = ipld::ipns_record_value(&addr, &record, js_sys::Date::now() as i64)?;
+
+        match Cid::try_from(path.trim_start_matches("/ipfs/")) {
+            Ok(cid) => Ok(cid),
+            Err(e) => Err(ClientError::Decode(e.to_string())),
+        }
+    }
+
+    async fn name_publish(&self, _cid: Cid, _key: String) -> Result<(), ClientError> {
+        Err(ClientError::ReadOnly)
+    }
+
+    /// Gateways cannot subscribe, the stream ends immediately.
+    fn pubsub_sub(
+        &self,
+        _topic: String,
+    ) -> LocalBoxStream<'static, Result<PubSubMessage, ClientError>> {
+        stream::empty().boxed_local()
+    }
+
+    async fn pubsub_pub(&self, _topic: String, _data: Vec<u8>) -> Result<(), ClientError> {
+        Err(ClientError::ReadOnly)
+    }
+
+    async fn key_list(&self) -> Result<HashMap<String, Cid>, ClientError> {
+        Err(ClientError::ReadOnly)
+    }
+
+    async fn key_rm(&self, _key: String) -> Result<(), ClientError> {
+        Err(ClientError::ReadOnly)
+    }
+
+    async fn pin_rm(&self, _cid: Cid, _recursive: bool) -> Result<(), ClientError> {
+        Err(ClientError::ReadOnly)
+    }
+
+    async fn add(&self, _data: Vec<u8>) -> Result<Cid, ClientError> {
+        Err(ClientError::ReadOnly)
+    }
+
+    async fn cat(&self, cid: Cid, path: Option<&str>) -> Result<Vec<u8>, ClientError> {
+        let cid = match path {
+            Some(path) => {
+                let value = self.resolve(cid, Some(path)).await?;
+
+                match as_link(&value) {
+                    Some(link) => link,
+                    None => return Err(ClientError::NotFound(format!("{}{}", cid, path))),
+                }
+            }
+            None => cid,
+        };
+
+        self.file(cid).await
+    }
+}
@@ -0,0 +1,973 @@
+#![cfg(target_arch = "wasm32")]
+
+use chrono::DateTime;
+
+use cid::Cid;
+
+use ed25519_dalek::{PublicKey, Signature};
+
+use multihash::{Code, MultihashDigest};
+
+use serde_json::{Map, Number, Value};
+
+use crate::client::ClientError;
+
+pub const RAW: u64 = 0x55;
+pub const DAG_PB: u64 = 0x70;
+pub const DAG_CBOR: u64 = 0x71;
+pub const DAG_JSON: u64 = 0x0129;
+pub const JSON: u64 = 0x0200;
+pub const LIBP2P_KEY: u64 = 0x72;
+
+/// Multihash code of inlined data.
+const IDENTITY_HASH: u64 = 0x00;
+
+/// Libp2p public key type.
+const ED25519: u64 = 1;
+
+/// IPNS V2 signatures sign the record data after this prefix.
+const IPNS_SIGNATURE_PREFIX: &[u8] = b"ipns-signature:";
+
+/// Check that the data hash match the CID.
+pub fn verify_block(cid: &Cid, data: &[u8]) -> bool {
+    match Code::try_from(cid.hash().code()) {
+        Ok(code) => code.digest(data) == *cid.hash(),
+        Err(_) => false,
+    }
+}
+
+/// Decode a block into it's DAG-JSON representation.
+pub fn decode_block(cid: &Cid, data: &[u8]) -> Result<Value, ClientError> {
+    match cid.codec() {
+        DAG_CBOR => CborDecoder::new(data).decode(),
+        DAG_JSON | JSON => Ok(serde_json::from_slice(data)?),
+        DAG_PB => decode_pb_node(data),
+        RAW => Ok(bytes_value(data)),
+        codec => Err(ClientError::Decode(format!(
+            "Unsupported codec {:#x}",
+            codec
+        ))),
+    }
+}
+
+/// DAG-JSON bytes encoding.
+fn bytes_value(data: &[u8]) -> Value {
+    let mut bytes = Map::new();
+    bytes.insert(
+        "bytes".to_owned(),
+        Value::String(base64::encode_config(data, base64::STANDARD_NO_PAD)),
+    );
+
+    let mut map = Map::new();
+    map.insert("/".to_owned(), Value::Object(bytes));
+
+    Value::Object(map)
+}
+
+fn link_value(cid: Cid) -> Value {
+    let mut map = Map::new();
+    map.insert("/".to_owned(), Value::String(cid.to_string()));
+
+    Value::Object(map)
+}
+
+/// Return the bytes if the value is DAG-JSON bytes.
+pub fn as_bytes(value: &Value) -> Option<Vec<u8>> {
+    let encoded = value.get("/")?.get("bytes")?.as_str()?;
+
+    base64::decode_config(encoded, base64::STANDARD_NO_PAD).ok()
+}
+
+/// Maximum nesting of CBOR values, deeper data is rejected instead of overflowing the stack.
+const MAX_CBOR_DEPTH: usize = 64;
+
+struct CborDecoder<'a> {
+    data: &'a [u8],
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> CborDecoder<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            depth: 0,
+        }
+    }
+
+    fn decode(mut self) -> Result<Value, ClientError> {
+        let value = self.value()?;
+
+        if self.position != self.data.len() {
+            return Err(ClientError::Decode("Trailing CBOR data".to_owned()));
+        }
+
+        Ok(value)
+    }
+
+    fn take(&mut self, count: usize) -> Result<&'a [u8], ClientError> {
+        let end = self.position + count;
+
+        match self.data.get(self.position..end) {
+            Some(slice) => {
+                self.position = end;
+                Ok(slice)
+            }
+            None => Err(ClientError::Decode("Unexpected end of CBOR".to_owned())),
+        }
+    }
+
+    fn argument(&mut self, info: u8) -> Result<u64, ClientError> {
+        let count = match info {
+            0..=23 => return Ok(info as u64),
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            _ => return Err(ClientError::Decode("Indefinite CBOR length".to_owned())),
+        };
+
+        let bytes = self.take(count)?;
+
+        Ok(bytes.iter().fold(0, |acc, byte| (acc << 8) | *byte as u64))
+    }
+
+    fn length(&mut self, info: u8) -> Result<usize, ClientError> {
+        let length = match usize::try_from(self.argument(info)?) {
+            Ok(length) => length,
+            Err(_) => return Err(ClientError::Decode("Invalid CBOR length".to_owned())),
+        };
+
+        // Every item is at least one byte
+        if length > self.data.len() - self.position {
+            return Err(ClientError::Decode("Invalid CBOR length".to_owned()));
+        }
+
+        Ok(length)
+    }
+
+    fn value(&mut self) -> Result<Value, ClientError> {
+        if self.depth >= MAX_CBOR_DEPTH {
+            return Err(ClientError::Decode("CBOR nested too deeply".to_owned()));
+        }
+
+        self.depth += 1;
+        let value = self.item();
+        self.depth -= 1;
+
+        value
+    }
+
+    fn item(&mut self) -> Result<Value, ClientError> {
+        let initial = self.take(1)?[0];
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+
+        match major {
+            0 => Ok(Value::from(self.argument(info)?)),
+            1 => {
+                let arg = self.argument(info)? as i128;
+
+                match i64::try_from(-1 - arg) {
+                    Ok(int) => Ok(Value::from(int)),
+                    Err(_) => Err(ClientError::Decode("CBOR integer overflow".to_owned())),
+                }
+            }
+            2 => {
+                let length = self.length(info)?;
+
+                Ok(bytes_value(self.take(length)?))
+            }
+            3 => {
+                let length = self.length(info)?;
+                let bytes = self.take(length)?;
+
+                match std::str::from_utf8(bytes) {
+                    Ok(str) => Ok(Value::String(str.to_owned())),
+                    Err(e) => Err(ClientError::Decode(e.to_string())),
+                }
+            }
+            4 => {
+                let length = self.length(info)?;
+
+                let mut array = Vec::with_capacity(length);
+
+                for _ in 0..length {
+                    array.push(self.value()?);
+                }
+
+                Ok(Value::Array(array))
+            }
+            5 => {
+                let length = self.length(info)?;
+
+                let mut map = Map::new();
+
+                for _ in 0..length {
+                    let key = match self.value()? {
+                        Value::String(key) => key,
+                        _ => return Err(ClientError::Decode("Non-string CBOR key".to_owned())),
+                    };
+
+                    let value = self.value()?;
+
+                    map.insert(key, value);
+                }
+
+                Ok(Value::Object(map))
+            }
+            6 => {
+                let tag = self.argument(info)?;
+
+                if tag != 42 {
+                    return Err(ClientError::Decode(format!("Unsupported CBOR tag {}", tag)));
+                }
+
+                let initial = self.take(1)?[0];
+
+                if initial >> 5 != 2 {
+                    return Err(ClientError::Decode("Invalid CID encoding".to_owned()));
+                }
+
+                let length = self.length(initial & 0x1f)?;
+                let bytes = self.take(length)?;
+
+                // Multibase identity prefix
+                match bytes.split_first() {
+                    Some((0, bytes)) => match Cid::try_from(bytes) {
+                        Ok(cid) => Ok(link_value(cid)),
+                        Err(e) => Err(ClientError::Decode(e.to_string())),
+                    },
+                    _ => Err(ClientError::Decode("Invalid CID encoding".to_owned())),
+                }
+            }
+            7 => match info {
+                20 => Ok(Value::Bool(false)),
+                21 => Ok(Value::Bool(true)),
+                22 => Ok(Value::Null),
+                25 => {
+                    let bits = self.argument(info)? as u16;
+
+                    float_value(half_to_f64(bits))
+                }
+                26 => {
+                    let bits = self.argument(info)? as u32;
+
+                    float_value(f32::from_bits(bits) as f64)
+                }
+                27 => {
+                    let bits = self.argument(info)?;
+
+                    float_value(f64::from_bits(bits))
+                }
+                _ => Err(ClientError::Decode(
+                    "Unsupported CBOR simple value".to_owned(),
+                )),
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+fn float_value(float: f64) -> Result<Value, ClientError> {
+    match Number::from_f64(float) {
+        Some(number) => Ok(Value::Number(number)),
+        None => Err(ClientError::Decode("Non-finite CBOR float".to_owned())),
+    }
+}
+
+fn half_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let fraction = (bits & 0x3ff) as f64;
+
+    match exponent {
+        0 => sign * fraction * 2f64.powi(-24),
+        31 if fraction == 0.0 => sign * f64::INFINITY,
+        31 => f64::NAN,
+        _ => sign * (1.0 + fraction / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+/// Split a protobuf message into it's fields.
+fn protobuf_fields(mut data: &[u8]) -> Result<Vec<(u64, Field)>, ClientError> {
+    let mut fields = Vec::new();
+
+    while !data.is_empty() {
+        let (key, rest) = varint(data)?;
+        data = rest;
+
+        let number = key >> 3;
+
+        match key & 0x7 {
+            0 => {
+                let (value, rest) = varint(data)?;
+                data = rest;
+
+                fields.push((number, Field::Varint(value)));
+            }
+            1 | 5 => {
+                let size = if key & 0x7 == 1 { 8 } else { 4 };
+
+                if data.len() < size {
+                    return Err(ClientError::Decode("Unexpected end of protobuf".to_owned()));
+                }
+
+                data = &data[size..];
+            }
+            2 => {
+                let (length, rest) = varint(data)?;
+
+                let length = match usize::try_from(length) {
+                    Ok(length) => length,
+                    Err(_) => {
+                        return Err(ClientError::Decode("Invalid protobuf length".to_owned()))
+                    }
+                };
+
+                if rest.len() < length {
+                    return Err(ClientError::Decode("Unexpected end of protobuf".to_owned()));
+                }
+
+                fields.push((number, Field::Bytes(&rest[..length])));
+                data = &rest[length..];
+            }
+            wire => {
+                return Err(ClientError::Decode(format!(
+                    "Unsupported protobuf wire type {}",
+                    wire
+                )))
+            }
+        }
+    }
+
+    Ok(fields)
+}
+
+fn varint(data: &[u8]) -> Result<(u64, &[u8]), ClientError> {
+    let mut value = 0u64;
+
+    for (i, byte) in data.iter().enumerate().take(10) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+
+        if byte & 0x80 == 0 {
+            return Ok((value, &data[i + 1..]));
+        }
+    }
+
+    Err(ClientError::Decode("Invalid varint".to_owned()))
+}
+
+/// A DAG-PB link.
+pub struct PbLink {
+    pub cid: Cid,
+    pub name: String,
+}
+
+/// Return the data and links of a DAG-PB node.
+pub fn pb_node(data: &[u8]) -> Result<(Option<Vec<u8>>, Vec<PbLink>), ClientError> {
+    let mut node_data = None;
+    let mut links = Vec::new();
+
+    for (number, field) in protobuf_fields(data)? {
+        match (number, field) {
+            (1, Field::Bytes(bytes)) => node_data = Some(bytes.to_vec()),
+            (2, Field::Bytes(bytes)) => {
+                let mut cid = None;
+                let mut name = String::new();
+
+                for (number, field) in protobuf_fields(bytes)? {
+                    match (number, field) {
+                        (1, Field::Bytes(bytes)) => match Cid::try_from(bytes) {
+                            Ok(link) => cid = Some(link),
+                            Err(e) => return Err(ClientError::Decode(e.to_string())),
+                        },
+                        (2, Field::Bytes(bytes)) => {
+                            name = String::from_utf8_lossy(bytes).into_owned()
+                        }
+                        _ => {}
+                    }
+                }
+
+                match cid {
+                    Some(cid) => links.push(PbLink { cid, name }),
+                    None => return Err(ClientError::Decode("DAG-PB link without CID".to_owned())),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok((node_data, links))
+}
+
+fn decode_pb_node(data: &[u8]) -> Result<Value, ClientError> {
+    let (node_data, links) = pb_node(data)?;
+
+    let links = links
+        .into_iter()
+        .map(|link| {
+            let mut map = Map::new();
+            map.insert("Hash".to_owned(), link_value(link.cid));
+            map.insert("Name".to_owned(), Value::String(link.name));
+
+            Value::Object(map)
+        })
+        .collect();
+
+    let mut map = Map::new();
+
+    if let Some(data) = node_data {
+        map.insert("Data".to_owned(), bytes_value(&data));
+    }
+
+    map.insert("Links".to_owned(), Value::Array(links));
+
+    Ok(Value::Object(map))
+}
+
+/// Return the file bytes embedded in UnixFS data.
+pub fn unixfs_data(data: &[u8]) -> Result<Vec<u8>, ClientError> {
+    for (number, field) in protobuf_fields(data)? {
+        if let (2, Field::Bytes(bytes)) = (number, field) {
+            return Ok(bytes.to_vec());
+        }
+    }
+
+    Ok(Vec::new())
+}
+
+/// Verify the IPNS record of the name then return it's path value.
+///
+/// The V2 signature must match the name key and the record must not be expired at `now`, in milliseconds.
+/// Only Ed25519 keys are supported.
+pub fn ipns_record_value(name: &Cid, record: &[u8], now: i64) -> Result<String, ClientError> {
+    let mut value = None;
+    let mut key = None;
+    let mut signature = None;
+    let mut cbor = None;
+
+    for (number, field) in protobuf_fields(record)? {
+        match (number, field) {
+            (1, Field::Bytes(bytes)) => value = Some(bytes),
+            (7, Field::Bytes(bytes)) => key = Some(bytes),
+            (8, Field::Bytes(bytes)) => signature = Some(bytes),
+            (9, Field::Bytes(bytes)) => cbor = Some(bytes),
+            _ => {}
+        }
+    }
+
+    let (signature, cbor) = match (signature, cbor) {
+        (Some(signature), Some(cbor)) => (signature, cbor),
+        _ => {
+            return Err(ClientError::Decode(
+                "IPNS record without V2 signature".to_owned(),
+            ))
+        }
+    };
+
+    let key = ipns_public_key(name, key)?;
+
+    let signature = match Signature::try_from(signature) {
+        Ok(signature) => signature,
+        Err(_) => return Err(ClientError::Signature(*name)),
+    };
+
+    let mut message = IPNS_SIGNATURE_PREFIX.to_vec();
+    message.extend_from_slice(cbor);
+
+    if key.verify_strict(&message, &signature).is_err() {
+        return Err(ClientError::Signature(*name));
+    }
+
+    let data = CborDecoder::new(cbor).decode()?;
+
+    let path = match data.get("Value").and_then(as_bytes) {
+        Some(path) => path,
+        None => return Err(ClientError::Decode("IPNS record without value".to_owned())),
+    };
+
+    // V1 fields are not signed, they must match the signed data
+    if value.map_or(false, |value| value != path.as_slice()) {
+        return Err(ClientError::Signature(*name));
+    }
+
+    // End of life is the only validity type
+    if data.get("ValidityType").and_then(Value::as_u64) != Some(0) {
+        return Err(ClientError::Decode(
+            "Unsupported IPNS validity type".to_owned(),
+        ));
+    }
+
+    let validity = match data.get("Validity").and_then(as_bytes) {
+        Some(validity) => String::from_utf8_lossy(&validity).into_owned(),
+        None => {
+            return Err(ClientError::Decode(
+                "IPNS record without validity".to_owned(),
+            ))
+        }
+    };
+
+    let validity = match DateTime::parse_from_rfc3339(&validity) {
+        Ok(validity) => validity,
+        Err(e) => return Err(ClientError::Decode(e.to_string())),
+    };
+
+    if validity.timestamp_millis() < now {
+        return Err(ClientError::Expired(*name));
+    }
+
+    match String::from_utf8(path) {
+        Ok(path) => Ok(path),
+        Err(e) => Err(ClientError::Decode(e.to_string())),
+    }
+}
+
+/// Public key of the IPNS name, inlined in the name or hashed from the record key.
+fn ipns_public_key(name: &Cid, record_key: Option<&[u8]>) -> Result<PublicKey, ClientError> {
+    let hash = name.hash();
+
+    let key = match record_key {
+        Some(key) => key,
+        None if hash.code() == IDENTITY_HASH => hash.digest(),
+        None => {
+            return Err(ClientError::Decode(
+                "IPNS record without public key".to_owned(),
+            ))
+        }
+    };
+
+    if !verify_block(name, key) {
+        return Err(ClientError::Signature(*name));
+    }
+
+    let mut key_type = None;
+    let mut key_data = None;
+
+    for (number, field) in protobuf_fields(key)? {
+        match (number, field) {
+            (1, Field::Varint(value)) => key_type = Some(value),
+            (2, Field::Bytes(bytes)) => key_data = Some(bytes),
+            _ => {}
+        }
+    }
+
+    match (key_type, key_data) {
+        (Some(ED25519), Some(data)) => match PublicKey::from_bytes(data) {
+            Ok(key) => Ok(key),
+            Err(_) => Err(ClientError::Signature(*name)),
+        },
+        _ => Err(ClientError::Decode("Unsupported IPNS key type".to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ed25519_dalek::{Keypair, SecretKey, Signer};
+
+    use serde_json::json;
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn decode_cbor(data: &[u8]) -> Result<Value, ClientError> {
+        CborDecoder::new(data).decode()
+    }
+
+    fn raw_cid(data: &[u8]) -> Cid {
+        Cid::new_v1(RAW, Code::Sha2_256.digest(data))
+    }
+
+    /// Length delimited protobuf field.
+    fn pb_field(number: u8, bytes: &[u8]) -> Vec<u8> {
+        let mut field = vec![(number << 3) | 2];
+
+        let mut length = bytes.len();
+
+        while length >= 0x80 {
+            field.push((length as u8 & 0x7f) | 0x80);
+            length >>= 7;
+        }
+
+        field.push(length as u8);
+        field.extend_from_slice(bytes);
+
+        field
+    }
+
+    /// CBOR head of a short item.
+    fn cbor_head(major: u8, length: usize) -> Vec<u8> {
+        match length {
+            0..=23 => vec![(major << 5) | length as u8],
+            _ => vec![(major << 5) | 24, length as u8],
+        }
+    }
+
+    fn cbor_text(text: &str) -> Vec<u8> {
+        let mut data = cbor_head(3, text.len());
+        data.extend_from_slice(text.as_bytes());
+
+        data
+    }
+
+    fn cbor_bytes(bytes: &[u8]) -> Vec<u8> {
+        let mut data = cbor_head(2, bytes.len());
+        data.extend_from_slice(bytes);
+
+        data
+    }
+
+    fn ipns_keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).expect("Valid Secret Key");
+        let public = PublicKey::from(&secret);
+
+        Keypair { secret, public }
+    }
+
+    /// IPNS name inlining the Ed25519 public key.
+    fn ipns_name(keypair: &Keypair) -> Cid {
+        let mut key = vec![0x08, ED25519 as u8];
+        key.extend(pb_field(2, keypair.public.as_bytes()));
+
+        Cid::new_v1(LIBP2P_KEY, Code::Identity.digest(&key))
+    }
+
+    /// IPNS record signed by the keypair, with an optional V1 value.
+    fn ipns_record(keypair: &Keypair, path: &str, validity: &str, value: Option<&str>) -> Vec<u8> {
+        let mut data = vec![0xa3];
+        data.extend(cbor_text("Value"));
+        data.extend(cbor_bytes(path.as_bytes()));
+        data.extend(cbor_text("Validity"));
+        data.extend(cbor_bytes(validity.as_bytes()));
+        data.extend(cbor_text("ValidityType"));
+        data.push(0x00);
+
+        let mut message = IPNS_SIGNATURE_PREFIX.to_vec();
+        message.extend_from_slice(&data);
+
+        let signature = keypair.sign(&message);
+
+        let mut record = Vec::new();
+
+        if let Some(value) = value {
+            record.extend(pb_field(1, value.as_bytes()));
+        }
+
+        record.extend(pb_field(8, &signature.to_bytes()));
+        record.extend(pb_field(9, &data));
+
+        record
+    }
+
+    #[wasm_bindgen_test]
+    fn cbor_map_and_array() {
+        // {"a": 1, "b": [true, null]}
+        let data = [0xa2, 0x61, b'a', 0x01, 0x61, b'b', 0x82, 0xf5, 0xf6];
+
+        let value = decode_cbor(&data).expect("Valid CBOR");
+
+        assert_eq!(value, json!({ "a": 1, "b": [true, null] }));
+    }
+
+    #[wasm_bindgen_test]
+    fn cbor_scalars() {
+        assert_eq!(decode_cbor(&[0x29]).unwrap(), json!(-10));
+        assert_eq!(decode_cbor(&[0xf9, 0x3e, 0x00]).unwrap(), json!(1.5));
+        assert_eq!(
+            decode_cbor(&[0x42, 0x01, 0x02]).unwrap(),
+            json!({ "/": { "bytes": "AQI" } })
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn cbor_link() {
+        let cid = raw_cid(b"data");
+        let bytes = cid.to_bytes();
+
+        let mut data = vec![0xd8, 0x2a, 0x58, bytes.len() as u8 + 1, 0x00];
+        data.extend_from_slice(&bytes);
+
+        let value = decode_cbor(&data).expect("Valid CBOR");
+
+        assert_eq!(value, json!({ "/": cid.to_string() }));
+    }
+
+    #[wasm_bindgen_test]
+    fn cbor_invalid() {
+        // Trailing data
+        assert!(decode_cbor(&[0x01, 0x01]).is_err());
+
+        // Truncated string
+        assert!(decode_cbor(&[0x63, b'a']).is_err());
+
+        // Length larger than the data
+        assert!(decode_cbor(&[0x9a, 0xff, 0xff, 0xff, 0xff]).is_err());
+
+        // Unsupported tag
+        assert!(decode_cbor(&[0xc1, 0x01]).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn cbor_depth_limit() {
+        let mut shallow = vec![0x81; MAX_CBOR_DEPTH - 1];
+        shallow.push(0x01);
+
+        assert!(decode_cbor(&shallow).is_ok());
+
+        let mut deep = vec![0x81; 10_000];
+        deep.push(0x01);
+
+        assert!(matches!(decode_cbor(&deep), Err(ClientError::Decode(_))));
+    }
+
+    #[wasm_bindgen_test]
+    fn oversized_lengths() {
+        // Lengths past the end of the data, including ones truncated by 32 bits targets
+        let cases: [&[u8]; 6] = [
+            &[0x5a, 0xff, 0xff, 0xff, 0xff],
+            &[0x5b, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00],
+            &[0x7b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            &[0x9b, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x01],
+            &[0xbb, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            &[
+                0xd8, 0x2a, 0x5b, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00,
+            ],
+        ];
+
+        for data in cases {
+            assert!(decode_cbor(data).is_err());
+        }
+
+        // Length delimited field of 2^32 + 1 bytes
+        let field = [0x0a, 0x81, 0x80, 0x80, 0x80, 0x10, 0x00];
+
+        assert!(pb_node(&field).is_err());
+        assert!(unixfs_data(&field).is_err());
+    }
+
+    /// Deterministic xorshift generator, for fuzzing.
+    struct Fuzzer(u64);
+
+    impl Fuzzer {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+
+            self.0
+        }
+
+        fn below(&mut self, max: usize) -> usize {
+            (self.next() % max as u64) as usize
+        }
+
+        fn bytes(&mut self, max: usize) -> Vec<u8> {
+            let length = self.below(max);
+
+            (0..length).map(|_| self.next() as u8).collect()
+        }
+
+        /// Overwrite, insert or truncate a few bytes.
+        fn mutate(&mut self, seed: &[u8]) -> Vec<u8> {
+            let mut data = seed.to_vec();
+
+            for _ in 0..=self.below(4) {
+                match self.below(3) {
+                    0 if !data.is_empty() => {
+                        let i = self.below(data.len());
+                        data[i] = self.next() as u8;
+                    }
+                    1 => {
+                        let i = self.below(data.len() + 1);
+                        data.insert(i, self.next() as u8);
+                    }
+                    _ => {
+                        let length = self.below(data.len() + 1);
+                        data.truncate(length);
+                    }
+                }
+            }
+
+            data
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn fuzz_cbor() {
+        let mut fuzzer = Fuzzer(0x2545_f491_4f6c_dd1d);
+
+        let cid = raw_cid(b"data").to_bytes();
+        let mut link = vec![0xd8, 0x2a, 0x58, cid.len() as u8 + 1, 0x00];
+        link.extend_from_slice(&cid);
+
+        let seeds = [
+            vec![0xa2, 0x61, b'a', 0x01, 0x61, b'b', 0x82, 0xf5, 0xf6],
+            vec![0xf9, 0x3e, 0x00],
+            vec![0x42, 0x01, 0x02],
+            link,
+        ];
+
+        for i in 0..10_000 {
+            let data = match i % 2 {
+                0 => fuzzer.bytes(64),
+                _ => fuzzer.mutate(&seeds[i % seeds.len()]),
+            };
+
+            // Any result is fine, as long as it does not panic
+            let _ = decode_cbor(&data);
+        }
+
+        // Nesting of random depth, only accepted under the limit
+        for _ in 0..100 {
+            let depth = fuzzer.below(MAX_CBOR_DEPTH * 2);
+
+            let mut data = Vec::with_capacity(depth + 1);
+
+            for _ in 0..depth {
+                data.push(match fuzzer.below(2) {
+                    0 => 0x81,
+                    _ => 0xa1,
+                });
+
+                // Map keys are strings
+                if data.last() == Some(&0xa1) {
+                    data.extend_from_slice(&[0x61, b'k']);
+                }
+            }
+
+            data.push(0x01);
+
+            assert_eq!(decode_cbor(&data).is_ok(), depth < MAX_CBOR_DEPTH);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn fuzz_protobuf() {
+        let mut fuzzer = Fuzzer(0x9e37_79b9_7f4a_7c15);
+
+        let keypair = ipns_keypair(1);
+        let name = ipns_name(&keypair);
+        let path = format!("/ipfs/{}", raw_cid(b"root"));
+
+        let mut link = pb_field(1, &raw_cid(b"child").to_bytes());
+        link.extend(pb_field(2, b"child"));
+
+        let mut node = pb_field(2, &link);
+        node.extend(pb_field(1, &[0x08, 0x02, 0x12, 0x01, 0x00]));
+
+        let seeds = [node, ipns_record(&keypair, &path, VALID_UNTIL, Some(&path))];
+
+        for i in 0..10_000 {
+            let data = match i % 2 {
+                0 => fuzzer.bytes(64),
+                _ => fuzzer.mutate(&seeds[i % seeds.len()]),
+            };
+
+            // Any result is fine, as long as it does not panic
+            let _ = pb_node(&data);
+            let _ = unixfs_data(&data);
+            let _ = ipns_record_value(&name, &data, NOW);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn dag_pb_node() {
+        let cid = raw_cid(b"child");
+
+        let mut link = pb_field(1, &cid.to_bytes());
+        link.extend(pb_field(2, b"child"));
+
+        let mut data = pb_field(2, &link);
+        data.extend(pb_field(1, &[0x08, 0x01]));
+
+        let (node_data, links) = pb_node(&data).expect("Valid DAG-PB");
+
+        assert_eq!(node_data, Some(vec![0x08, 0x01]));
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].cid, cid);
+        assert_eq!(links[0].name, "child");
+    }
+
+    #[wasm_bindgen_test]
+    fn unixfs_file_data() {
+        let mut data = vec![0x08, 0x02];
+        data.extend(pb_field(2, b"abc"));
+
+        assert_eq!(unixfs_data(&data).unwrap(), b"abc".to_vec());
+    }
+
+    /// 2023-11-14T22:13:20Z in milliseconds.
+    const NOW: i64 = 1_700_000_000_000;
+
+    const VALID_UNTIL: &str = "2999-01-01T00:00:00.000000000Z";
+
+    #[wasm_bindgen_test]
+    fn ipns_signed_record() {
+        let keypair = ipns_keypair(1);
+        let name = ipns_name(&keypair);
+        let path = format!("/ipfs/{}", raw_cid(b"root"));
+
+        let record = ipns_record(&keypair, &path, VALID_UNTIL, Some(&path));
+
+        assert_eq!(ipns_record_value(&name, &record, NOW).unwrap(), path);
+    }
+
+    #[wasm_bindgen_test]
+    fn ipns_expired_record() {
+        let keypair = ipns_keypair(1);
+        let name = ipns_name(&keypair);
+        let path = format!("/ipfs/{}", raw_cid(b"root"));
+
+        let record = ipns_record(&keypair, &path, "2000-01-01T00:00:00Z", None);
+
+        assert!(matches!(
+            ipns_record_value(&name, &record, NOW),
+            Err(ClientError::Expired(_))
+        ));
+    }
+
+    #[wasm_bindgen_test]
+    fn ipns_forged_record() {
+        let owner = ipns_keypair(1);
+        let name = ipns_name(&owner);
+        let path = format!("/ipfs/{}", raw_cid(b"root"));
+        let forged = format!("/ipfs/{}", raw_cid(b"forged"));
+
+        // Signed by another key
+        let record = ipns_record(&ipns_keypair(2), &forged, VALID_UNTIL, None);
+
+        assert!(matches!(
+            ipns_record_value(&name, &record, NOW),
+            Err(ClientError::Signature(_))
+        ));
+
+        // Unsigned V1 value
+        let record = ipns_record(&owner, &path, VALID_UNTIL, Some(&forged));
+
+        assert!(matches!(
+            ipns_record_value(&name, &record, NOW),
+            Err(ClientError::Signature(_))
+        ));
+
+        // V1 only
+        let record = pb_field(1, forged.as_bytes());
+
+        assert!(ipns_record_value(&name, &record, NOW).is_err());
+        assert!(ipns_record_value(&name, &[], NOW).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn block_verification() {
+        let cid = raw_cid(b"block");
+
+        assert!(verify_block(&cid, b"block"));
+        assert!(!verify_block(&cid, b"forged"));
+    }
+}
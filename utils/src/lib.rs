@@ -1,15 +1,38 @@
 #![cfg(target_arch = "wasm32")]
 
+pub mod aggregation;
 pub mod r#async;
+pub mod backup;
+pub mod cache;
+pub mod client;
 pub mod commentary;
 pub mod defluencer;
+pub mod errors;
+pub mod follow_sync;
+pub mod identities;
 pub mod identity;
 pub mod ipfs;
+pub mod ipld;
+pub mod metadata;
+pub mod moderation;
+pub mod mute;
+pub mod notifications;
+pub mod outbox;
+pub mod reactions;
 pub mod subscriptions;
+pub mod syndication;
 pub mod web3;
 pub mod web_crypto;
 
-use chrono::{DateTime, Local, TimeZone, Utc};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+
+use gloo_console::error;
+
+use gloo_timers::future::TimeoutFuture;
+
+use wasm_bindgen::{JsCast, JsValue};
+
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
 
 /// Translate total number of seconds to timecode.
 pub fn seconds_to_timecode(seconds: f64) -> (u8, u8, u8) {
@@ -39,9 +62,55 @@ pub fn timestamp_to_datetime(seconds: i64) -> String {
     local_d_t.format("%Y-%m-%d %H:%M:%S").to_string()
 }
 
+/// Local date string (YYYY-MM-DD) to unix time at midnight in total number of seconds.
+pub fn date_to_timestamp(date: &str) -> Option<i64> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+
+    let d_t = date.and_hms_opt(0, 0, 0)?;
+
+    match Local.from_local_datetime(&d_t) {
+        chrono::LocalResult::None => None,
+        chrono::LocalResult::Single(time) => Some(time.timestamp()),
+        chrono::LocalResult::Ambiguous(time, _) => Some(time.timestamp()),
+    }
+}
+
 /// Take 20 bytes in hexa and prefix it with 0x
 pub fn display_address(addr: [u8; 20]) -> String {
     let mut addr = hex::encode(addr);
     addr.insert_str(0, "0x");
     addr
 }
+
+/// Milliseconds before a downloaded file url is revoked.
+const REVOKE_DELAY: u32 = 60_000;
+
+/// Prompt the browser to save this document.
+pub fn save_file(document: &str, file_name: &str, mime_type: &str) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&document.into());
+
+    let mut options = BlobPropertyBag::new();
+    options.type_(mime_type);
+
+    let blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let window = web_sys::window().unwrap();
+    let document = window.document().unwrap();
+
+    let anchor: HtmlAnchorElement = document.create_element("a")?.unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+
+    // Some browsers start the download after the click returns
+    wasm_bindgen_futures::spawn_local(async move {
+        TimeoutFuture::new(REVOKE_DELAY).await;
+
+        if let Err(e) = Url::revoke_object_url(&url) {
+            error!(&format!("{:#?}", e));
+        }
+    });
+
+    Ok(())
+}
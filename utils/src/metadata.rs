@@ -0,0 +1,189 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::Cell, rc::Rc};
+
+use cid::Cid;
+
+use gloo_timers::future::TimeoutFuture;
+
+use linked_data::types::{IPLDLink, IPNSAddress};
+
+use crate::{
+    client::{as_link, link_value, IpfsClient},
+    defluencer::ChannelContext,
+    errors::AppError,
+    moderation,
+    outbox::{self, Operation},
+    reactions,
+};
+
+/// Fields added by this app to the channel metadata, dropped by defluencer channel updates.
+const APP_FIELDS: [&str; 2] = [moderation::METADATA_KEY, reactions::METADATA_KEY];
+
+/// Milliseconds between checks of a held lock.
+const LOCK_POLL: u32 = 100;
+
+thread_local! {
+    static WRITING: Cell<bool> = Cell::new(false);
+}
+
+/// Held while a field root is read, updated then set, so that concurrent updates are not lost.
+pub struct WriteLock;
+
+impl WriteLock {
+    pub async fn acquire() -> Self {
+        loop {
+            if !WRITING.with(|cell| cell.replace(true)) {
+                return Self;
+            }
+
+            TimeoutFuture::new(LOCK_POLL).await;
+        }
+    }
+}
+
+impl Drop for WriteLock {
+    fn drop(&mut self) {
+        WRITING.with(|cell| cell.set(false));
+    }
+}
+
+/// Root linked under this field of the channel metadata, if any.
+pub async fn get(
+    ipfs: &Rc<dyn IpfsClient>,
+    addr: IPNSAddress,
+    key: &str,
+) -> Result<Option<Cid>, AppError> {
+    let cid = ipfs
+        .name_resolve(addr.into())
+        .await
+        .map_err(AppError::ipns)?;
+
+    let metadata = ipfs.dag_get_value(cid, None).await?;
+
+    Ok(metadata.get(key).and_then(as_link))
+}
+
+/// Root linked under this field of a local channel, queued links first.
+pub async fn get_latest(
+    ipfs: &Rc<dyn IpfsClient>,
+    channel: &ChannelContext,
+    key: &str,
+) -> Result<Option<Cid>, AppError> {
+    let addr = channel.channel.get_address();
+
+    let queued = outbox::list()
+        .await
+        .into_iter()
+        .rev()
+        .filter(|pending| pending.channel == addr)
+        .find_map(|pending| match pending.operation {
+            Operation::SetLink(field, ipld) if field == key => Some(ipld.link),
+            _ => pending
+                .links
+                .into_iter()
+                .find(|(field, _)| field == key)
+                .map(|(_, root)| root),
+        });
+
+    match queued {
+        Some(root) => Ok(Some(root)),
+        None => get(ipfs, addr, key).await,
+    }
+}
+
+/// Link the root from this field of the channel metadata, published by the outbox.
+pub async fn set(channel: &ChannelContext, key: &str, root: Cid) -> Result<(), AppError> {
+    let operation = Operation::SetLink(key.to_owned(), root.into());
+
+    outbox::submit(channel, operation).await?;
+
+    Ok(())
+}
+
+/// Update the links with the fields added by this app to the current channel metadata.
+///
+/// Links missing from the metadata are kept.
+pub async fn read_links(
+    channel: &ChannelContext,
+    links: &mut Vec<(String, Cid)>,
+) -> Result<(), AppError> {
+    let ipfs: &dyn IpfsClient = &channel.ipfs;
+
+    let cid = ipfs
+        .name_resolve(channel.channel.get_address().into())
+        .await
+        .map_err(AppError::ipns)?;
+
+    let metadata = ipfs.dag_get_value(cid, None).await?;
+
+    for key in APP_FIELDS {
+        let root = match metadata.get(key).and_then(as_link) {
+            Some(root) => root,
+            None => continue,
+        };
+
+        links.retain(|(field, _)| field != key);
+        links.push((key.to_owned(), root));
+    }
+
+    Ok(())
+}
+
+/// Link the roots from these fields of the channel metadata then publish it.
+///
+/// Return the published root, the same one if nothing changed.
+pub async fn link_fields(
+    channel: &ChannelContext,
+    root: Cid,
+    links: &[(String, Cid)],
+) -> Result<Cid, AppError> {
+    let ipfs: &dyn IpfsClient = &channel.ipfs;
+
+    // Updated as a value so that fields outside the defluencer schema are kept
+    let mut metadata = ipfs.dag_get_value(root, None).await?;
+
+    let map = match metadata.as_object_mut() {
+        Some(map) => map,
+        None => return Err(AppError::Decode(root.to_string())),
+    };
+
+    let mut changed = false;
+
+    for (key, link) in links {
+        if map.get(key).and_then(as_link) == Some(*link) {
+            continue;
+        }
+
+        map.insert(key.clone(), link_value(*link));
+        changed = true;
+    }
+
+    if !changed {
+        return Ok(root);
+    }
+
+    let root = ipfs.dag_put_value(&metadata).await?;
+
+    ipfs.name_publish(root, channel.key.clone())
+        .await
+        .map_err(AppError::ipns)?;
+
+    Ok(root)
+}
+
+/// Link the root from this field of the current channel metadata then publish it.
+pub async fn publish_link(
+    channel: &ChannelContext,
+    key: &str,
+    ipld: IPLDLink,
+) -> Result<Cid, AppError> {
+    let ipfs: &dyn IpfsClient = &channel.ipfs;
+
+    let cid = ipfs
+        .name_resolve(channel.channel.get_address().into())
+        .await
+        .map_err(AppError::ipns)?;
+
+    link_fields(channel, cid, &[(key.to_owned(), ipld.link)]).await
+}
@@ -0,0 +1,113 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{collections::HashSet, rc::Rc};
+
+use cid::Cid;
+
+use defluencer::crypto::{
+    signed_link::SignedLink,
+    signers::{MetamaskSigner, Signer},
+};
+
+use gloo_console::error;
+
+use linked_data::{
+    media::comments::Comment,
+    types::{IPLDLink, IPNSAddress},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{client::IpfsClient, defluencer::ChannelContext, errors::AppError, metadata};
+
+/// Field of the channel metadata linking to the signed moderation list.
+pub const METADATA_KEY: &str = "moderation";
+
+/// Comments a channel owner hides under their own content.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Moderation {
+    /// Hidden comments
+    #[serde(default)]
+    pub hidden: HashSet<IPLDLink>,
+
+    /// Identities of which all comments are hidden
+    #[serde(default)]
+    pub blocked: HashSet<IPLDLink>,
+}
+
+impl Moderation {
+    pub fn hides(&self, cid: Cid, comment: &Comment) -> bool {
+        self.hidden.contains(&cid.into()) || self.blocked.contains(&comment.identity)
+    }
+
+    pub fn is_blocked(&self, identity: &IPLDLink) -> bool {
+        self.blocked.contains(identity)
+    }
+
+    /// Returns true if now hidden.
+    pub fn toggle_hidden(&mut self, cid: Cid) -> bool {
+        let link = cid.into();
+
+        let hidden = self.hidden.insert(link);
+
+        if !hidden {
+            self.hidden.remove(&link);
+        }
+
+        hidden
+    }
+
+    /// Returns true if now blocked.
+    pub fn toggle_blocked(&mut self, identity: IPLDLink) -> bool {
+        let blocked = self.blocked.insert(identity);
+
+        if !blocked {
+            self.blocked.remove(&identity);
+        }
+
+        blocked
+    }
+}
+
+/// Fetch the moderation list of a channel.
+///
+/// Lists not signed by the owner address are ignored.
+pub async fn fetch(
+    ipfs: &Rc<dyn IpfsClient>,
+    addr: IPNSAddress,
+    owner: &str,
+) -> Result<Option<Moderation>, AppError> {
+    let root = match metadata::get(ipfs, addr, METADATA_KEY).await? {
+        Some(root) => root,
+        None => return Ok(None),
+    };
+
+    let signed_link = ipfs.dag_get::<SignedLink>(root, None).await?;
+
+    if !signed_link.verify() || signed_link.get_address() != owner {
+        error!("Moderation List Signature Verification Failed!");
+        return Ok(None);
+    }
+
+    let moderation = ipfs
+        .dag_get::<Moderation>(signed_link.link.link, None)
+        .await?;
+
+    Ok(Some(moderation))
+}
+
+/// Sign the moderation list then link it from the channel metadata.
+pub async fn publish(
+    ipfs: &Rc<dyn IpfsClient>,
+    channel: &ChannelContext,
+    signer: &MetamaskSigner,
+    moderation: &Moderation,
+) -> Result<(), AppError> {
+    let cid = ipfs.dag_put(moderation).await?;
+
+    let signed_link = signer.sign(cid).await.map_err(AppError::signing)?;
+
+    let root = ipfs.dag_put(&signed_link).await?;
+
+    metadata::set(channel, METADATA_KEY, root).await
+}
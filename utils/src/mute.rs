@@ -0,0 +1,256 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{collections::HashSet, fmt, str::FromStr};
+
+use cid::Cid;
+
+use gloo_console::error;
+
+use gloo_storage::{LocalStorage, Storage};
+
+use js_sys::{Array, Function, Reflect, RegExp};
+
+use linked_data::{identity::Identity, media::Media, types::IPNSAddress};
+
+use serde::{Deserialize, Serialize};
+
+use wasm_bindgen::JsCast;
+
+const MUTE_LIST: &str = "mute_list";
+
+/// Something to hide.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(tag = "kind", content = "value")]
+pub enum MuteEntry {
+    /// Content and comments signed with this identity.
+    Identity(Cid),
+
+    /// Content from or shared by this channel.
+    Channel(IPNSAddress),
+
+    /// Anything signed by this Ethereum address.
+    EthAddress(String),
+
+    /// Text containing this word, case insensitive.
+    Keyword(String),
+
+    /// Text matching this regular expression.
+    Regex(String),
+}
+
+impl MuteEntry {
+    /// Guess the kind of entry from user input.
+    ///
+    /// Regexes are written between slashes e.g. /crypto|nft/
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+
+        if input.is_empty() {
+            return None;
+        }
+
+        if input.len() > 2 && input.starts_with('/') && input.ends_with('/') {
+            let pattern = &input[1..input.len() - 1];
+
+            // Invalid patterns are rejected
+            compile_regex(pattern)?;
+
+            return Some(Self::Regex(pattern.to_owned()));
+        }
+
+        if is_eth_addr(input) {
+            return Some(Self::EthAddress(input.to_lowercase()));
+        }
+
+        if let Ok(addr) = IPNSAddress::from_str(input) {
+            return Some(Self::Channel(addr));
+        }
+
+        if let Ok(cid) = Cid::try_from(input) {
+            return Some(Self::Identity(cid));
+        }
+
+        Some(Self::Keyword(input.to_lowercase()))
+    }
+
+    /// Regexes and Ethereum addresses are checked, other kinds are validated when deserialized.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::EthAddress(addr) => is_eth_addr(addr),
+            Self::Regex(pattern) => compile_regex(pattern).is_some(),
+            Self::Identity(_) | Self::Channel(_) | Self::Keyword(_) => true,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Identity(_) => "Identity",
+            Self::Channel(_) => "Channel",
+            Self::EthAddress(_) => "Ethereum",
+            Self::Keyword(_) => "Keyword",
+            Self::Regex(_) => "Regex",
+        }
+    }
+}
+
+impl fmt::Display for MuteEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Identity(cid) => write!(f, "{}", cid),
+            Self::Channel(addr) => write!(f, "{}", addr),
+            Self::EthAddress(addr) => write!(f, "{}", addr),
+            Self::Keyword(word) => write!(f, "{}", word),
+            Self::Regex(pattern) => write!(f, "/{}/", pattern),
+        }
+    }
+}
+
+/// Get all mute entries
+pub fn get_mute_list() -> Vec<MuteEntry> {
+    match LocalStorage::get(MUTE_LIST) {
+        Ok(list) => return list,
+        Err(e) => error!(&format!("{:?}", e)),
+    }
+
+    Vec::new()
+}
+
+/// Set new list of mute entries
+pub fn set_mute_list(list: &[MuteEntry]) {
+    if let Err(e) = LocalStorage::set(MUTE_LIST, list) {
+        error!(&format!("{:?}", e));
+    }
+}
+
+/// 0x followed by 20 bytes in hexa.
+pub fn is_eth_addr(addr: &str) -> bool {
+    addr.len() == 42
+        && addr.starts_with("0x")
+        && addr[2..].chars().all(|char| char.is_ascii_hexdigit())
+}
+
+/// Case insensitive regex or None if the pattern is invalid.
+fn compile_regex(pattern: &str) -> Option<RegExp> {
+    // RegExp::new throws on invalid patterns, Reflect::construct returns the error instead.
+    let constructor: Function = Reflect::get(&js_sys::global(), &"RegExp".into())
+        .ok()?
+        .unchecked_into();
+
+    let args = Array::of2(&pattern.into(), &"iu".into());
+
+    match Reflect::construct(&constructor, &args) {
+        Ok(regex) => Some(regex.unchecked_into()),
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            None
+        }
+    }
+}
+
+/// Mute entries ready for matching.
+#[derive(Default, Clone)]
+pub struct MuteList {
+    identities: HashSet<Cid>,
+    channels: HashSet<IPNSAddress>,
+    eth_addrs: HashSet<String>,
+    keywords: Vec<String>,
+    patterns: Vec<RegExp>,
+}
+
+impl MuteList {
+    /// Load the mute list from storage.
+    pub fn load() -> Self {
+        Self::new(get_mute_list())
+    }
+
+    pub fn new(entries: impl IntoIterator<Item = MuteEntry>) -> Self {
+        let mut list = Self::default();
+
+        for entry in entries {
+            match entry {
+                MuteEntry::Identity(cid) => {
+                    list.identities.insert(cid);
+                }
+                MuteEntry::Channel(addr) => {
+                    list.channels.insert(addr);
+                }
+                MuteEntry::EthAddress(addr) => {
+                    list.eth_addrs.insert(addr.to_lowercase());
+                }
+                MuteEntry::Keyword(word) => list.keywords.push(word.to_lowercase()),
+                MuteEntry::Regex(pattern) => list.patterns.extend(compile_regex(&pattern)),
+            }
+        }
+
+        list
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.identities.is_empty()
+            && self.channels.is_empty()
+            && self.eth_addrs.is_empty()
+            && self.keywords.is_empty()
+            && self.patterns.is_empty()
+    }
+
+    pub fn mutes_channel(&self, addr: &IPNSAddress) -> bool {
+        self.channels.contains(addr)
+    }
+
+    pub fn mutes_eth_addr(&self, addr: &str) -> bool {
+        self.eth_addrs.contains(&addr.to_lowercase())
+    }
+
+    /// Check the identity CID and, once fetched, its channel and Ethereum address.
+    pub fn mutes_identity(&self, cid: &Cid, identity: Option<&Identity>) -> bool {
+        if self.identities.contains(cid) {
+            return true;
+        }
+
+        let identity = match identity {
+            Some(identity) => identity,
+            None => return false,
+        };
+
+        if let Some(addr) = identity.ipns_addr.as_ref() {
+            if self.mutes_channel(addr) {
+                return true;
+            }
+        }
+
+        if let Some(addr) = identity.eth_addr.as_deref() {
+            if self.mutes_eth_addr(addr) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn mutes_text(&self, text: &str) -> bool {
+        let lowercase = text.to_lowercase();
+
+        if self
+            .keywords
+            .iter()
+            .any(|word| lowercase.contains(word.as_str()))
+        {
+            return true;
+        }
+
+        self.patterns.iter().any(|regex| regex.test(text))
+    }
+
+    /// Check the media signer and titles or comment text.
+    pub fn mutes_media(&self, media: &Media, identity: Option<&Identity>) -> bool {
+        if self.mutes_identity(&media.identity().link, identity) {
+            return true;
+        }
+
+        match media {
+            Media::Blog(article) => self.mutes_text(&article.title),
+            Media::Video(video) => self.mutes_text(&video.title),
+            Media::Comment(comment) => self.mutes_text(&comment.text),
+        }
+    }
+}
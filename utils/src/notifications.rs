@@ -0,0 +1,351 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use cid::Cid;
+
+use defluencer::Defluencer;
+
+use futures_util::stream::{AbortHandle, StreamExt, TryStreamExt};
+
+use gloo_console::error;
+
+use gloo_storage::{LocalStorage, Storage};
+
+use linked_data::{channel::ChannelMetadata, identity::Identity, media::Media, types::IPNSAddress};
+
+use serde::{Deserialize, Serialize};
+
+use wasm_bindgen::{closure::Closure, JsCast};
+
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+
+use web_sys::{Notification, NotificationOptions, NotificationPermission};
+
+use yew::Callback;
+
+use crate::{
+    client::IpfsClient,
+    r#async::{cached_dag_get, channel_subscribe, get_signed_media, index_stream},
+    subscriptions,
+};
+
+const NOTIFICATION_SETTINGS: &str = "notification_settings";
+const KNOWN_ROOTS: &str = "notification_roots";
+
+/// Above this, a single summary notification is shown.
+const MAX_NOTIFICATIONS: usize = 3;
+
+/// Content checked per channel update.
+const MAX_NEW_CONTENT: usize = 20;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct NotificationSettings {
+    /// Channels opted in.
+    pub channels: HashSet<IPNSAddress>,
+
+    /// Local hour at which notifications stop.
+    pub quiet_start: Option<u8>,
+
+    /// Local hour at which notifications resume.
+    pub quiet_end: Option<u8>,
+}
+
+impl NotificationSettings {
+    /// Quiet hours may wrap around midnight, e.g. 22 to 7.
+    pub fn is_quiet(&self, hour: u8) -> bool {
+        match (self.quiet_start, self.quiet_end) {
+            (Some(start), Some(end)) if start < end => hour >= start && hour < end,
+            (Some(start), Some(end)) if start > end => hour >= start || hour < end,
+            _ => false,
+        }
+    }
+
+    pub fn is_quiet_now(&self) -> bool {
+        self.is_quiet(js_sys::Date::new_0().get_hours() as u8)
+    }
+}
+
+pub fn get_settings() -> NotificationSettings {
+    LocalStorage::get(NOTIFICATION_SETTINGS).unwrap_or_default()
+}
+
+pub fn set_settings(settings: &NotificationSettings) {
+    if let Err(e) = LocalStorage::set(NOTIFICATION_SETTINGS, settings) {
+        error!(&format!("{:?}", e));
+    }
+}
+
+/// Opt in or out of notifications for this channel. Returns true if now opted in.
+pub fn toggle_channel(addr: IPNSAddress) -> bool {
+    let mut settings = get_settings();
+
+    let enabled = settings.channels.insert(addr);
+
+    if !enabled {
+        settings.channels.remove(&addr);
+    }
+
+    set_settings(&settings);
+
+    enabled
+}
+
+/// Content index root and newest content timestamp last seen by the watcher.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+struct KnownRoot {
+    index: Cid,
+    newest: i64,
+}
+
+fn get_known_roots() -> HashMap<IPNSAddress, KnownRoot> {
+    LocalStorage::get::<Vec<(IPNSAddress, KnownRoot)>>(KNOWN_ROOTS)
+        .map(|list| list.into_iter().collect())
+        .unwrap_or_default()
+}
+
+fn set_known_root(addr: IPNSAddress, root: KnownRoot) {
+    let mut roots = get_known_roots();
+    roots.insert(addr, root);
+
+    let list: Vec<_> = roots.into_iter().collect();
+
+    if let Err(e) = LocalStorage::set(KNOWN_ROOTS, list) {
+        error!(&format!("{:?}", e));
+    }
+}
+
+pub fn is_supported() -> bool {
+    js_sys::Reflect::has(&js_sys::global(), &"Notification".into()).unwrap_or(false)
+}
+
+pub fn is_granted() -> bool {
+    is_supported() && Notification::permission() == NotificationPermission::Granted
+}
+
+/// Ask the browser for permission. Returns true if granted.
+pub async fn request_permission() -> bool {
+    if !is_supported() {
+        return false;
+    }
+
+    let promise = match Notification::request_permission() {
+        Ok(promise) => promise,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return false;
+        }
+    };
+
+    if let Err(e) = JsFuture::from(promise).await {
+        error!(&format!("{:#?}", e));
+    }
+
+    is_granted()
+}
+
+/// Keep opted in channels subscribed while the app is open.
+///
+/// Subscriptions are aborted when dropped.
+pub struct Watcher {
+    ipfs: Rc<dyn IpfsClient>,
+
+    handles: HashMap<IPNSAddress, AbortHandle>,
+}
+
+impl Watcher {
+    pub fn new(ipfs: Rc<dyn IpfsClient>) -> Self {
+        let mut watcher = Self {
+            ipfs,
+            handles: HashMap::default(),
+        };
+
+        watcher.refresh();
+
+        watcher
+    }
+
+    /// Subscribe to newly opted in channels and drop the others.
+    pub fn refresh(&mut self) {
+        let follows = subscriptions::get_sub_list();
+
+        let channels: HashSet<IPNSAddress> = get_settings()
+            .channels
+            .into_iter()
+            .filter(|addr| follows.contains(addr))
+            .collect();
+
+        self.handles.retain(|addr, handle| {
+            let keep = channels.contains(addr);
+
+            if !keep {
+                handle.abort();
+            }
+
+            keep
+        });
+
+        for addr in channels {
+            if self.handles.contains_key(&addr) {
+                continue;
+            }
+
+            let (handle, regis) = AbortHandle::new_pair();
+
+            let callback = {
+                let ipfs = self.ipfs.clone();
+
+                Callback::from(
+                    move |(addr, _, metadata): (IPNSAddress, Cid, ChannelMetadata)| {
+                        spawn_local(on_channel_update(ipfs.clone(), addr, metadata))
+                    },
+                )
+            };
+
+            spawn_local(channel_subscribe(self.ipfs.clone(), callback, addr, regis));
+
+            self.handles.insert(addr, handle);
+        }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        for handle in self.handles.values() {
+            handle.abort();
+        }
+    }
+}
+
+/// Diff the new content index against the last known root then notify.
+async fn on_channel_update(ipfs: Rc<dyn IpfsClient>, addr: IPNSAddress, metadata: ChannelMetadata) {
+    let settings = get_settings();
+
+    if !settings.channels.contains(&addr) {
+        return;
+    }
+
+    let index = match metadata.content_index {
+        Some(index) => index,
+        None => return,
+    };
+
+    let known = get_known_roots().get(&addr).copied();
+
+    if known.map(|root| root.index) == Some(index.link) {
+        return;
+    }
+
+    let defluencer = ipfs.service().map(Defluencer::from);
+
+    let stream = index_stream(&ipfs, defluencer.as_ref(), index)
+        .map_ok(|cid| {
+            let ipfs = ipfs.clone();
+
+            async move {
+                // Forged content is not notified
+                match get_signed_media(&*ipfs, cid).await {
+                    Ok((media, _)) => Ok((cid, media)),
+                    Err(e) => Err(e),
+                }
+            }
+        })
+        .try_buffered(5)
+        .take(MAX_NEW_CONTENT);
+
+    futures_util::pin_mut!(stream);
+
+    let since = known.map_or(i64::MAX, |root| root.newest);
+    let mut newest = known.map_or(i64::MIN, |root| root.newest);
+    let mut new_content = Vec::new();
+
+    while let Some(result) = stream.next().await {
+        let (cid, media) = match result {
+            Ok(tuple) => tuple,
+            Err(e) => {
+                error!(&format!("{:#?}", e));
+                break;
+            }
+        };
+
+        newest = newest.max(media.user_timestamp());
+
+        // Newest first, everything after was already known
+        if media.user_timestamp() <= since {
+            break;
+        }
+
+        new_content.push((cid, media));
+    }
+
+    set_known_root(
+        addr,
+        KnownRoot {
+            index: index.link,
+            newest,
+        },
+    );
+
+    // The first update only sets the baseline
+    if known.is_none() || new_content.is_empty() {
+        return;
+    }
+
+    if settings.is_quiet_now() || !is_granted() {
+        return;
+    }
+
+    let name = match cached_dag_get::<Identity>(&*ipfs, metadata.identity.link, None).await {
+        Ok(identity) => identity.name,
+        Err(_) => addr.to_string(),
+    };
+
+    if new_content.len() > MAX_NOTIFICATIONS {
+        let body = format!("{} new posts", new_content.len());
+
+        return notify(&name, &body, &format!("#/channel/{}", addr));
+    }
+
+    for (cid, media) in new_content {
+        let body = match &media {
+            Media::Blog(article) => article.title.clone(),
+            Media::Video(video) => video.title.clone(),
+            Media::Comment(comment) => comment.text.clone(),
+        };
+
+        notify(&name, &body, &format!("#/content/{}", cid));
+    }
+}
+
+/// Show a notification, clicking it focuses the tab and opens the route.
+fn notify(title: &str, body: &str, route: &str) {
+    let mut options = NotificationOptions::new();
+    options.body(body);
+
+    let notification = match Notification::new_with_options(title, &options) {
+        Ok(notification) => notification,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    };
+
+    let route = route.to_owned();
+
+    let closure = Closure::once_into_js(move || {
+        if let Some(window) = web_sys::window() {
+            if let Err(e) = window.focus() {
+                error!(&format!("{:#?}", e));
+            }
+
+            if let Err(e) = window.location().set_hash(&route) {
+                error!(&format!("{:#?}", e));
+            }
+        }
+    });
+
+    notification.set_onclick(Some(closure.unchecked_ref()));
+}
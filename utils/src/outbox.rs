@@ -0,0 +1,436 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use cid::Cid;
+
+use gloo_console::error;
+
+use gloo_timers::future::TimeoutFuture;
+
+use linked_data::types::{IPLDLink, IPNSAddress};
+
+use rexie::{ObjectStore, Rexie, TransactionMode};
+
+use serde::{Deserialize, Serialize};
+
+use wasm_bindgen::JsValue;
+
+use crate::{
+    cache::{from_js, to_js},
+    defluencer::ChannelContext,
+    errors::AppError,
+    follow_sync, metadata,
+};
+
+const DB_NAME: &str = "defluencer_outbox";
+const STORE_NAME: &str = "operations";
+
+/// Milliseconds before the first retry, doubled after each failure.
+const BASE_BACKOFF: f64 = 10_000.0;
+
+/// Maximum milliseconds between retries.
+const MAX_BACKOFF: f64 = 60.0 * 60.0 * 1000.0;
+
+/// Milliseconds between checks of a held lock.
+const LOCK_POLL: u32 = 100;
+
+thread_local! {
+    static DATABASE: RefCell<Option<Rc<Rexie>>> = RefCell::new(None);
+    static REPLAYING: Cell<bool> = Cell::new(false);
+}
+
+/// A channel mutation waiting to be published.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Operation {
+    AddContent(IPLDLink),
+    RemoveContent(IPLDLink),
+    AddComment(IPLDLink),
+
+    /// Comments aggregated since the last update.
+    AddComments(Vec<IPLDLink>),
+
+    Follow(IPNSAddress),
+    Unfollow(IPNSAddress),
+
+    /// Many followees changed in a single channel update.
+    UpdateFollows {
+        follow: Vec<IPNSAddress>,
+        unfollow: Vec<IPNSAddress>,
+    },
+
+    /// Link the root from this field of the channel metadata.
+    SetLink(String, IPLDLink),
+}
+
+impl Operation {
+    /// Human-readable description.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::AddContent(ipld) => format!("Add content {}", ipld.link),
+            Self::RemoveContent(ipld) => format!("Remove content {}", ipld.link),
+            Self::AddComment(ipld) => format!("Add comment {}", ipld.link),
+            Self::AddComments(iplds) => format!("Add {} comments", iplds.len()),
+            Self::Follow(addr) => format!("Follow {}", addr),
+            Self::Unfollow(addr) => format!("Unfollow {}", addr),
+            Self::UpdateFollows { follow, unfollow } => format!(
+                "Follow {} and unfollow {} channels",
+                follow.len(),
+                unfollow.len()
+            ),
+            Self::SetLink(key, ipld) => {
+                format!("Link {} from the channel metadata field {}", ipld.link, key)
+            }
+        }
+    }
+}
+
+/// A queued operation saved in IndexedDB.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Pending {
+    pub id: String,
+
+    /// Channel to update.
+    pub channel: IPNSAddress,
+
+    pub operation: Operation,
+
+    pub attempts: u32,
+
+    /// Unix time in milliseconds
+    pub created: f64,
+
+    /// Unix time in milliseconds
+    pub next_attempt: f64,
+
+    pub last_error: Option<String>,
+
+    /// Links of the fields added by this app, read before the first attempt.
+    #[serde(default)]
+    pub links: Vec<(String, Cid)>,
+}
+
+async fn database() -> Option<Rc<Rexie>> {
+    if let Some(rexie) = DATABASE.with(|cell| cell.borrow().clone()) {
+        return Some(rexie);
+    }
+
+    let result = Rexie::builder(DB_NAME)
+        .version(1)
+        .add_object_store(ObjectStore::new(STORE_NAME).key_path("id"))
+        .build()
+        .await;
+
+    let rexie = match result {
+        Ok(rexie) => Rc::new(rexie),
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return None;
+        }
+    };
+
+    DATABASE.with(|cell| cell.replace(Some(rexie.clone())));
+
+    Some(rexie)
+}
+
+/// Return every queued operation, oldest first.
+pub async fn list() -> Vec<Pending> {
+    let rexie = match database().await {
+        Some(rexie) => rexie,
+        None => return Vec::new(),
+    };
+
+    let transaction = match rexie.transaction(&[STORE_NAME], TransactionMode::ReadOnly) {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return Vec::new();
+        }
+    };
+
+    let store = match transaction.store(STORE_NAME) {
+        Ok(store) => store,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return Vec::new();
+        }
+    };
+
+    let entries = match store.get_all(None, None, None, None).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return Vec::new();
+        }
+    };
+
+    let mut pendings: Vec<Pending> = entries
+        .into_iter()
+        .filter_map(|(_, value)| from_js(&value))
+        .collect();
+
+    pendings.sort_unstable_by(|a, b| a.created.total_cmp(&b.created));
+
+    pendings
+}
+
+async fn save(pending: &Pending) {
+    let value = match to_js(pending) {
+        Some(value) => value,
+        None => return,
+    };
+
+    let rexie = match database().await {
+        Some(rexie) => rexie,
+        None => return,
+    };
+
+    let transaction = match rexie.transaction(&[STORE_NAME], TransactionMode::ReadWrite) {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    };
+
+    let store = match transaction.store(STORE_NAME) {
+        Ok(store) => store,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    };
+
+    if let Err(e) = store.put(&value, None).await {
+        error!(&format!("{:#?}", e));
+        return;
+    }
+
+    if let Err(e) = transaction.done().await {
+        error!(&format!("{:#?}", e));
+    }
+}
+
+/// Drop a queued operation.
+pub async fn remove(id: &str) {
+    let rexie = match database().await {
+        Some(rexie) => rexie,
+        None => return,
+    };
+
+    let transaction = match rexie.transaction(&[STORE_NAME], TransactionMode::ReadWrite) {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    };
+
+    let store = match transaction.store(STORE_NAME) {
+        Ok(store) => store,
+        Err(e) => {
+            error!(&format!("{:#?}", e));
+            return;
+        }
+    };
+
+    if let Err(e) = store.delete(&JsValue::from(id)).await {
+        error!(&format!("{:#?}", e));
+        return;
+    }
+
+    if let Err(e) = transaction.done().await {
+        error!(&format!("{:#?}", e));
+    }
+}
+
+/// Apply the operation to the channel.
+///
+/// Return the new channel root or none if there was nothing to do.
+async fn apply(
+    context: &ChannelContext,
+    operation: &Operation,
+    links: &mut Vec<(String, Cid)>,
+) -> Result<Option<Cid>, AppError> {
+    let channel = &context.channel;
+
+    // Defluencer channel updates drop the fields outside its schema,
+    // links are kept across attempts since a failed update may have been published.
+    match operation {
+        Operation::UpdateFollows { .. } | Operation::SetLink(..) => {}
+        _ => metadata::read_links(context, links).await?,
+    }
+
+    let result = match operation {
+        Operation::AddContent(ipld) => channel.add_content(ipld.link).await.map(Some),
+        Operation::RemoveContent(ipld) => channel.remove_content(ipld.link).await,
+        Operation::AddComment(ipld) => channel.add_comment(ipld.link).await.map(Some),
+        Operation::AddComments(iplds) => {
+            let mut root = Ok(None);
+
+            // Adding a comment twice changes nothing, a retry can start over
+            for ipld in iplds {
+                root = channel.add_comment(ipld.link).await.map(Some);
+
+                if root.is_err() {
+                    break;
+                }
+            }
+
+            root
+        }
+        Operation::Follow(addr) => channel.follow(*addr).await.map(Some),
+        Operation::Unfollow(addr) => channel.unfollow(*addr).await.map(Some),
+        Operation::UpdateFollows { follow, unfollow } => {
+            return follow_sync::update_follows(context, follow, unfollow).await
+        }
+        Operation::SetLink(key, ipld) => {
+            return metadata::publish_link(context, key, *ipld).await.map(Some)
+        }
+    };
+
+    match result.map_err(AppError::ipns)? {
+        Some(root) if !links.is_empty() => {
+            metadata::link_fields(context, root, links).await.map(Some)
+        }
+        root => Ok(root),
+    }
+}
+
+fn backoff(attempts: u32) -> f64 {
+    (BASE_BACKOFF * 2f64.powi(attempts as i32)).min(MAX_BACKOFF)
+}
+
+/// Held while operations are applied, so that each one is applied once and in order.
+struct ReplayLock;
+
+impl ReplayLock {
+    fn try_acquire() -> Option<Self> {
+        if REPLAYING.with(|cell| cell.replace(true)) {
+            return None;
+        }
+
+        Some(Self)
+    }
+
+    async fn acquire() -> Self {
+        loop {
+            if let Some(lock) = Self::try_acquire() {
+                return lock;
+            }
+
+            TimeoutFuture::new(LOCK_POLL).await;
+        }
+    }
+}
+
+impl Drop for ReplayLock {
+    fn drop(&mut self) {
+        REPLAYING.with(|cell| cell.set(false));
+    }
+}
+
+/// Queued operations of this channel, oldest first.
+async fn channel_list(addr: IPNSAddress) -> Vec<Pending> {
+    list()
+        .await
+        .into_iter()
+        .filter(|pending| pending.channel == addr)
+        .collect()
+}
+
+/// Queue then apply the operation, after the older operations of the channel.
+///
+/// The operation stays queued if it or an older one fails and will be replayed later.
+pub async fn submit(
+    context: &ChannelContext,
+    operation: Operation,
+) -> Result<Option<Cid>, AppError> {
+    // Acquired before saving so that a replay cannot apply the operation too
+    let _lock = ReplayLock::acquire().await;
+
+    let addr = context.channel.get_address();
+    let older = channel_list(addr).await;
+
+    let now = js_sys::Date::now();
+
+    let mut pending = Pending {
+        id: format!(
+            "{:x}-{:x}",
+            now as u64,
+            (js_sys::Math::random() * u32::MAX as f64) as u32
+        ),
+        channel: addr,
+        operation,
+        attempts: 0,
+        created: now,
+        next_attempt: now,
+        last_error: None,
+        links: Vec::new(),
+    };
+
+    // Saved first so that nothing is lost if the tab is closed
+    save(&pending).await;
+
+    for mut older in older {
+        if older.next_attempt > now {
+            return Err(AppError::Queued(older.operation.describe()));
+        }
+
+        attempt(context, &mut older).await?;
+    }
+
+    attempt(context, &mut pending).await
+}
+
+async fn attempt(context: &ChannelContext, pending: &mut Pending) -> Result<Option<Cid>, AppError> {
+    match apply(context, &pending.operation, &mut pending.links).await {
+        Ok(root) => {
+            remove(&pending.id).await;
+
+            Ok(root)
+        }
+        Err(e) => {
+            pending.attempts += 1;
+            pending.next_attempt = js_sys::Date::now() + backoff(pending.attempts);
+            pending.last_error = Some(e.details().to_owned());
+
+            save(pending).await;
+
+            Err(e)
+        }
+    }
+}
+
+/// Replay the queued operations of this channel, oldest first.
+///
+/// Stop at the first operation not yet due or failing, the following ones must wait for it.
+/// Return the number of operations still queued or none if a replay is already running.
+pub async fn replay(context: &ChannelContext, ignore_backoff: bool) -> Option<usize> {
+    let _lock = ReplayLock::try_acquire()?;
+
+    let mut pendings = channel_list(context.channel.get_address()).await;
+
+    let now = js_sys::Date::now();
+
+    let mut remaining = pendings.len();
+
+    for pending in pendings.iter_mut() {
+        if !ignore_backoff && pending.next_attempt > now {
+            break;
+        }
+
+        if let Err(e) = attempt(context, pending).await {
+            error!(&format!("{:#?}", e));
+            break;
+        }
+
+        remaining -= 1;
+    }
+
+    Some(remaining)
+}
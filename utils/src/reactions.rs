@@ -0,0 +1,270 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    rc::Rc,
+};
+
+use cid::Cid;
+
+use defluencer::crypto::{
+    signed_link::SignedLink,
+    signers::{MetamaskSigner, Signer},
+};
+
+use futures_util::stream::{FuturesUnordered, StreamExt};
+
+use gloo_console::error;
+
+use linked_data::{
+    identity::Identity,
+    types::{IPLDLink, IPNSAddress},
+};
+
+use serde::{Deserialize, Serialize};
+
+use yew::Callback;
+
+use crate::{
+    client::{as_link, ClientError, IpfsClient},
+    defluencer::ChannelContext,
+    errors::AppError,
+    metadata,
+    r#async::{cached_dag_get, is_signer},
+};
+
+/// Field of the channel metadata linking to the reaction index.
+pub const METADATA_KEY: &str = "reactions";
+
+/// Emojis offered when reacting.
+pub const EMOJIS: [&str; 6] = ["👍", "❤️", "😂", "😮", "😢", "🎉"];
+
+/// Lightweight response to content, signed like comments.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Reaction {
+    /// Link to the reacting identity
+    pub identity: IPLDLink,
+
+    /// Unix time in seconds
+    pub user_timestamp: i64,
+
+    /// Media reacted to, linked through its signed link like comment origins
+    pub origin: IPLDLink,
+
+    pub emoji: String,
+}
+
+/// Reactions of a channel, by content then emoji.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ReactionIndex {
+    /// Content CID to emoji to signed reaction link
+    #[serde(default)]
+    pub reactions: BTreeMap<String, BTreeMap<String, IPLDLink>>,
+}
+
+impl ReactionIndex {
+    pub fn get(&self, content: &Cid) -> Option<&BTreeMap<String, IPLDLink>> {
+        self.reactions.get(&content.to_string())
+    }
+
+    /// Drop the emojis not offered in [`EMOJIS`], indexes of other channels are not trusted.
+    fn retain_offered(&mut self) {
+        for emojis in self.reactions.values_mut() {
+            emojis.retain(|emoji, _| EMOJIS.contains(&emoji.as_str()));
+        }
+
+        self.reactions.retain(|_, emojis| !emojis.is_empty());
+    }
+}
+
+/// Reaction indexes of followed channels, shared by every page.
+#[derive(Clone, Default, PartialEq)]
+pub struct ReactionContext {
+    /// Channel address to identity and reaction index
+    channels: Rc<HashMap<IPNSAddress, (Cid, ReactionIndex)>>,
+
+    /// Update the index of a channel, after reacting for example.
+    pub callback: Callback<(IPNSAddress, Cid, ReactionIndex)>,
+}
+
+impl ReactionContext {
+    pub fn new(callback: Callback<(IPNSAddress, Cid, ReactionIndex)>) -> Self {
+        Self {
+            channels: Rc::default(),
+            callback,
+        }
+    }
+
+    pub fn insert(&mut self, addr: IPNSAddress, identity: Cid, index: ReactionIndex) {
+        Rc::make_mut(&mut self.channels).insert(addr, (identity, index));
+    }
+
+    /// Number of channels per emoji.
+    pub fn counts(&self, content: &Cid) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+
+        for (_, index) in self.channels.values() {
+            for emoji in index
+                .get(content)
+                .into_iter()
+                .flat_map(|emojis| emojis.keys())
+            {
+                *counts.entry(emoji.clone()).or_default() += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// Identity, emoji and signed reaction of each channel that reacted.
+    pub fn reactions(&self, content: &Cid) -> Vec<(Cid, String, Cid)> {
+        self.channels
+            .values()
+            .filter_map(|(identity, index)| Some((identity, index.get(content)?)))
+            .flat_map(|(identity, emojis)| {
+                emojis
+                    .iter()
+                    .map(|(emoji, signed)| (*identity, emoji.clone(), signed.link))
+            })
+            .collect()
+    }
+}
+
+/// Fetch the reaction index of each channel.
+///
+/// Channels without reactions are skipped.
+pub async fn crawl(
+    ipfs: Rc<dyn IpfsClient>,
+    addresses: HashSet<IPNSAddress>,
+    callback: Callback<(IPNSAddress, Cid, ReactionIndex)>,
+) {
+    let mut stream: FuturesUnordered<_> = addresses
+        .into_iter()
+        .map(|addr| {
+            let ipfs = ipfs.clone();
+
+            async move { get_index(&ipfs, addr).await }
+        })
+        .collect();
+
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok(Some(tuple)) => callback.emit(tuple),
+            Ok(None) => {}
+            Err(e) => error!(&format!("{:#?}", e)),
+        }
+    }
+}
+
+async fn get_index(
+    ipfs: &Rc<dyn IpfsClient>,
+    addr: IPNSAddress,
+) -> Result<Option<(IPNSAddress, Cid, ReactionIndex)>, AppError> {
+    let cid = ipfs
+        .name_resolve(addr.into())
+        .await
+        .map_err(AppError::ipns)?;
+
+    let metadata = ipfs.dag_get_value(cid, None).await?;
+
+    let identity = match metadata.get("identity").and_then(as_link) {
+        Some(identity) => identity,
+        None => return Ok(None),
+    };
+
+    let root = match metadata.get(METADATA_KEY).and_then(as_link) {
+        Some(root) => root,
+        None => return Ok(None),
+    };
+
+    let mut index = cached_dag_get::<ReactionIndex>(&**ipfs, root, None).await?;
+    index.retain_offered();
+
+    Ok(Some((addr, identity, index)))
+}
+
+/// Sign a new reaction or remove an existing one, then publish the channel reaction index.
+///
+/// The content is the CID of its signed link.
+/// Returns the published index.
+pub async fn toggle(
+    ipfs: &Rc<dyn IpfsClient>,
+    channel: &ChannelContext,
+    signer: &MetamaskSigner,
+    identity: Cid,
+    content: Cid,
+    emoji: String,
+) -> Result<ReactionIndex, AppError> {
+    if !EMOJIS.contains(&emoji.as_str()) {
+        return Err(AppError::Decode(emoji));
+    }
+
+    // Held until the new root is stored, so that concurrent toggles are not lost
+    let _lock = metadata::WriteLock::acquire().await;
+
+    // Queued links are more recent than the metadata while publishing
+    let root = metadata::get_latest(ipfs, channel, METADATA_KEY).await?;
+
+    let mut index = match root {
+        Some(root) => cached_dag_get::<ReactionIndex>(&**ipfs, root, None).await?,
+        None => ReactionIndex::default(),
+    };
+
+    index.retain_offered();
+
+    let key = content.to_string();
+    let emojis = index.reactions.entry(key.clone()).or_default();
+
+    if emojis.remove(&emoji).is_none() {
+        let reaction = Reaction {
+            identity: identity.into(),
+            user_timestamp: (js_sys::Date::now() / 1000.0) as i64,
+            origin: content.into(),
+            emoji: emoji.clone(),
+        };
+
+        let cid = ipfs.dag_put(&reaction).await?;
+
+        let signed_link = signer.sign(cid).await.map_err(AppError::signing)?;
+
+        let signed = ipfs.dag_put(&signed_link).await?;
+
+        emojis.insert(emoji, signed.into());
+    } else if emojis.is_empty() {
+        index.reactions.remove(&key);
+    }
+
+    let root = ipfs.dag_put(&index).await?;
+
+    metadata::set(channel, METADATA_KEY, root).await?;
+
+    Ok(index)
+}
+
+/// Returns true if the reaction matches the index entry and is signed by its identity wallet.
+pub async fn verify(
+    ipfs: &dyn IpfsClient,
+    signed: Cid,
+    identity: Cid,
+    content: Cid,
+    emoji: &str,
+) -> Result<bool, ClientError> {
+    let signed_link = cached_dag_get::<SignedLink>(ipfs, signed, None).await?;
+
+    if !signed_link.verify() {
+        return Ok(false);
+    }
+
+    let reaction = cached_dag_get::<Reaction>(ipfs, signed_link.link.link, None).await?;
+
+    if reaction.identity.link != identity
+        || reaction.origin.link != content
+        || reaction.emoji != emoji
+    {
+        return Ok(false);
+    }
+
+    let identity = cached_dag_get::<Identity>(ipfs, identity, None).await?;
+
+    Ok(is_signer(&identity, &signed_link))
+}
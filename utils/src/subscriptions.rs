@@ -1,28 +1,199 @@
 #![cfg(target_arch = "wasm32")]
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use gloo_storage::{LocalStorage, Storage};
+use gloo_storage::{errors::StorageError, LocalStorage, Storage};
 
 use gloo_console::error;
 
 use linked_data::types::IPNSAddress;
 
+use serde::{Deserialize, Serialize};
+
+/// Single follow set used before named lists.
 const FOLLOW_LIST: &str = "follow_list";
+const SUBSCRIPTION_LISTS: &str = "subscription_lists";
+const LAST_SEEN: &str = "last_seen";
+const UNREAD_COUNTS: &str = "unread_counts";
+
+/// List new followees are added to.
+pub const DEFAULT_LIST: &str = "Following";
+
+/// Unread content is not counted past this.
+pub const MAX_UNREAD: usize = 99;
 
-/// Get addresses of all followees
+/// Milliseconds before unread counts are recomputed.
+const UNREAD_REFRESH: f64 = 60_000.0;
+
+/// Get addresses of all followees, in any list.
 pub fn get_sub_list() -> HashSet<IPNSAddress> {
-    match LocalStorage::get(FOLLOW_LIST) {
-        Ok(list) => return list,
+    get_lists().into_values().flatten().collect()
+}
+
+/// Set new list of followees.
+///
+/// New followees are added to the default list and removed followees are removed from every list.
+pub fn set_sub_list(list: HashSet<IPNSAddress>) {
+    let mut lists = get_lists();
+
+    let current: HashSet<IPNSAddress> = lists.values().flatten().copied().collect();
+
+    for set in lists.values_mut() {
+        set.retain(|addr| list.contains(addr));
+    }
+
+    lists
+        .entry(DEFAULT_LIST.to_owned())
+        .or_default()
+        .extend(list.difference(&current).copied());
+
+    set_lists(&lists);
+}
+
+/// Get all named lists of followees.
+pub fn get_lists() -> BTreeMap<String, HashSet<IPNSAddress>> {
+    match LocalStorage::get(SUBSCRIPTION_LISTS) {
+        Ok(lists) => return lists,
+        Err(StorageError::KeyNotFound(_)) => {}
+        Err(e) => {
+            error!(&format!("{:?}", e));
+            return BTreeMap::default();
+        }
+    }
+
+    // Migrate the single follow set to the default list
+    let mut lists = BTreeMap::default();
+
+    match LocalStorage::get::<HashSet<IPNSAddress>>(FOLLOW_LIST) {
+        Ok(list) => {
+            lists.insert(DEFAULT_LIST.to_owned(), list);
+
+            set_lists(&lists);
+
+            LocalStorage::delete(FOLLOW_LIST);
+        }
+        Err(StorageError::KeyNotFound(_)) => {}
         Err(e) => error!(&format!("{:?}", e)),
     }
 
-    HashSet::default()
+    lists
 }
 
-/// Set new list of followees
-pub fn set_sub_list(list: HashSet<IPNSAddress>) {
-    if let Err(e) = LocalStorage::set(FOLLOW_LIST, list) {
+/// Set all named lists of followees.
+pub fn set_lists(lists: &BTreeMap<String, HashSet<IPNSAddress>>) {
+    if let Err(e) = LocalStorage::set(SUBSCRIPTION_LISTS, lists) {
+        error!(&format!("{:?}", e));
+    }
+}
+
+/// Get addresses of the followees in this list.
+pub fn get_list(name: &str) -> HashSet<IPNSAddress> {
+    get_lists().remove(name).unwrap_or_default()
+}
+
+/// Add or remove a followee from a list, creating the list if needed.
+pub fn toggle_in_list(name: &str, addr: IPNSAddress) {
+    let mut lists = get_lists();
+
+    let list = lists.entry(name.to_owned()).or_default();
+
+    if !list.insert(addr) {
+        list.remove(&addr);
+    }
+
+    set_lists(&lists);
+}
+
+/// Create an empty list. Returns false if it already exists.
+pub fn create_list(name: &str) -> bool {
+    let mut lists = get_lists();
+
+    if lists.contains_key(name) {
+        return false;
+    }
+
+    lists.insert(name.to_owned(), HashSet::default());
+
+    set_lists(&lists);
+
+    true
+}
+
+/// Delete a list, followees in other lists are kept.
+pub fn delete_list(name: &str) {
+    let mut lists = get_lists();
+
+    if lists.remove(name).is_some() {
+        set_lists(&lists);
+    }
+}
+
+/// Get the timestamp of the newest content seen, per followee.
+pub fn get_last_seen() -> HashMap<IPNSAddress, i64> {
+    LocalStorage::get::<Vec<(IPNSAddress, i64)>>(LAST_SEEN)
+        .map(|list| list.into_iter().collect())
+        .unwrap_or_default()
+}
+
+/// Save the timestamps, keeping the newest ones.
+pub fn update_last_seen(seen: &HashMap<IPNSAddress, i64>) -> HashMap<IPNSAddress, i64> {
+    let mut last_seen = get_last_seen();
+
+    for (addr, timestamp) in seen {
+        let entry = last_seen.entry(*addr).or_insert(*timestamp);
+        *entry = (*entry).max(*timestamp);
+    }
+
+    let list: Vec<_> = last_seen.iter().collect();
+
+    if let Err(e) = LocalStorage::set(LAST_SEEN, list) {
+        error!(&format!("{:?}", e));
+    }
+
+    last_seen
+}
+
+/// Content published since last seen by a followee.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Unread {
+    pub addr: IPNSAddress,
+    pub name: String,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct UnreadCounts {
+    /// Unix time in milliseconds
+    pub updated: f64,
+
+    pub channels: Vec<Unread>,
+}
+
+impl UnreadCounts {
+    pub fn total(&self) -> usize {
+        self.channels.iter().map(|unread| unread.count).sum()
+    }
+
+    pub fn is_stale(&self) -> bool {
+        js_sys::Date::now() - self.updated > UNREAD_REFRESH
+    }
+}
+
+/// Get the last computed unread counts.
+pub fn get_unread_counts() -> UnreadCounts {
+    LocalStorage::get(UNREAD_COUNTS).unwrap_or_default()
+}
+
+pub fn set_unread_counts(counts: &UnreadCounts) {
+    if let Err(e) = LocalStorage::set(UNREAD_COUNTS, counts) {
         error!(&format!("{:?}", e));
     }
 }
+
+/// Everything was seen, nothing to count until new content is published.
+pub fn clear_unread_counts() {
+    set_unread_counts(&UnreadCounts {
+        updated: js_sys::Date::now(),
+        channels: Vec::new(),
+    });
+}
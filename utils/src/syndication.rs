@@ -0,0 +1,384 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{collections::HashMap, rc::Rc};
+
+use chrono::{TimeZone, Utc};
+
+use cid::Cid;
+
+use defluencer::Defluencer;
+
+use futures_util::stream::TryStreamExt;
+
+use gloo_console::error;
+
+use linked_data::{identity::Identity, media::Media, types::IPLDLink};
+
+use serde_json::{json, Value};
+
+use crate::{
+    client::{ClientError, IpfsClient},
+    r#async::{cached_dag_get, index_stream},
+};
+
+/// Maximum number of entries in a feed document.
+pub const MAX_ENTRIES: usize = 50;
+
+/// Characters of a micro post used as title.
+const TITLE_LENGTH: usize = 80;
+
+/// Author name when the identity cannot be fetched.
+const UNKNOWN_AUTHOR: &str = "Unknown author";
+
+/// A channel, as seen by feed readers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeedInfo {
+    /// Channel name.
+    pub title: String,
+
+    /// App url, e.g. https://social.defluencer.eth/
+    pub base_url: String,
+
+    /// Gateway used for images.
+    pub gateway: String,
+
+    /// Link to this channel page.
+    pub channel_path: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeedEntry {
+    pub cid: Cid,
+    pub media: Media,
+
+    /// Name of the content signer.
+    pub author: String,
+}
+
+impl FeedEntry {
+    fn title(&self) -> String {
+        match &self.media {
+            Media::Blog(article) => article.title.clone(),
+            Media::Video(video) => video.title.clone(),
+            Media::Comment(comment) => {
+                let mut title: String = comment.text.chars().take(TITLE_LENGTH).collect();
+
+                if title.len() < comment.text.len() {
+                    title.push('…');
+                }
+
+                title
+            }
+        }
+    }
+
+    fn text(&self) -> Option<&str> {
+        match &self.media {
+            Media::Comment(comment) => Some(&comment.text),
+            _ => None,
+        }
+    }
+
+    fn image(&self) -> Option<Cid> {
+        match &self.media {
+            Media::Blog(article) => article.image.map(|ipld| ipld.link),
+            Media::Video(video) => video.image.map(|ipld| ipld.link),
+            Media::Comment(_) => None,
+        }
+    }
+}
+
+/// Collect the newest micro posts, articles and videos of a content index.
+///
+/// Comments on other content and media that cannot be fetched are skipped.
+pub async fn collect_entries(
+    ipfs: Rc<dyn IpfsClient>,
+    index: IPLDLink,
+) -> Result<Vec<FeedEntry>, ClientError> {
+    let defluencer = ipfs.service().map(Defluencer::from);
+
+    let stream = index_stream(&ipfs, defluencer.as_ref(), index);
+
+    futures_util::pin_mut!(stream);
+
+    let mut authors: HashMap<Cid, String> = HashMap::new();
+    let mut entries = Vec::with_capacity(MAX_ENTRIES);
+
+    while let Some(cid) = stream.try_next().await? {
+        let media = match cached_dag_get::<Media>(&*ipfs, cid, Some("/link")).await {
+            Ok(media) => media,
+            Err(e) => {
+                error!(&format!("{:#?}", e));
+                continue;
+            }
+        };
+
+        if let Media::Comment(comment) = &media {
+            if comment.origin.is_some() {
+                continue;
+            }
+        }
+
+        let identity = media.identity().link;
+
+        let author = match authors.get(&identity) {
+            Some(name) => name.clone(),
+            None => {
+                let name = match cached_dag_get::<Identity>(&*ipfs, identity, None).await {
+                    Ok(identity) => identity.name,
+                    Err(e) => {
+                        error!(&format!("{:#?}", e));
+                        UNKNOWN_AUTHOR.to_owned()
+                    }
+                };
+
+                authors.insert(identity, name.clone());
+
+                name
+            }
+        };
+
+        entries.push(FeedEntry { cid, media, author });
+
+        if entries.len() >= MAX_ENTRIES {
+            break;
+        }
+    }
+
+    entries.sort_unstable_by_key(|entry| std::cmp::Reverse(entry.media.user_timestamp()));
+
+    Ok(entries)
+}
+
+/// Unix time in total number of seconds to RFC 3339.
+fn rfc3339(seconds: i64) -> String {
+    match Utc.timestamp_opt(seconds, 0) {
+        chrono::LocalResult::Single(time) => time.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        _ => String::from("1970-01-01T00:00:00Z"),
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(char),
+        }
+    }
+
+    escaped
+}
+
+fn content_url(info: &FeedInfo, cid: Cid) -> String {
+    format!("{}#/content/{}", info.base_url, cid)
+}
+
+fn image_url(info: &FeedInfo, cid: Cid) -> String {
+    format!("{}/ipfs/{}", info.gateway, cid)
+}
+
+/// Serialize entries as an Atom document.
+pub fn to_atom(info: &FeedInfo, entries: &[FeedEntry]) -> String {
+    let updated = entries
+        .iter()
+        .map(|entry| entry.media.user_timestamp())
+        .max()
+        .unwrap_or_default();
+
+    let channel_url = format!("{}{}", info.base_url, info.channel_path);
+
+    let mut xml = String::new();
+
+    xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(&format!("  <title>{}</title>\n", xml_escape(&info.title)));
+    xml.push_str(&format!("  <id>{}</id>\n", xml_escape(&channel_url)));
+    xml.push_str(&format!(
+        "  <link rel=\"alternate\" href=\"{}\"/>\n",
+        xml_escape(&channel_url)
+    ));
+    xml.push_str(&format!("  <updated>{}</updated>\n", rfc3339(updated)));
+    xml.push_str(&format!(
+        "  <author><name>{}</name></author>\n",
+        xml_escape(&info.title)
+    ));
+
+    for entry in entries {
+        let url = xml_escape(&content_url(info, entry.cid));
+        let date = rfc3339(entry.media.user_timestamp());
+
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!("    <id>ipfs://{}</id>\n", entry.cid));
+        xml.push_str(&format!(
+            "    <title>{}</title>\n",
+            xml_escape(&entry.title())
+        ));
+        xml.push_str(&format!("    <link rel=\"alternate\" href=\"{}\"/>\n", url));
+        xml.push_str(&format!("    <published>{}</published>\n", date));
+        xml.push_str(&format!("    <updated>{}</updated>\n", date));
+        xml.push_str(&format!(
+            "    <author><name>{}</name></author>\n",
+            xml_escape(&entry.author)
+        ));
+
+        if let Some(text) = entry.text() {
+            xml.push_str(&format!(
+                "    <content type=\"text\">{}</content>\n",
+                xml_escape(text)
+            ));
+        }
+
+        if let Some(image) = entry.image() {
+            xml.push_str(&format!(
+                "    <link rel=\"enclosure\" href=\"{}\"/>\n",
+                xml_escape(&image_url(info, image))
+            ));
+        }
+
+        xml.push_str("  </entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+
+    xml
+}
+
+/// Serialize entries as a JSON Feed 1.1 document.
+pub fn to_json_feed(info: &FeedInfo, entries: &[FeedEntry]) -> String {
+    let items: Vec<Value> = entries
+        .iter()
+        .map(|entry| {
+            let mut item = json!({
+                "id": format!("ipfs://{}", entry.cid),
+                "url": content_url(info, entry.cid),
+                "title": entry.title(),
+                "date_published": rfc3339(entry.media.user_timestamp()),
+                "authors": [{ "name": entry.author }],
+            });
+
+            // Items must have content
+            let text = entry
+                .text()
+                .map(str::to_owned)
+                .unwrap_or_else(|| entry.title());
+            item["content_text"] = Value::from(text);
+
+            if let Some(image) = entry.image() {
+                item["image"] = Value::from(image_url(info, image));
+            }
+
+            item
+        })
+        .collect();
+
+    let feed = json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": info.title,
+        "home_page_url": format!("{}{}", info.base_url, info.channel_path),
+        "authors": [{ "name": info.title }],
+        "items": items,
+    });
+
+    serde_json::to_string_pretty(&feed).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use linked_data::media::comments::Comment;
+
+    use multihash::{Code, MultihashDigest};
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn cid(seed: &str) -> Cid {
+        Cid::new_v1(0x55, Code::Sha2_256.digest(seed.as_bytes()))
+    }
+
+    fn info() -> FeedInfo {
+        FeedInfo {
+            title: "Tom & Jerry".to_owned(),
+            base_url: "https://social.defluencer.eth/".to_owned(),
+            gateway: "https://ipfs.io".to_owned(),
+            channel_path: "#/channel/addr".to_owned(),
+        }
+    }
+
+    fn entry(text: &str, user_timestamp: i64) -> FeedEntry {
+        let comment = Comment {
+            identity: cid("identity").into(),
+            user_timestamp,
+            origin: None,
+            text: text.to_owned(),
+        };
+
+        FeedEntry {
+            cid: cid(text),
+            media: Media::Comment(comment),
+            author: "<Jerry>".to_owned(),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn escape_markup() {
+        assert_eq!(
+            xml_escape(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &apos;Jerry&apos;&lt;/a&gt;"
+        );
+        assert_eq!(xml_escape("plain text"), "plain text");
+    }
+
+    #[wasm_bindgen_test]
+    fn atom_document() {
+        let entries = [entry("Hello <world>", 1_600_000_000)];
+
+        let atom = to_atom(&info(), &entries);
+
+        assert!(atom.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>"));
+        assert!(atom.contains("<title>Tom &amp; Jerry</title>"));
+        assert!(atom.contains("<updated>2020-09-13T12:26:40Z</updated>"));
+        assert!(atom.contains(&format!("<id>ipfs://{}</id>", entries[0].cid)));
+        assert!(atom.contains("<author><name>&lt;Jerry&gt;</name></author>"));
+        assert!(atom.contains("<content type=\"text\">Hello &lt;world&gt;</content>"));
+        assert!(atom.trim_end().ends_with("</feed>"));
+    }
+
+    #[wasm_bindgen_test]
+    fn json_feed_document() {
+        let long = "a".repeat(TITLE_LENGTH + 10);
+        let entries = [entry("Hello", 1_600_000_000), entry(&long, 1_500_000_000)];
+
+        let json = to_json_feed(&info(), &entries);
+        let feed: Value = serde_json::from_str(&json).expect("Valid JSON");
+
+        assert_eq!(feed["version"], "https://jsonfeed.org/version/1.1");
+        assert_eq!(feed["title"], "Tom & Jerry");
+        assert_eq!(
+            feed["home_page_url"],
+            "https://social.defluencer.eth/#/channel/addr"
+        );
+
+        let items = feed["items"].as_array().expect("Items");
+        assert_eq!(items.len(), 2);
+
+        assert_eq!(items[0]["id"], format!("ipfs://{}", entries[0].cid));
+        assert_eq!(
+            items[0]["url"],
+            format!("https://social.defluencer.eth/#/content/{}", entries[0].cid)
+        );
+        assert_eq!(items[0]["content_text"], "Hello");
+        assert_eq!(items[0]["date_published"], "2020-09-13T12:26:40Z");
+        assert_eq!(items[0]["authors"][0]["name"], "<Jerry>");
+
+        let title = items[1]["title"].as_str().expect("Title");
+        assert_eq!(title.chars().count(), TITLE_LENGTH + 1);
+        assert!(title.ends_with('…'));
+    }
+}